@@ -1,9 +1,14 @@
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use proc_macro2::{Delimiter, Group, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse_macro_input, punctuated::Punctuated, spanned::Spanned, Error, FnArg, GenericParam, Ident,
-    ItemFn, Pat, Path, Token, TypeParamBound,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    Error, FnArg, GenericParam, Ident, ImplItem, ItemFn, ItemImpl, Pat, Path, ReturnType,
+    Signature, Token, Type, TypeParamBound,
 };
 
 struct DispatchOpts<'a> {
@@ -11,7 +16,7 @@ struct DispatchOpts<'a> {
 }
 
 impl<'a> DispatchOpts<'a> {
-    fn from_args(args: &Punctuated<Path, Token![,]>) -> Result<DispatchOpts, Error> {
+    fn from_args(args: &Punctuated<Path, Token![,]>) -> Result<DispatchOpts<'_>, Error> {
         if args.empty_or_trailing() {
             return Err(Error::new(
                 args.span(),
@@ -30,19 +35,42 @@ impl<'a> DispatchOpts<'a> {
 
 struct FnInfo<'a> {
     func: &'a ItemFn,
+    imp: Option<&'a ItemImpl>,
+    sig: Signature,
+    inner_sig: Signature,
     arch_ident: &'a Ident,
     generic_idents: Vec<&'a Ident>,
-    generic_params_no_arch: Vec<&'a GenericParam>,
     generic_idents_no_arch: Vec<&'a Ident>,
-    arg_idents: Vec<&'a Ident>,
+    task_params: Vec<&'a GenericParam>,
+    task_idents: Vec<&'a Ident>,
+    phantom_idents: Vec<&'a Ident>,
+    impl_params: Vec<GenericParam>,
+    impl_idents: Vec<Ident>,
+    arg_idents: Vec<Ident>,
     arg_types: Vec<Ident>,
     arg_fields: Vec<Ident>,
 }
 
 impl<'a> FnInfo<'a> {
-    fn from_fn(func: &ItemFn) -> Result<FnInfo, Error> {
+    fn from_fn(func: &'a ItemFn, imp: Option<&'a ItemImpl>) -> Result<FnInfo<'a>, Error> {
         let mut arch_ident = None;
 
+        // The task is also generic over the parameters of the enclosing impl block, if any
+        let mut task_params = Vec::new();
+        let mut task_idents = Vec::new();
+        let mut phantom_idents = Vec::new();
+        for param in imp.iter().flat_map(|imp| &imp.generics.params) {
+            match param {
+                GenericParam::Type(ty) => {
+                    task_idents.push(&ty.ident);
+                    phantom_idents.push(&ty.ident);
+                }
+                GenericParam::Const(param) => task_idents.push(&param.ident),
+                GenericParam::Lifetime(_) => {}
+            }
+            task_params.push(param);
+        }
+
         let mut generic_idents = Vec::new();
         let mut generic_params_no_arch = Vec::new();
         let mut generic_idents_no_arch = Vec::new();
@@ -55,7 +83,7 @@ impl<'a> FnInfo<'a> {
                         for bound in &ty.bounds {
                             if let TypeParamBound::Trait(tr) = bound {
                                 if let Some(last) = tr.path.segments.last() {
-                                    if last.ident.to_string() == "Arch" {
+                                    if last.ident == "Arch" {
                                         arch_ident = Some(&ty.ident);
                                         found_arch = true;
                                     }
@@ -69,15 +97,15 @@ impl<'a> FnInfo<'a> {
                     if !found_arch {
                         generic_params_no_arch.push(param);
                         generic_idents_no_arch.push(&ty.ident);
+                        phantom_idents.push(&ty.ident);
                     }
                 }
-                GenericParam::Const(_) => {
-                    return Err(Error::new(
-                        func.sig.generics.span(),
-                        "const generics not supported",
-                    ));
+                GenericParam::Const(param_const) => {
+                    generic_idents.push(&param_const.ident);
+                    generic_params_no_arch.push(param);
+                    generic_idents_no_arch.push(&param_const.ident);
                 }
-                GenericParam::Lifetime(_) => {}
+                GenericParam::Lifetime(_) => task_params.push(param),
             };
         }
 
@@ -89,35 +117,76 @@ impl<'a> FnInfo<'a> {
         }
         let arch_ident = arch_ident.unwrap();
 
-        let mut arg_idents = Vec::with_capacity(func.sig.inputs.len());
-        let mut arg_types = Vec::with_capacity(func.sig.inputs.len());
-        let mut arg_fields = Vec::with_capacity(func.sig.inputs.len());
-        for (i, arg) in func.sig.inputs.iter().enumerate() {
+        // Lifetimes of the function are only needed for the bounds of the task, and must precede
+        // the other parameters
+        task_params.extend(&generic_params_no_arch);
+        task_params.sort_by_key(|param| !matches!(param, GenericParam::Lifetime(_)));
+        task_idents.extend(&generic_idents_no_arch);
+
+        // The outer signature binds every argument to a plain identifier so that it can be moved
+        // into the task, while `__inner` keeps the original patterns
+        let mut sig = func.sig.clone();
+        let mut arg_idents = Vec::with_capacity(sig.inputs.len());
+        let mut arg_types = Vec::with_capacity(sig.inputs.len());
+        let mut arg_fields = Vec::with_capacity(sig.inputs.len());
+        for (i, arg) in sig.inputs.iter_mut().enumerate() {
             match arg {
-                FnArg::Receiver(_) => {
-                    return Err(Error::new(
-                        func.span(),
-                        "associated functions not supported",
-                    ));
+                FnArg::Receiver(receiver) => {
+                    if imp.is_none() {
+                        return Err(Error::new(
+                            receiver.span(),
+                            "methods require #[multitrack::methods] on the impl block",
+                        ));
+                    }
+
+                    if receiver.reference.is_none() {
+                        receiver.mutability = None;
+                    }
+                    arg_idents.push(Ident::new("self", receiver.self_token.span));
                 }
                 FnArg::Typed(typed) => {
-                    if let Pat::Ident(ident) = &*typed.pat {
-                        arg_idents.push(&ident.ident);
-                        arg_types.push(format_ident!("I{}", i));
-                        arg_fields.push(format_ident!("_{}", i));
-                    } else {
-                        return Err(Error::new(typed.span(), "argument patterns not supported"));
-                    }
+                    let ident = match &*typed.pat {
+                        Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                            pat.ident.clone()
+                        }
+                        _ => format_ident!("__arg{}", i),
+                    };
+                    typed.pat = parse_quote!(#ident);
+                    arg_idents.push(ident);
                 }
             }
+            arg_types.push(format_ident!("I{}", i));
+            arg_fields.push(format_ident!("_{}", i));
         }
 
+        // `impl Trait` arguments become named generic parameters of `__inner`, so that they can
+        // be passed explicitly when it is called from the task
+        let mut inner_sig = func.sig.clone();
+        inner_sig.ident = format_ident!("__inner");
+        let mut impl_traits = ImplTraitParams::default();
+        for arg in &mut inner_sig.inputs {
+            if let FnArg::Typed(typed) = arg {
+                impl_traits.visit_type_mut(&mut typed.ty);
+            }
+        }
+        inner_sig
+            .generics
+            .params
+            .extend(impl_traits.params.iter().cloned());
+
         Ok(FnInfo {
             func,
+            imp,
+            sig,
+            inner_sig,
             arch_ident,
             generic_idents,
-            generic_params_no_arch,
             generic_idents_no_arch,
+            task_params,
+            task_idents,
+            phantom_idents,
+            impl_params: impl_traits.params,
+            impl_idents: impl_traits.idents,
             arg_idents,
             arg_types,
             arg_fields,
@@ -128,45 +197,132 @@ impl<'a> FnInfo<'a> {
         let attrs = &self.func.attrs;
         let vis = &self.func.vis;
         let generic_idents_no_arch = &self.generic_idents_no_arch;
+        let impl_args = self.impl_idents.iter().map(|_| quote! { _ });
+        let phantom_idents = &self.phantom_idents;
         let arg_idents = &self.arg_idents;
         let arg_fields = &self.arg_fields;
 
-        let mut sig = self.func.sig.clone();
-        sig.generics.params = self
-            .generic_params_no_arch
-            .iter()
-            .map(|p| (*p).clone())
-            .collect::<Punctuated<_, Token![,]>>();
+        // The outer function keeps every generic parameter other than the Arch parameter, including
+        // lifetimes
+        let mut sig = self.sig.clone();
+        sig.generics.params = sig
+            .generics
+            .params
+            .into_iter()
+            .filter(
+                |param| !matches!(param, GenericParam::Type(ty) if ty.ident == *self.arch_ident),
+            )
+            .collect();
 
         let task = self.task();
 
+        // An architecture forced at runtime takes precedence, as long as it is supported. Otherwise,
+        // the architecture is chosen on the first call and cached in a static. A static is shared
+        // between every instantiation of a generic function, so it can only hold a function
+        // pointer for one specific task type when the function is not generic. Generic functions
+        // cache the index of the architecture instead.
         let (supported, possible) = opts.archs.split_last().unwrap();
-        let mut proto_sig = sig.clone();
-        proto_sig.ident = format_ident!("__proto");
+        let archs = &opts.archs;
+        let indices = (0..possible.len()).map(|i| i as u8).collect::<Vec<_>>();
+        let fallback = possible.len() as u8;
+
+        let inner = self.inner();
+        let proto = self.item_path(&format_ident!("__proto"));
+        let task_path = self.task_path();
+
+        let forced = quote! {
+            let forced = if ::multitrack::__arch_forced() {
+                ::multitrack::__forced_arch(&[
+                    #((<#archs as ::multitrack::Possible>::NAME, <#archs as ::multitrack::Possible>::supported as fn() -> bool),)*
+                ])
+            } else {
+                None
+            };
+        };
+        let probe = quote! {
+            'probe: {
+                #(
+                    if <#possible as ::multitrack::Possible>::supported() {
+                        break 'probe #indices;
+                    }
+                )*
+                #fallback
+            }
+        };
+
+        let call = if self.task_idents.is_empty() && self.impl_idents.is_empty() {
+            quote! {
+                static __CACHE: ::multitrack::__FnCache = ::multitrack::__FnCache::new();
+
+                #forced
+
+                let cached = match forced {
+                    Some(_) => None,
+                    None => unsafe { __CACHE.get() },
+                };
+                let f = match cached {
+                    Some(f) => f,
+                    None => {
+                        let index = match forced {
+                            Some(index) => index,
+                            None => #probe,
+                        };
+                        let f = match index {
+                            #(
+                                #indices => unsafe { <#possible as ::multitrack::Possible>::specialize_unchecked() },
+                            )*
+                            _ => <#supported as ::multitrack::Supported>::specialize(),
+                        };
+                        if forced.is_none() {
+                            unsafe { __CACHE.set(f) };
+                        }
+                        f
+                    }
+                };
+
+                f(task)
+            }
+        } else {
+            quote! {
+                static __ARCH: ::core::sync::atomic::AtomicU8 = ::core::sync::atomic::AtomicU8::new(u8::MAX);
+
+                #forced
+
+                let index = match forced {
+                    Some(index) => index,
+                    None => {
+                        let mut index = __ARCH.load(::core::sync::atomic::Ordering::Relaxed);
+                        if index == u8::MAX {
+                            index = #probe;
+                            __ARCH.store(index, ::core::sync::atomic::Ordering::Relaxed);
+                        }
+                        index
+                    }
+                };
+
+                match index {
+                    #(
+                        #indices => unsafe { <#possible as ::multitrack::Possible>::invoke_unchecked(task) },
+                    )*
+                    _ => <#supported as ::multitrack::Supported>::invoke(task),
+                }
+            }
+        };
 
         quote! {
             #(#attrs)*
             #vis #sig {
                 #task
 
-                #[allow(unused_variables)]
-                #proto_sig {
-                    core::unimplemented!()
-                }
+                #inner
 
-                let task = __Task {
+                let task = #task_path {
                     #(#arg_fields: ::core::mem::ManuallyDrop::new(#arg_idents),)*
-                    _f: __proto::<#(#generic_idents_no_arch,)*>,
-                    _phantom: ::core::marker::PhantomData::<(#(#generic_idents_no_arch,)*)>,
+                    _f: #proto::<#(#generic_idents_no_arch,)* #(#impl_args,)*>,
+                    _phantom: ::core::marker::PhantomData::<(#(#phantom_idents,)*)>,
                 };
 
-                #(
-                    if <#possible as ::multitrack::Possible>::supported() {
-                        return unsafe { <#possible as ::multitrack::Possible>::invoke_unchecked(task) };
-                    }
-                )*
-
-                <#supported as ::multitrack::Supported>::invoke(task)
+                #call
             }
         }
     }
@@ -174,24 +330,30 @@ impl<'a> FnInfo<'a> {
     fn specialize(&self) -> TokenStream2 {
         let attrs = &self.func.attrs;
         let vis = &self.func.vis;
-        let sig = &self.func.sig;
+        let sig = &self.sig;
         let arch_ident = self.arch_ident;
-        let generic_idents = &self.generic_idents;
         let generic_idents_no_arch = &self.generic_idents_no_arch;
+        let impl_args = self.impl_idents.iter().map(|_| quote! { _ });
+        let phantom_idents = &self.phantom_idents;
         let arg_idents = &self.arg_idents;
         let arg_fields = &self.arg_fields;
 
         let task = self.task();
+        let inner = self.inner();
+        let proto = self.item_path(&format_ident!("__proto"));
+        let task_path = self.task_path();
 
         quote! {
             #(#attrs)*
             #vis #sig {
                 #task
 
-                <#arch_ident as ::multitrack::Arch>::invoke(__Task {
+                #inner
+
+                <#arch_ident as ::multitrack::Arch>::invoke(#task_path {
                     #(#arg_fields: ::core::mem::ManuallyDrop::new(#arg_idents),)*
-                    _f: __inner::<#(#generic_idents,)*>,
-                    _phantom: ::core::marker::PhantomData::<(#(#generic_idents_no_arch,)*)>,
+                    _f: #proto::<#(#generic_idents_no_arch,)* #(#impl_args,)*>,
+                    _phantom: ::core::marker::PhantomData::<(#(#phantom_idents,)*)>,
                 })
             }
         }
@@ -199,34 +361,45 @@ impl<'a> FnInfo<'a> {
 
     fn task(&self) -> TokenStream2 {
         let generic_idents = &self.generic_idents;
-        let generic_params_no_arch = &self.generic_params_no_arch;
-        let generic_idents_no_arch = &self.generic_idents_no_arch;
+        let task_params = &self.task_params;
+        let task_idents = &self.task_idents;
+        let phantom_idents = &self.phantom_idents;
+        let impl_params = &self.impl_params;
+        let impl_idents = &self.impl_idents;
         let arg_types = &self.arg_types;
         let arg_fields = &self.arg_fields;
         let arch_ident = &self.arch_ident;
 
+        let impl_where_clause = self.imp.and_then(|imp| imp.generics.where_clause.as_ref());
         let where_clause = &self.func.sig.generics.where_clause;
-        let predicates = where_clause
-            .as_ref()
-            .map(|w| w.predicates.iter())
+        let predicates = impl_where_clause
             .into_iter()
-            .flatten();
+            .chain(where_clause)
+            .flat_map(|w| w.predicates.iter());
 
-        let mut inner_sig = self.func.sig.clone();
-        inner_sig.ident = format_ident!("__inner");
+        let inner = self.item_path(&format_ident!("__inner"));
 
-        let block = &self.func.block;
+        // Lifetimes are erased in the argument types, and bounds are only needed on the impl
+        let struct_params = task_params.iter().filter_map(|param| match param {
+            GenericParam::Type(ty) => Some(ty.ident.to_token_stream()),
+            GenericParam::Const(param) => {
+                let (ident, ty) = (&param.ident, &param.ty);
+                Some(quote! { const #ident: #ty })
+            }
+            GenericParam::Lifetime(_) => None,
+        });
 
+        // The `impl Trait` parameters are recovered from the return type of `__proto`
         quote! {
-            struct __Task<#(#generic_idents_no_arch,)* F, #(#arg_types,)*> {
+            struct __Task<#(#struct_params,)* F, #(#arg_types,)*> {
                 #(#arg_fields: ::core::mem::ManuallyDrop<#arg_types>,)*
                 _f: F,
-                _phantom: ::core::marker::PhantomData<(#(#generic_idents_no_arch,)*)>,
+                _phantom: ::core::marker::PhantomData<(#(#phantom_idents,)*)>,
             }
 
-            impl<#(#generic_params_no_arch,)* F, #(#arg_types,)* O> ::multitrack::Task for __Task<#(#generic_idents_no_arch,)* F, #(#arg_types),*>
+            impl<#(#task_params,)* #(#impl_params,)* F, #(#arg_types,)* O> ::multitrack::Task for __Task<#(#task_idents,)* F, #(#arg_types),*>
             where
-                F: Fn(#(#arg_types),*) -> O,
+                F: Fn(#(#arg_types),*) -> (::core::marker::PhantomData<(#(#impl_idents,)*)>, O),
                 #(
                     #predicates,
                 )*
@@ -238,30 +411,202 @@ impl<'a> FnInfo<'a> {
                     use ::core::mem::{ManuallyDrop, transmute_copy};
 
                     unsafe {
-                        let res = __inner::<#(#generic_idents,)*>(
+                        let res = #inner::<#(#generic_idents,)* #(#impl_idents,)*>(
                             #(transmute_copy::<ManuallyDrop<#arg_types>, _>(&self.#arg_fields),)*
                         );
                         transmute_copy::<_, O>(&ManuallyDrop::new(res))
                     }
                 }
             }
+        }
+    }
+
+    /// Returns the path to `__Task` with its generic arguments, which must be given explicitly as
+    /// const parameters cannot be inferred from the fields.
+    fn task_path(&self) -> TokenStream2 {
+        let task_idents = &self.task_idents;
+        let arg_types = self.arg_types.iter().map(|_| quote! { _ });
+
+        quote! { __Task::<#(#task_idents,)* _, #(#arg_types,)*> }
+    }
+
+    /// Defines `__inner`, which contains the original function body, and `__proto`, which is
+    /// never called but provides the argument and return types for the task.
+    fn inner(&self) -> TokenStream2 {
+        let inner_sig = &self.inner_sig;
+        let block = &self.func.block;
+
+        // `__proto` takes the same arguments as `__inner` without the Arch parameter, and also
+        // returns the `impl Trait` parameters, which could not be named by the caller
+        let mut proto_sig = declaration(inner_sig);
+        proto_sig.ident = format_ident!("__proto");
+        proto_sig.generics.params = proto_sig
+            .generics
+            .params
+            .into_iter()
+            .filter(
+                |param| !matches!(param, GenericParam::Type(ty) if ty.ident == *self.arch_ident),
+            )
+            .collect();
+        let impl_idents = &self.impl_idents;
+        let output = match &proto_sig.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => ty.to_token_stream(),
+        };
+        proto_sig.output = parse_quote! {
+            -> (::core::marker::PhantomData<(#(#impl_idents,)*)>, #output)
+        };
+
+        let imp = match self.imp {
+            Some(imp) => imp,
+            None => {
+                return quote! {
+                    #[inline(always)]
+                    #inner_sig #block
+
+                    #proto_sig {
+                        core::unimplemented!()
+                    }
+                };
+            }
+        };
+
+        // Nested items cannot refer to `Self` or to the generics of the impl block, so methods are
+        // defined in a local trait implemented for the self type instead
+        let (impl_generics, ty_generics, where_clause) = imp.generics.split_for_impl();
+        let self_ty = &imp.self_ty;
+        let inner_decl = declaration(inner_sig);
+
+        quote! {
+            trait __Methods #impl_generics #where_clause {
+                #inner_decl;
+                #proto_sig;
+            }
+
+            impl #impl_generics __Methods #ty_generics for #self_ty #where_clause {
+                #[inline(always)]
+                #inner_sig #block
+
+                #proto_sig {
+                    core::unimplemented!()
+                }
+            }
+        }
+    }
+
+    /// Returns the path to an item defined by [`FnInfo::inner`].
+    fn item_path(&self, ident: &Ident) -> TokenStream2 {
+        match self.imp {
+            Some(imp) => {
+                // Naming the self type with its lifetimes would require the bounds which are
+                // implied for the impl block, so leave them to inference instead
+                let self_ty = &imp.self_ty;
+                let (_, ty_generics, _) = imp.generics.split_for_impl();
+                erase_lifetimes(quote! { <#self_ty as __Methods #ty_generics>::#ident })
+            }
+            None => quote! { #ident },
+        }
+    }
+}
+
+/// Replaces every lifetime with `'_`.
+fn erase_lifetimes(tokens: TokenStream2) -> TokenStream2 {
+    let mut after_quote = false;
+    tokens
+        .into_iter()
+        .map(|token| {
+            let token = match token {
+                TokenTree::Group(group) => {
+                    let mut erased = Group::new(group.delimiter(), erase_lifetimes(group.stream()));
+                    erased.set_span(group.span());
+                    TokenTree::Group(erased)
+                }
+                TokenTree::Ident(ident) if after_quote => {
+                    TokenTree::Ident(Ident::new("_", ident.span()))
+                }
+                token => token,
+            };
+            after_quote = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '\'');
+            token
+        })
+        .collect()
+}
+
+/// Replaces `impl Trait` types with new generic parameters.
+#[derive(Default)]
+struct ImplTraitParams {
+    params: Vec<GenericParam>,
+    idents: Vec<Ident>,
+}
+
+impl VisitMut for ImplTraitParams {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        visit_mut::visit_type_mut(self, ty);
+
+        if let Type::ImplTrait(impl_trait) = ty {
+            let ident = format_ident!("__Impl{}", self.idents.len());
+            let bounds = &impl_trait.bounds;
+            self.params.push(parse_quote!(#ident: #bounds));
+            *ty = parse_quote!(#ident);
+            self.idents.push(ident);
+        }
+    }
+}
+
+/// Strips the argument patterns from a signature, including `mut self`, which are not allowed
+/// without a body.
+fn declaration(sig: &Signature) -> Signature {
+    let mut sig = sig.clone();
+    for arg in &mut sig.inputs {
+        match arg {
+            FnArg::Receiver(receiver) if receiver.reference.is_none() => {
+                receiver.mutability = None;
+            }
+            FnArg::Receiver(_) => {}
+            FnArg::Typed(typed) => typed.pat = parse_quote!(_),
+        }
+    }
+    sig
+}
+
+/// Attribute arguments: a list of architectures, optionally followed by `;` and the impl block
+/// enclosing the function (with no items), which is passed along by `#[methods]`.
+struct Args {
+    archs: Punctuated<Path, Token![,]>,
+    imp: Option<ItemImpl>,
+}
 
-            #[inline(always)]
-            #inner_sig #block
+impl Parse for Args {
+    fn parse(input: ParseStream) -> Result<Args, Error> {
+        let mut archs = Punctuated::new();
+        while !input.is_empty() && !input.peek(Token![;]) {
+            archs.push_value(input.parse()?);
+            if input.is_empty() || input.peek(Token![;]) {
+                break;
+            }
+            archs.push_punct(input.parse()?);
         }
+
+        let imp = if input.parse::<Option<Token![;]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Args { archs, imp })
     }
 }
 
 #[proc_macro_attribute]
 pub fn dispatch(attr: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(attr with Punctuated::parse_terminated);
-    let opts = match DispatchOpts::from_args(&args) {
+    let args = parse_macro_input!(attr as Args);
+    let opts = match DispatchOpts::from_args(&args.archs) {
         Ok(opts) => opts,
         Err(err) => return err.into_compile_error().into(),
     };
 
     let func = parse_macro_input!(input as ItemFn);
-    let info = match FnInfo::from_fn(&func) {
+    let info = match FnInfo::from_fn(&func, args.imp.as_ref()) {
         Ok(info) => info,
         Err(err) => return err.into_compile_error().into(),
     };
@@ -270,11 +615,58 @@ pub fn dispatch(attr: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn specialize(_attr: TokenStream, input: TokenStream) -> TokenStream {
+pub fn specialize(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as Args);
     let func = parse_macro_input!(input as ItemFn);
 
-    match FnInfo::from_fn(&func) {
+    match FnInfo::from_fn(&func, args.imp.as_ref()) {
         Ok(info) => info.specialize().into(),
         Err(err) => err.into_compile_error().into(),
     }
 }
+
+/// Allows `#[dispatch]` and `#[specialize]` on the methods of an impl block, by passing the impl
+/// block on to them. The code generated for a method needs to name the self type, which an
+/// attribute on the method cannot see.
+#[proc_macro_attribute]
+pub fn methods(_attr: TokenStream, input: TokenStream) -> TokenStream {
+    let mut imp = parse_macro_input!(input as ItemImpl);
+
+    let header = ItemImpl {
+        attrs: Vec::new(),
+        defaultness: None,
+        unsafety: None,
+        trait_: None,
+        items: Vec::new(),
+        ..imp.clone()
+    };
+
+    for item in &mut imp.items {
+        if let ImplItem::Method(method) = item {
+            for attr in &mut method.attrs {
+                if let Some(segment) = attr.path.segments.last() {
+                    if segment.ident == "dispatch" || segment.ident == "specialize" {
+                        attr.tokens = append_impl(&attr.tokens, &header);
+                    }
+                }
+            }
+        }
+    }
+
+    quote!(#imp).into()
+}
+
+/// Appends `; <impl block>` to the parenthesized arguments of an attribute.
+fn append_impl(tokens: &TokenStream2, header: &ItemImpl) -> TokenStream2 {
+    let mut iter = tokens.clone().into_iter();
+    let args = match (iter.next(), iter.next()) {
+        (None, _) => TokenStream2::new(),
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Parenthesis => {
+            group.stream()
+        }
+        // Leave malformed arguments for the attribute to report
+        _ => return tokens.clone(),
+    };
+
+    quote! { (#args; #header) }
+}
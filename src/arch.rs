@@ -1,5 +1,8 @@
 use crate::{Arch, Possible, Supported, Task};
 
+mod emulated;
+#[cfg(feature = "portable")]
+mod portable;
 mod scalar;
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -10,6 +13,8 @@ pub use x86::*;
 pub struct Scalar;
 
 impl Possible for Scalar {
+    const NAME: &'static str = scalar::ScalarImpl::NAME;
+
     #[inline]
     fn supported() -> bool {
         true
@@ -22,3 +27,91 @@ impl Possible for Scalar {
 }
 
 unsafe impl Supported for Scalar {}
+
+/// The scalar backend widened to vectors of `N` lanes, for testing code against lane counts that
+/// the target does not natively support. `N` must be between 1 and 64.
+pub struct Emulated<const N: usize>;
+
+impl<const N: usize> Possible for Emulated<N> {
+    const NAME: &'static str = emulated::EmulatedImpl::<N>::NAME;
+
+    #[inline]
+    fn supported() -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+        emulated::EmulatedImpl::<N>::invoke(task)
+    }
+}
+
+unsafe impl<const N: usize> Supported for Emulated<N> {}
+
+/// Vectors of `N` lanes built on `core::simd`, for targets without a hand-written backend. `N`
+/// must be a power of two no greater than 64. Requires the `portable` feature and a nightly
+/// compiler.
+#[cfg(feature = "portable")]
+pub struct Portable<const N: usize>;
+
+#[cfg(feature = "portable")]
+impl<const N: usize> Possible for Portable<N> {
+    const NAME: &'static str = portable::PortableImpl::<N>::NAME;
+
+    #[inline]
+    fn supported() -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+        portable::PortableImpl::<N>::invoke(task)
+    }
+}
+
+#[cfg(feature = "portable")]
+unsafe impl<const N: usize> Supported for Portable<N> {}
+
+/// The name of a backend with a configurable lane count, followed by the lane count, such as
+/// `emulated4`. Built at compile time so that it can be used as [`Arch::NAME`].
+pub(crate) struct LanesName {
+    buf: [u8; 16],
+    len: usize,
+}
+
+impl LanesName {
+    pub(crate) const fn new(backend: &str, lanes: usize) -> LanesName {
+        let backend = backend.as_bytes();
+        let mut buf = [0; 16];
+        let mut len = 0;
+        while len < backend.len() {
+            buf[len] = backend[len];
+            len += 1;
+        }
+
+        let mut digits = 1;
+        let mut rest = lanes / 10;
+        while rest > 0 {
+            digits += 1;
+            rest /= 10;
+        }
+
+        len += digits;
+        let mut i = len;
+        let mut rest = lanes;
+        while i > len - digits {
+            i -= 1;
+            buf[i] = b'0' + (rest % 10) as u8;
+            rest /= 10;
+        }
+
+        LanesName { buf, len }
+    }
+
+    pub(crate) const fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.buf.split_at(self.len).0) {
+            Ok(name) => name,
+            Err(_) => unreachable!(),
+        }
+    }
+}
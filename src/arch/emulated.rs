@@ -0,0 +1,630 @@
+#![allow(non_camel_case_types)]
+
+use core::array;
+use core::fmt::{self, Debug};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
+use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+use core::slice;
+
+use super::scalar::*;
+use super::LanesName;
+use crate::mask::*;
+use crate::simd::{
+    Array, Bitwise, Cast, Float, Gather, Int, LanesEq, LanesOrd, Mask, RoundCast, Select, Shuffle,
+    Simd, Widen,
+};
+use crate::{Arch, Task};
+
+pub struct EmulatedImpl<const N: usize>;
+
+impl<const N: usize> Arch for EmulatedImpl<N> {
+    type f32 = f32xN<N>;
+    type f64 = f64xN<N>;
+
+    type u8 = u8xN<N>;
+    type u16 = u16xN<N>;
+    type u32 = u32xN<N>;
+    type u64 = u64xN<N>;
+
+    type i8 = i8xN<N>;
+    type i16 = i16xN<N>;
+    type i32 = i32xN<N>;
+    type i64 = i64xN<N>;
+
+    type m8 = m8xN<N>;
+    type m16 = m16xN<N>;
+    type m32 = m32xN<N>;
+    type m64 = m64xN<N>;
+
+    const NAME: &'static str = LanesName::new("emulated", N).as_str();
+
+    #[inline]
+    fn invoke<T: Task>(task: T) -> T::Result {
+        // Masks must fit in a u64 bitmask
+        const { assert!(N > 0 && N <= 64, "lane count must be between 1 and 64") };
+
+        task.run::<EmulatedImpl<N>>()
+    }
+}
+
+// Vectors are arrays of the scalar backend's one-lane vectors, and every operation is applied to
+// each lane in turn, so that results match the scalar backend exactly. Reductions fold the lanes
+// from first to last.
+
+macro_rules! vector_type {
+    ($vec:ident, $lane:ident, $elem:ident, $mask:ident) => {
+        #[derive(Copy, Clone)]
+        #[repr(transparent)]
+        pub struct $vec<const N: usize>([$lane; N]);
+
+        impl<const N: usize> $vec<N> {
+            #[inline]
+            fn map(self, f: impl FnMut($lane) -> $lane) -> Self {
+                $vec(self.0.map(f))
+            }
+
+            #[inline]
+            fn zip(self, other: Self, mut f: impl FnMut($lane, $lane) -> $lane) -> Self {
+                $vec(array::from_fn(|i| f(self.0[i], other.0[i])))
+            }
+
+            #[inline]
+            fn fold(self, f: impl FnMut($lane, &$lane) -> $lane) -> $elem {
+                self.0[1..].iter().fold(self.0[0], f)[0]
+            }
+        }
+
+        impl<const N: usize> Default for $vec<N> {
+            #[inline]
+            fn default() -> Self {
+                $vec([$lane::default(); N])
+            }
+        }
+
+        impl<const N: usize> Shuffle for $vec<N> {}
+
+        impl<const N: usize> Simd for $vec<N> {
+            type Elem = $elem;
+            type Mask = $mask<N>;
+
+            const LANES: usize = N;
+
+            #[inline]
+            fn new(elem: Self::Elem) -> Self {
+                $vec([$lane::new(elem); N])
+            }
+
+            #[inline]
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
+            }
+
+            #[inline]
+            fn from_slice(slice: &[Self::Elem]) -> Self {
+                assert!(slice.len() == N);
+                $vec(array::from_fn(|i| $lane::new(slice[i])))
+            }
+
+            #[inline]
+            fn write_to_slice(&self, slice: &mut [Self::Elem]) {
+                slice.copy_from_slice(self.as_slice());
+            }
+        }
+
+        impl<const N: usize> Array for $vec<N> {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                // Scalar vectors are transparent wrappers around a single element
+                unsafe { slice::from_raw_parts(self.0.as_ptr() as *const Self::Elem, N) }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe { slice::from_raw_parts_mut(self.0.as_mut_ptr() as *mut Self::Elem, N) }
+            }
+
+            #[inline]
+            fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
+                unsafe { slice.align_to::<Self>() }
+            }
+
+            #[inline]
+            fn align_mut_slice(
+                slice: &mut [Self::Elem],
+            ) -> (&mut [Self::Elem], &mut [Self], &mut [Self::Elem]) {
+                unsafe { slice.align_to_mut::<Self>() }
+            }
+        }
+
+        impl<const N: usize> LanesEq for $vec<N> {
+            type Output = $mask<N>;
+
+            #[inline]
+            fn eq(&self, other: &Self) -> $mask<N> {
+                $mask(array::from_fn(|i| self.0[i].eq(&other.0[i])))
+            }
+        }
+
+        impl<const N: usize> LanesOrd for $vec<N> {
+            #[inline]
+            fn lt(&self, other: &Self) -> $mask<N> {
+                $mask(array::from_fn(|i| self.0[i].lt(&other.0[i])))
+            }
+        }
+
+        impl<const N: usize> Index<usize> for $vec<N> {
+            type Output = $elem;
+
+            #[inline]
+            fn index(&self, index: usize) -> &$elem {
+                &self.as_slice()[index]
+            }
+        }
+
+        impl<const N: usize> IndexMut<usize> for $vec<N> {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut $elem {
+                &mut self.as_mut_slice()[index]
+            }
+        }
+
+        impl<const N: usize> Debug for $vec<N> {
+            #[inline]
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Debug::fmt(self.as_slice(), fmt)
+            }
+        }
+
+        impl<const N: usize> Select<$vec<N>> for $mask<N> {
+            #[inline]
+            fn select(self, if_true: $vec<N>, if_false: $vec<N>) -> $vec<N> {
+                $vec(array::from_fn(|i| {
+                    self.0[i].select(if_true.0[i], if_false.0[i])
+                }))
+            }
+        }
+    };
+}
+
+macro_rules! impl_binary_op {
+    ($vec:ident, $op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+        impl<const N: usize> $op for $vec<N> {
+            type Output = Self;
+
+            #[inline]
+            fn $fn(self, rhs: Self) -> Self {
+                self.zip(rhs, $op::$fn)
+            }
+        }
+
+        impl<const N: usize> $op_assign for $vec<N> {
+            #[inline]
+            fn $fn_assign(&mut self, rhs: Self) {
+                *self = $op::$fn(*self, rhs);
+            }
+        }
+    };
+}
+
+macro_rules! impl_float {
+    ($float:ident, $bits:ident) => {
+        impl<const N: usize> Float for $float<N> {
+            type Bits = $bits<N>;
+
+            #[inline]
+            fn to_bits(self) -> Self::Bits {
+                $bits(self.0.map(Float::to_bits))
+            }
+
+            #[inline]
+            fn from_bits(bits: Self::Bits) -> Self {
+                $float(bits.0.map(Float::from_bits))
+            }
+
+            #[inline]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                self * a + b
+            }
+
+            #[inline]
+            fn sqrt(self) -> Self {
+                self.map(Float::sqrt)
+            }
+
+            #[inline]
+            fn recip(self) -> Self {
+                self.map(Float::recip)
+            }
+
+            #[inline]
+            fn recip_approx(self) -> Self {
+                self.recip()
+            }
+
+            #[inline]
+            fn rsqrt(self) -> Self {
+                self.map(Float::rsqrt)
+            }
+
+            #[inline]
+            fn rsqrt_approx(self) -> Self {
+                self.rsqrt()
+            }
+
+            #[inline]
+            fn abs(self) -> Self {
+                self.map(Float::abs)
+            }
+
+            #[inline]
+            fn copysign(self, sign: Self) -> Self {
+                self.zip(sign, Float::copysign)
+            }
+
+            #[inline]
+            fn signum(self) -> Self {
+                self.map(Float::signum)
+            }
+
+            #[inline]
+            fn floor(self) -> Self {
+                self.map(Float::floor)
+            }
+
+            #[inline]
+            fn ceil(self) -> Self {
+                self.map(Float::ceil)
+            }
+
+            #[inline]
+            fn round(self) -> Self {
+                self.map(Float::round)
+            }
+
+            #[inline]
+            fn trunc(self) -> Self {
+                self.map(Float::trunc)
+            }
+
+            #[inline]
+            fn fract(self) -> Self {
+                self.map(Float::fract)
+            }
+
+            #[inline]
+            fn reduce_sum(self) -> Self::Elem {
+                self.fold(|acc, &x| acc + x)
+            }
+
+            #[inline]
+            fn reduce_product(self) -> Self::Elem {
+                self.fold(|acc, &x| acc * x)
+            }
+
+            #[inline]
+            fn reduce_min(self) -> Self::Elem {
+                self.fold(|acc, &x| acc.min(x))
+            }
+
+            #[inline]
+            fn reduce_max(self) -> Self::Elem {
+                self.fold(|acc, &x| acc.max(x))
+            }
+        }
+
+        impl_binary_op! { $float, Add, add, AddAssign, add_assign }
+        impl_binary_op! { $float, Sub, sub, SubAssign, sub_assign }
+        impl_binary_op! { $float, Mul, mul, MulAssign, mul_assign }
+        impl_binary_op! { $float, Div, div, DivAssign, div_assign }
+
+        impl<const N: usize> Neg for $float<N> {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                self.map(Neg::neg)
+            }
+        }
+    };
+}
+
+macro_rules! impl_int {
+    ($int:ident) => {
+        impl<const N: usize> Int for $int<N> {
+            #[inline]
+            fn saturating_add(self, rhs: Self) -> Self {
+                self.zip(rhs, Int::saturating_add)
+            }
+
+            #[inline]
+            fn saturating_sub(self, rhs: Self) -> Self {
+                self.zip(rhs, Int::saturating_sub)
+            }
+
+            #[inline]
+            fn avg(self, rhs: Self) -> Self {
+                self.zip(rhs, Int::avg)
+            }
+
+            #[inline]
+            fn mulhi(self, rhs: Self) -> Self {
+                self.zip(rhs, Int::mulhi)
+            }
+
+            #[inline]
+            fn shl_by(self, counts: Self) -> Self {
+                self.zip(counts, Int::shl_by)
+            }
+
+            #[inline]
+            fn shr_by(self, counts: Self) -> Self {
+                self.zip(counts, Int::shr_by)
+            }
+
+            #[inline]
+            fn rotate_left(self, n: usize) -> Self {
+                self.map(|x| x.rotate_left(n))
+            }
+
+            #[inline]
+            fn rotate_right(self, n: usize) -> Self {
+                self.map(|x| x.rotate_right(n))
+            }
+
+            #[inline]
+            fn reduce_sum(self) -> Self::Elem {
+                self.fold(|acc, &x| acc + x)
+            }
+
+            #[inline]
+            fn reduce_product(self) -> Self::Elem {
+                self.fold(|acc, &x| acc * x)
+            }
+
+            #[inline]
+            fn reduce_min(self) -> Self::Elem {
+                self.fold(|acc, &x| acc.min(x))
+            }
+
+            #[inline]
+            fn reduce_max(self) -> Self::Elem {
+                self.fold(|acc, &x| acc.max(x))
+            }
+        }
+
+        impl_binary_op! { $int, Add, add, AddAssign, add_assign }
+        impl_binary_op! { $int, Sub, sub, SubAssign, sub_assign }
+        impl_binary_op! { $int, Mul, mul, MulAssign, mul_assign }
+        impl_binary_op! { $int, Div, div, DivAssign, div_assign }
+        impl_binary_op! { $int, Rem, rem, RemAssign, rem_assign }
+
+        impl<const N: usize> Neg for $int<N> {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                self.map(Neg::neg)
+            }
+        }
+
+        impl<const N: usize> Shl<usize> for $int<N> {
+            type Output = Self;
+
+            #[inline]
+            fn shl(self, rhs: usize) -> Self {
+                self.map(|x| x << rhs)
+            }
+        }
+
+        impl<const N: usize> ShlAssign<usize> for $int<N> {
+            #[inline]
+            fn shl_assign(&mut self, rhs: usize) {
+                *self = *self << rhs;
+            }
+        }
+
+        impl<const N: usize> Shr<usize> for $int<N> {
+            type Output = Self;
+
+            #[inline]
+            fn shr(self, rhs: usize) -> Self {
+                self.map(|x| x >> rhs)
+            }
+        }
+
+        impl<const N: usize> ShrAssign<usize> for $int<N> {
+            #[inline]
+            fn shr_assign(&mut self, rhs: usize) {
+                *self = *self >> rhs;
+            }
+        }
+    };
+}
+
+macro_rules! impl_bitwise {
+    ($bitwise:ident) => {
+        impl<const N: usize> Bitwise for $bitwise<N> {
+            #[inline]
+            fn reduce_and(self) -> Self::Elem {
+                self.fold(|acc, &x| acc & x)
+            }
+
+            #[inline]
+            fn reduce_or(self) -> Self::Elem {
+                self.fold(|acc, &x| acc | x)
+            }
+
+            #[inline]
+            fn reduce_xor(self) -> Self::Elem {
+                self.fold(|acc, &x| acc ^ x)
+            }
+        }
+
+        impl_binary_op! { $bitwise, BitAnd, bitand, BitAndAssign, bitand_assign }
+        impl_binary_op! { $bitwise, BitOr, bitor, BitOrAssign, bitor_assign }
+        impl_binary_op! { $bitwise, BitXor, bitxor, BitXorAssign, bitxor_assign }
+
+        impl<const N: usize> Not for $bitwise<N> {
+            type Output = Self;
+
+            #[inline]
+            fn not(self) -> Self::Output {
+                self.map(Not::not)
+            }
+        }
+    };
+}
+
+macro_rules! impl_mask {
+    ($mask:ident, $lane:ident) => {
+        impl<const N: usize> Mask for $mask<N> {
+            #[inline]
+            fn to_bitmask(self) -> u64 {
+                let lanes = self.0.iter().enumerate();
+                lanes.fold(0, |bits, (i, x)| bits | x.to_bitmask() << i)
+            }
+
+            #[inline]
+            fn from_bitmask(bits: u64) -> Self {
+                $mask(array::from_fn(|i| $lane::from_bitmask(bits >> i)))
+            }
+        }
+    };
+}
+
+macro_rules! impl_cast {
+    ($from:ident, $to:ident) => {
+        impl<const N: usize> Cast<$to<N>> for $from<N> {
+            #[inline]
+            fn cast(self) -> $to<N> {
+                $to(self.0.map(Cast::cast))
+            }
+        }
+    };
+}
+
+macro_rules! impl_round_cast {
+    ($from:ident, $to:ident) => {
+        impl<const N: usize> RoundCast<$to<N>> for $from<N> {
+            #[inline]
+            fn round_cast(self) -> $to<N> {
+                $to(self.0.map(RoundCast::round_cast))
+            }
+        }
+    };
+}
+
+macro_rules! impl_widen {
+    ($narrow:ident, $wide:ident) => {
+        impl<const N: usize> Widen for $narrow<N> {
+            type Wide = $wide<N>;
+            type Parts = [$wide<N>; 1];
+
+            #[inline]
+            fn widen(self) -> Self::Parts {
+                [$wide(self.0.map(|x| x.widen()[0]))]
+            }
+
+            #[inline]
+            fn narrow(parts: Self::Parts) -> Self {
+                $narrow(parts[0].0.map(|x| Widen::narrow([x])))
+            }
+        }
+    };
+}
+
+macro_rules! impl_gather {
+    ($vec:ident, $($index:ident),*) => {
+        $(
+            impl<const N: usize> Gather<$index<N>> for $vec<N> {
+                type Index = $index<N>;
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+}
+
+vector_type! { f32xN, f32x1, f32, m32xN }
+vector_type! { f64xN, f64x1, f64, m64xN }
+impl_float! { f32xN, u32xN }
+impl_float! { f64xN, u64xN }
+impl_cast! { f32xN, i32xN }
+impl_cast! { f32xN, u32xN }
+impl_cast! { f64xN, i64xN }
+impl_cast! { f64xN, u64xN }
+impl_round_cast! { f32xN, i32xN }
+impl_round_cast! { f32xN, u32xN }
+impl_round_cast! { f64xN, i64xN }
+impl_round_cast! { f64xN, u64xN }
+impl_widen! { f32xN, f64xN }
+impl_gather! { f32xN, u32xN, i32xN }
+impl_gather! { f64xN, u64xN, i64xN, u32xN, i32xN }
+
+vector_type! { u8xN, u8x1, u8, m8xN }
+vector_type! { u16xN, u16x1, u16, m16xN }
+vector_type! { u32xN, u32x1, u32, m32xN }
+vector_type! { u64xN, u64x1, u64, m64xN }
+impl_int! { u8xN }
+impl_int! { u16xN }
+impl_int! { u32xN }
+impl_int! { u64xN }
+impl_bitwise! { u8xN }
+impl_bitwise! { u16xN }
+impl_bitwise! { u32xN }
+impl_bitwise! { u64xN }
+impl_cast! { u8xN, i8xN }
+impl_cast! { u16xN, i16xN }
+impl_cast! { u32xN, i32xN }
+impl_cast! { u32xN, f32xN }
+impl_cast! { u64xN, i64xN }
+impl_cast! { u64xN, f64xN }
+impl_widen! { u8xN, u16xN }
+impl_widen! { u16xN, u32xN }
+impl_widen! { u32xN, u64xN }
+impl_gather! { u8xN, u8xN, i8xN, u32xN, i32xN }
+impl_gather! { u16xN, u16xN, i16xN, u32xN, i32xN }
+impl_gather! { u32xN, u32xN, i32xN }
+impl_gather! { u64xN, u64xN, i64xN, u32xN, i32xN }
+
+vector_type! { i8xN, i8x1, i8, m8xN }
+vector_type! { i16xN, i16x1, i16, m16xN }
+vector_type! { i32xN, i32x1, i32, m32xN }
+vector_type! { i64xN, i64x1, i64, m64xN }
+impl_int! { i8xN }
+impl_int! { i16xN }
+impl_int! { i32xN }
+impl_int! { i64xN }
+impl_bitwise! { i8xN }
+impl_bitwise! { i16xN }
+impl_bitwise! { i32xN }
+impl_bitwise! { i64xN }
+impl_cast! { i8xN, u8xN }
+impl_cast! { i16xN, u16xN }
+impl_cast! { i32xN, u32xN }
+impl_cast! { i32xN, f32xN }
+impl_cast! { i64xN, u64xN }
+impl_cast! { i64xN, f64xN }
+impl_widen! { i8xN, i16xN }
+impl_widen! { i16xN, i32xN }
+impl_widen! { i32xN, i64xN }
+impl_gather! { i8xN, u8xN, i8xN, u32xN, i32xN }
+impl_gather! { i16xN, u16xN, i16xN, u32xN, i32xN }
+impl_gather! { i32xN, u32xN, i32xN }
+impl_gather! { i64xN, u64xN, i64xN, u32xN, i32xN }
+
+vector_type! { m8xN, m8x1, m8, m8xN }
+vector_type! { m16xN, m16x1, m16, m16xN }
+vector_type! { m32xN, m32x1, m32, m32xN }
+vector_type! { m64xN, m64x1, m64, m64xN }
+impl_bitwise! { m8xN }
+impl_bitwise! { m16xN }
+impl_bitwise! { m32xN }
+impl_bitwise! { m64xN }
+impl_mask! { m8xN, m8x1 }
+impl_mask! { m16xN, m16x1 }
+impl_mask! { m32xN, m32x1 }
+impl_mask! { m64xN, m64x1 }
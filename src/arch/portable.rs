@@ -0,0 +1,1029 @@
+#![allow(non_camel_case_types)]
+
+use core::fmt::{self, Debug};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
+use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+use core::slice;
+
+use core::simd::cmp::{SimdOrd, SimdPartialEq, SimdPartialOrd};
+use core::simd::num::{SimdFloat, SimdInt, SimdUint};
+use core::simd::{Mask as CoreMask, Select as _, Simd as CoreSimd};
+use std::simd::StdFloat;
+
+use super::LanesName;
+use crate::mask::*;
+use crate::simd::{
+    check_mask_len, Array, Bitwise, Cast, Float, Gather, Int, LanesEq, LanesOrd, Mask, RoundCast,
+    Select, Shuffle, Simd, Widen,
+};
+use crate::{Arch, Task};
+
+pub struct PortableImpl<const N: usize>;
+
+impl<const N: usize> Arch for PortableImpl<N> {
+    type f32 = f32xN<N>;
+    type f64 = f64xN<N>;
+
+    type u8 = u8xN<N>;
+    type u16 = u16xN<N>;
+    type u32 = u32xN<N>;
+    type u64 = u64xN<N>;
+
+    type i8 = i8xN<N>;
+    type i16 = i16xN<N>;
+    type i32 = i32xN<N>;
+    type i64 = i64xN<N>;
+
+    type m8 = m8xN<N>;
+    type m16 = m16xN<N>;
+    type m32 = m32xN<N>;
+    type m64 = m64xN<N>;
+
+    const NAME: &'static str = LanesName::new("portable", N).as_str();
+
+    #[inline]
+    fn invoke<T: Task>(task: T) -> T::Result {
+        // Vectors must have no padding for `align_slice`, and masks must fit in a u64 bitmask
+        const {
+            assert!(
+                N.is_power_of_two() && N <= 64,
+                "lane count must be a power of two no greater than 64"
+            )
+        };
+
+        task.run::<PortableImpl<N>>()
+    }
+}
+
+// Vectors wrap a `core::simd::Simd` of their element type, except for masks, which wrap a vector
+// of signed integers that are either 0 or -1 so that they convert freely to and from
+// `core::simd::Mask`.
+
+macro_rules! vector_type {
+    ($vec:ident, $elem:ident, $inner:ident, $mask:ident) => {
+        #[derive(Copy, Clone, Default)]
+        #[repr(transparent)]
+        pub struct $vec<const N: usize>(CoreSimd<$inner, N>);
+
+        impl<const N: usize> $vec<N> {
+            #[inline(always)]
+            fn native(slice: &[$elem]) -> &[$inner] {
+                unsafe { slice::from_raw_parts(slice.as_ptr() as *const $inner, slice.len()) }
+            }
+
+            #[inline(always)]
+            fn native_mut(slice: &mut [$elem]) -> &mut [$inner] {
+                unsafe { slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut $inner, slice.len()) }
+            }
+        }
+
+        impl<const N: usize> Simd for $vec<N> {
+            type Elem = $elem;
+            type Mask = $mask<N>;
+
+            const LANES: usize = N;
+
+            #[inline(always)]
+            fn new(elem: Self::Elem) -> Self {
+                $vec(CoreSimd::splat(Self::native(slice::from_ref(&elem))[0]))
+            }
+
+            #[inline]
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
+            }
+
+            #[inline(always)]
+            fn from_slice(slice: &[Self::Elem]) -> Self {
+                assert!(slice.len() == N);
+                $vec(CoreSimd::from_slice(Self::native(slice)))
+            }
+
+            #[inline(always)]
+            fn write_to_slice(&self, slice: &mut [Self::Elem]) {
+                assert!(slice.len() == N);
+                self.0.copy_to_slice(Self::native_mut(slice));
+            }
+
+            #[inline(always)]
+            fn load_or(slice: &[Self::Elem], or: Self) -> Self {
+                $vec(CoreSimd::load_or(Self::native(slice), or.0))
+            }
+
+            #[inline(always)]
+            fn store_partial(&self, slice: &mut [Self::Elem]) {
+                self.0
+                    .store_select(Self::native_mut(slice), CoreMask::splat(true));
+            }
+
+            #[inline(always)]
+            fn load_masked(slice: &[Self::Elem], mask: Self::Mask) -> Self {
+                check_mask_len(slice.len(), mask);
+                $vec(CoreSimd::load_select_or_default(
+                    Self::native(slice),
+                    mask.to_native(),
+                ))
+            }
+
+            #[inline(always)]
+            fn store_masked(&self, slice: &mut [Self::Elem], mask: Self::Mask) {
+                check_mask_len(slice.len(), mask);
+                self.0
+                    .store_select(Self::native_mut(slice), mask.to_native());
+            }
+        }
+
+        impl<const N: usize> Array for $vec<N> {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe { slice::from_raw_parts(self as *const Self as *const Self::Elem, N) }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe { slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, N) }
+            }
+
+            #[inline]
+            fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
+                unsafe { slice.align_to::<Self>() }
+            }
+
+            #[inline]
+            fn align_mut_slice(
+                slice: &mut [Self::Elem],
+            ) -> (&mut [Self::Elem], &mut [Self], &mut [Self::Elem]) {
+                unsafe { slice.align_to_mut::<Self>() }
+            }
+        }
+
+        impl<const N: usize> Debug for $vec<N> {
+            #[inline]
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Debug::fmt(self.as_slice(), fmt)
+            }
+        }
+
+        impl<const N: usize> LanesEq for $vec<N> {
+            type Output = $mask<N>;
+
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> Self::Output {
+                $mask::from_native(self.0.simd_eq(other.0))
+            }
+        }
+
+        impl<const N: usize> Index<usize> for $vec<N> {
+            type Output = $elem;
+
+            #[inline]
+            fn index(&self, index: usize) -> &$elem {
+                &self.as_slice()[index]
+            }
+        }
+
+        impl<const N: usize> IndexMut<usize> for $vec<N> {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut $elem {
+                &mut self.as_mut_slice()[index]
+            }
+        }
+
+        impl<const N: usize> Select<$vec<N>> for $mask<N> {
+            #[inline(always)]
+            fn select(self, if_true: $vec<N>, if_false: $vec<N>) -> $vec<N> {
+                $vec(self.to_native().select(if_true.0, if_false.0))
+            }
+        }
+
+        impl<const N: usize> Shuffle for $vec<N> {
+            #[inline(always)]
+            fn reverse(self) -> Self {
+                $vec(self.0.reverse())
+            }
+
+            #[inline(always)]
+            fn rotate_lanes_left<const OFFSET: usize>(self) -> Self {
+                $vec(self.0.rotate_elements_left::<OFFSET>())
+            }
+
+            #[inline(always)]
+            fn rotate_lanes_right<const OFFSET: usize>(self) -> Self {
+                $vec(self.0.rotate_elements_right::<OFFSET>())
+            }
+
+            #[inline(always)]
+            fn interleave_lo(self, other: Self) -> Self {
+                $vec(self.0.interleave(other.0).0)
+            }
+
+            #[inline(always)]
+            fn interleave_hi(self, other: Self) -> Self {
+                $vec(self.0.interleave(other.0).1)
+            }
+
+            #[inline(always)]
+            fn deinterleave(self, other: Self) -> (Self, Self) {
+                let (even, odd) = self.0.deinterleave(other.0);
+                ($vec(even), $vec(odd))
+            }
+        }
+    };
+}
+
+macro_rules! impl_float {
+    ($float:ident, $bits:ident, $mask:ident) => {
+        impl<const N: usize> LanesOrd for $float<N> {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                $mask::from_native(self.0.simd_lt(other.0))
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                $mask::from_native(self.0.simd_le(other.0))
+            }
+        }
+
+        impl<const N: usize> Float for $float<N> {
+            type Bits = $bits<N>;
+
+            #[inline(always)]
+            fn to_bits(self) -> Self::Bits {
+                $bits(self.0.to_bits())
+            }
+
+            #[inline(always)]
+            fn from_bits(bits: Self::Bits) -> Self {
+                $float(CoreSimd::from_bits(bits.0))
+            }
+
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                // StdFloat::mul_add is always fused, which is a libm call per lane on targets
+                // without FMA
+                self * a + b
+            }
+
+            #[inline(always)]
+            fn sqrt(self) -> Self {
+                $float(self.0.sqrt())
+            }
+
+            #[inline(always)]
+            fn recip(self) -> Self {
+                $float(self.0.recip())
+            }
+
+            #[inline(always)]
+            fn recip_approx(self) -> Self {
+                self.recip()
+            }
+
+            #[inline(always)]
+            fn rsqrt(self) -> Self {
+                $float(self.0.sqrt().recip())
+            }
+
+            #[inline(always)]
+            fn rsqrt_approx(self) -> Self {
+                self.rsqrt()
+            }
+
+            #[inline(always)]
+            fn abs(self) -> Self {
+                $float(self.0.abs())
+            }
+
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self {
+                $float(self.0.copysign(sign.0))
+            }
+
+            #[inline(always)]
+            fn signum(self) -> Self {
+                $float(self.0.signum())
+            }
+
+            #[inline(always)]
+            fn floor(self) -> Self {
+                $float(self.0.floor())
+            }
+
+            #[inline(always)]
+            fn ceil(self) -> Self {
+                $float(self.0.ceil())
+            }
+
+            #[inline(always)]
+            fn round(self) -> Self {
+                $float(self.0.round())
+            }
+
+            #[inline(always)]
+            fn trunc(self) -> Self {
+                $float(self.0.trunc())
+            }
+
+            #[inline(always)]
+            fn fract(self) -> Self {
+                $float(self.0.fract())
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                self.0.reduce_sum()
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                self.0.reduce_product()
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.0.reduce_min()
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.0.reduce_max()
+            }
+        }
+
+        impl<const N: usize> Add for $float<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                $float(self.0 + rhs.0)
+            }
+        }
+
+        impl<const N: usize> AddAssign for $float<N> {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl<const N: usize> Sub for $float<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self {
+                $float(self.0 - rhs.0)
+            }
+        }
+
+        impl<const N: usize> SubAssign for $float<N> {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl<const N: usize> Mul for $float<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                $float(self.0 * rhs.0)
+            }
+        }
+
+        impl<const N: usize> MulAssign for $float<N> {
+            #[inline(always)]
+            fn mul_assign(&mut self, rhs: Self) {
+                self.0 *= rhs.0;
+            }
+        }
+
+        impl<const N: usize> Div for $float<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                $float(self.0 / rhs.0)
+            }
+        }
+
+        impl<const N: usize> DivAssign for $float<N> {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: Self) {
+                self.0 /= rhs.0;
+            }
+        }
+
+        impl<const N: usize> Neg for $float<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self {
+                $float(-self.0)
+            }
+        }
+    };
+}
+
+macro_rules! impl_int {
+    ($int:ident, $elem:ident, $uint:ident, $signed:ident, $mask:ident, $mulhi:ident) => {
+        impl<const N: usize> $int<N> {
+            #[inline(always)]
+            fn splat(elem: $elem) -> CoreSimd<$elem, N> {
+                CoreSimd::splat(elem)
+            }
+
+            /// Replaces zero divisors, which panic in `core::simd`, with 1, and returns a mask of
+            /// where they were. `MIN / -1` already wraps.
+            #[inline(always)]
+            fn nonzero_divisor(rhs: Self) -> (CoreSimd<$elem, N>, CoreMask<$signed, N>) {
+                let zero = rhs.0.simd_eq(Self::splat(0));
+                (zero.select(Self::splat(1), rhs.0), zero)
+            }
+        }
+
+        impl<const N: usize> LanesOrd for $int<N> {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                $mask::from_native(self.0.simd_lt(other.0))
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                $mask::from_native(self.0.simd_le(other.0))
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                $int(self.0.simd_max(other.0))
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                $int(self.0.simd_min(other.0))
+            }
+        }
+
+        impl<const N: usize> Int for $int<N> {
+            #[inline(always)]
+            fn saturating_add(self, rhs: Self) -> Self {
+                $int(self.0.saturating_add(rhs.0))
+            }
+
+            #[inline(always)]
+            fn saturating_sub(self, rhs: Self) -> Self {
+                $int(self.0.saturating_sub(rhs.0))
+            }
+
+            #[inline(always)]
+            fn avg(self, rhs: Self) -> Self {
+                // (a | b) - ((a ^ b) >> 1) rounds the average up without overflowing
+                $int((self.0 | rhs.0) - ((self.0 ^ rhs.0) >> Self::splat(1)))
+            }
+
+            #[inline(always)]
+            fn mulhi(self, rhs: Self) -> Self {
+                $int($mulhi(self.0, rhs.0))
+            }
+
+            #[inline(always)]
+            fn shl_by(self, counts: Self) -> Self {
+                let counts = counts.0 & Self::splat(($elem::BITS - 1) as $elem);
+                $int(self.0 << counts)
+            }
+
+            #[inline(always)]
+            fn shr_by(self, counts: Self) -> Self {
+                let counts = counts.0 & Self::splat(($elem::BITS - 1) as $elem);
+                $int(self.0 >> counts)
+            }
+
+            #[inline(always)]
+            fn rotate_left(self, n: usize) -> Self {
+                let bits = $elem::BITS as usize;
+                let left = n & (bits - 1);
+                let right = (bits - left) & (bits - 1);
+                // Shift right logically, even for signed types
+                let vec = self.0.cast::<$uint>();
+                let res = (vec << CoreSimd::splat(left as $uint))
+                    | (vec >> CoreSimd::splat(right as $uint));
+                $int(res.cast())
+            }
+
+            #[inline(always)]
+            fn rotate_right(self, n: usize) -> Self {
+                let bits = $elem::BITS as usize;
+                self.rotate_left(bits - (n & (bits - 1)))
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                self.0.reduce_sum()
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                self.0.reduce_product()
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.0.reduce_min()
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.0.reduce_max()
+            }
+        }
+
+        impl<const N: usize> Bitwise for $int<N> {
+            #[inline(always)]
+            fn reduce_and(self) -> Self::Elem {
+                self.0.reduce_and()
+            }
+
+            #[inline(always)]
+            fn reduce_or(self) -> Self::Elem {
+                self.0.reduce_or()
+            }
+
+            #[inline(always)]
+            fn reduce_xor(self) -> Self::Elem {
+                self.0.reduce_xor()
+            }
+        }
+
+        impl<const N: usize> Add for $int<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                $int(self.0 + rhs.0)
+            }
+        }
+
+        impl<const N: usize> AddAssign for $int<N> {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl<const N: usize> Sub for $int<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self {
+                $int(self.0 - rhs.0)
+            }
+        }
+
+        impl<const N: usize> SubAssign for $int<N> {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl<const N: usize> Mul for $int<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                $int(self.0 * rhs.0)
+            }
+        }
+
+        impl<const N: usize> MulAssign for $int<N> {
+            #[inline(always)]
+            fn mul_assign(&mut self, rhs: Self) {
+                self.0 *= rhs.0;
+            }
+        }
+
+        impl<const N: usize> Div for $int<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                let (divisor, zero) = Self::nonzero_divisor(rhs);
+                $int(zero.select(Self::splat(0), self.0 / divisor))
+            }
+        }
+
+        impl<const N: usize> DivAssign for $int<N> {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl<const N: usize> Rem for $int<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn rem(self, rhs: Self) -> Self {
+                let (divisor, zero) = Self::nonzero_divisor(rhs);
+                $int(zero.select(self.0, self.0 % divisor))
+            }
+        }
+
+        impl<const N: usize> RemAssign for $int<N> {
+            #[inline(always)]
+            fn rem_assign(&mut self, rhs: Self) {
+                *self = *self % rhs;
+            }
+        }
+
+        impl<const N: usize> Neg for $int<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self {
+                $int(Self::splat(0) - self.0)
+            }
+        }
+
+        impl<const N: usize> Shl<usize> for $int<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn shl(self, rhs: usize) -> Self {
+                let shift = rhs & ($elem::BITS as usize - 1);
+                $int(self.0 << Self::splat(shift as $elem))
+            }
+        }
+
+        impl<const N: usize> ShlAssign<usize> for $int<N> {
+            #[inline(always)]
+            fn shl_assign(&mut self, rhs: usize) {
+                *self = *self << rhs;
+            }
+        }
+
+        impl<const N: usize> Shr<usize> for $int<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn shr(self, rhs: usize) -> Self {
+                let shift = rhs & ($elem::BITS as usize - 1);
+                $int(self.0 >> Self::splat(shift as $elem))
+            }
+        }
+
+        impl<const N: usize> ShrAssign<usize> for $int<N> {
+            #[inline(always)]
+            fn shr_assign(&mut self, rhs: usize) {
+                *self = *self >> rhs;
+            }
+        }
+    };
+}
+
+macro_rules! impl_bitwise_ops {
+    ($bitwise:ident) => {
+        impl<const N: usize> BitAnd for $bitwise<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitand(self, rhs: Self) -> Self::Output {
+                $bitwise(self.0 & rhs.0)
+            }
+        }
+
+        impl<const N: usize> BitAndAssign for $bitwise<N> {
+            #[inline(always)]
+            fn bitand_assign(&mut self, rhs: Self) {
+                self.0 &= rhs.0;
+            }
+        }
+
+        impl<const N: usize> BitOr for $bitwise<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitor(self, rhs: Self) -> Self::Output {
+                $bitwise(self.0 | rhs.0)
+            }
+        }
+
+        impl<const N: usize> BitOrAssign for $bitwise<N> {
+            #[inline(always)]
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl<const N: usize> BitXor for $bitwise<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitxor(self, rhs: Self) -> Self::Output {
+                $bitwise(self.0 ^ rhs.0)
+            }
+        }
+
+        impl<const N: usize> BitXorAssign for $bitwise<N> {
+            #[inline(always)]
+            fn bitxor_assign(&mut self, rhs: Self) {
+                self.0 ^= rhs.0;
+            }
+        }
+
+        impl<const N: usize> Not for $bitwise<N> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn not(self) -> Self::Output {
+                $bitwise(!self.0)
+            }
+        }
+    };
+}
+
+macro_rules! impl_mask {
+    ($mask:ident, $inner:ident) => {
+        impl<const N: usize> $mask<N> {
+            #[inline(always)]
+            fn from_native(mask: CoreMask<$inner, N>) -> Self {
+                $mask(mask.to_simd())
+            }
+
+            #[inline(always)]
+            fn to_native(self) -> CoreMask<$inner, N> {
+                // Every lane of a mask is either 0 or -1
+                unsafe { CoreMask::from_simd_unchecked(self.0) }
+            }
+        }
+
+        impl<const N: usize> LanesOrd for $mask<N> {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                !*self & *other
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                !*self | *other
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                self | other
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                self & other
+            }
+        }
+
+        impl<const N: usize> Bitwise for $mask<N> {
+            #[inline(always)]
+            fn reduce_and(self) -> Self::Elem {
+                self.to_native().all().into()
+            }
+
+            #[inline(always)]
+            fn reduce_or(self) -> Self::Elem {
+                self.to_native().any().into()
+            }
+
+            #[inline(always)]
+            fn reduce_xor(self) -> Self::Elem {
+                (self.to_bitmask().count_ones() % 2 == 1).into()
+            }
+        }
+
+        impl<const N: usize> Mask for $mask<N> {
+            #[inline(always)]
+            fn to_bitmask(self) -> u64 {
+                self.to_native().to_bitmask()
+            }
+
+            #[inline(always)]
+            fn from_bitmask(bits: u64) -> Self {
+                Self::from_native(CoreMask::from_bitmask(bits))
+            }
+        }
+    };
+}
+
+macro_rules! mulhi_widen {
+    ($name:ident, $elem:ident, $wide:ident) => {
+        #[inline(always)]
+        fn $name<const N: usize>(
+            a: CoreSimd<$elem, N>,
+            b: CoreSimd<$elem, N>,
+        ) -> CoreSimd<$elem, N> {
+            let product = a.cast::<$wide>() * b.cast::<$wide>();
+            (product >> CoreSimd::splat($elem::BITS as $wide)).cast()
+        }
+    };
+}
+
+mulhi_widen! { mulhi_u8, u8, u16 }
+mulhi_widen! { mulhi_u16, u16, u32 }
+mulhi_widen! { mulhi_u32, u32, u64 }
+mulhi_widen! { mulhi_i8, i8, i16 }
+mulhi_widen! { mulhi_i16, i16, i32 }
+mulhi_widen! { mulhi_i32, i32, i64 }
+
+#[inline(always)]
+fn mulhi_u64<const N: usize>(a: CoreSimd<u64, N>, b: CoreSimd<u64, N>) -> CoreSimd<u64, N> {
+    // Sum the four 32x32-bit partial products
+    let mask = CoreSimd::splat(0xFFFFFFFF);
+    let shift = CoreSimd::splat(32);
+    let (a_low, a_high) = (a & mask, a >> shift);
+    let (b_low, b_high) = (b & mask, b >> shift);
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    let high_high = a_high * b_high;
+    // The middle column cannot overflow, as it is a sum of three 32-bit values
+    let middle = (low_low >> shift) + (low_high & mask) + (high_low & mask);
+    high_high + (low_high >> shift) + (high_low >> shift) + (middle >> shift)
+}
+
+#[inline(always)]
+fn mulhi_i64<const N: usize>(a: CoreSimd<i64, N>, b: CoreSimd<i64, N>) -> CoreSimd<i64, N> {
+    // Correct the unsigned product for negative inputs, each of which contributes an extra 2^64
+    // times the other input
+    let high = mulhi_u64(a.cast(), b.cast()).cast::<i64>();
+    let shift = CoreSimd::splat(63);
+    high - ((a >> shift) & b) - ((b >> shift) & a)
+}
+
+macro_rules! impl_cast {
+    ($from:ident, $to:ident) => {
+        impl<const N: usize> Cast<$to<N>> for $from<N> {
+            #[inline(always)]
+            fn cast(self) -> $to<N> {
+                $to(self.0.cast())
+            }
+        }
+    };
+}
+
+macro_rules! impl_round_cast {
+    ($from:ident, $to:ident) => {
+        impl<const N: usize> RoundCast<$to<N>> for $from<N> {
+            #[inline(always)]
+            fn round_cast(self) -> $to<N> {
+                $to(self.0.round_ties_even().cast())
+            }
+        }
+    };
+}
+
+macro_rules! impl_widen {
+    ($narrow:ident, $wide:ident) => {
+        impl<const N: usize> Widen for $narrow<N> {
+            type Wide = $wide<N>;
+            type Parts = [$wide<N>; 1];
+
+            #[inline(always)]
+            fn widen(self) -> Self::Parts {
+                [$wide(self.0.cast())]
+            }
+
+            #[inline(always)]
+            fn narrow(parts: Self::Parts) -> Self {
+                $narrow(parts[0].0.cast())
+            }
+        }
+    };
+}
+
+macro_rules! impl_gather {
+    ($vec:ident, $($index:ident),*) => {
+        $(
+            impl<const N: usize> Gather<$index<N>> for $vec<N> {
+                type Index = $index<N>;
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+
+                #[inline(always)]
+                unsafe fn gather_unchecked(base: &[Self::Elem], index: $index<N>) -> Self {
+                    let enable = CoreMask::splat(true);
+                    $vec(CoreSimd::gather_select_unchecked(base, enable, index.0.cast(), CoreSimd::default()))
+                }
+
+                #[inline(always)]
+                unsafe fn gather_masked_unchecked(
+                    base: &[Self::Elem],
+                    index: $index<N>,
+                    mask: Self::Mask,
+                    or: Self,
+                ) -> Self {
+                    let enable = mask.to_native().cast();
+                    $vec(CoreSimd::gather_select_unchecked(base, enable, index.0.cast(), or.0))
+                }
+
+                #[inline(always)]
+                unsafe fn scatter_unchecked(values: Self, base: &mut [Self::Elem], index: $index<N>) {
+                    // Scatters write lanes in order, so the highest lane wins for repeated indices
+                    let enable = CoreMask::splat(true);
+                    values.0.scatter_select_unchecked(base, enable, index.0.cast());
+                }
+
+                #[inline(always)]
+                unsafe fn scatter_masked_unchecked(
+                    values: Self,
+                    base: &mut [Self::Elem],
+                    index: $index<N>,
+                    mask: Self::Mask,
+                ) {
+                    let enable = mask.to_native().cast();
+                    values.0.scatter_select_unchecked(base, enable, index.0.cast());
+                }
+            }
+        )*
+    };
+}
+
+vector_type! { f32xN, f32, f32, m32xN }
+vector_type! { f64xN, f64, f64, m64xN }
+impl_float! { f32xN, u32xN, m32xN }
+impl_float! { f64xN, u64xN, m64xN }
+impl_cast! { f32xN, i32xN }
+impl_cast! { f32xN, u32xN }
+impl_cast! { f64xN, i64xN }
+impl_cast! { f64xN, u64xN }
+impl_round_cast! { f32xN, i32xN }
+impl_round_cast! { f32xN, u32xN }
+impl_round_cast! { f64xN, i64xN }
+impl_round_cast! { f64xN, u64xN }
+impl_widen! { f32xN, f64xN }
+impl_gather! { f32xN, u32xN, i32xN }
+impl_gather! { f64xN, u64xN, i64xN, u32xN, i32xN }
+
+vector_type! { u8xN, u8, u8, m8xN }
+vector_type! { u16xN, u16, u16, m16xN }
+vector_type! { u32xN, u32, u32, m32xN }
+vector_type! { u64xN, u64, u64, m64xN }
+impl_int! { u8xN, u8, u8, i8, m8xN, mulhi_u8 }
+impl_int! { u16xN, u16, u16, i16, m16xN, mulhi_u16 }
+impl_int! { u32xN, u32, u32, i32, m32xN, mulhi_u32 }
+impl_int! { u64xN, u64, u64, i64, m64xN, mulhi_u64 }
+impl_bitwise_ops! { u8xN }
+impl_bitwise_ops! { u16xN }
+impl_bitwise_ops! { u32xN }
+impl_bitwise_ops! { u64xN }
+impl_cast! { u8xN, i8xN }
+impl_cast! { u16xN, i16xN }
+impl_cast! { u32xN, i32xN }
+impl_cast! { u32xN, f32xN }
+impl_cast! { u64xN, i64xN }
+impl_cast! { u64xN, f64xN }
+impl_widen! { u8xN, u16xN }
+impl_widen! { u16xN, u32xN }
+impl_widen! { u32xN, u64xN }
+impl_gather! { u8xN, u8xN, i8xN, u32xN, i32xN }
+impl_gather! { u16xN, u16xN, i16xN, u32xN, i32xN }
+impl_gather! { u32xN, u32xN, i32xN }
+impl_gather! { u64xN, u64xN, i64xN, u32xN, i32xN }
+
+vector_type! { i8xN, i8, i8, m8xN }
+vector_type! { i16xN, i16, i16, m16xN }
+vector_type! { i32xN, i32, i32, m32xN }
+vector_type! { i64xN, i64, i64, m64xN }
+impl_int! { i8xN, i8, u8, i8, m8xN, mulhi_i8 }
+impl_int! { i16xN, i16, u16, i16, m16xN, mulhi_i16 }
+impl_int! { i32xN, i32, u32, i32, m32xN, mulhi_i32 }
+impl_int! { i64xN, i64, u64, i64, m64xN, mulhi_i64 }
+impl_bitwise_ops! { i8xN }
+impl_bitwise_ops! { i16xN }
+impl_bitwise_ops! { i32xN }
+impl_bitwise_ops! { i64xN }
+impl_cast! { i8xN, u8xN }
+impl_cast! { i16xN, u16xN }
+impl_cast! { i32xN, u32xN }
+impl_cast! { i32xN, f32xN }
+impl_cast! { i64xN, u64xN }
+impl_cast! { i64xN, f64xN }
+impl_widen! { i8xN, i16xN }
+impl_widen! { i16xN, i32xN }
+impl_widen! { i32xN, i64xN }
+impl_gather! { i8xN, u8xN, i8xN, u32xN, i32xN }
+impl_gather! { i16xN, u16xN, i16xN, u32xN, i32xN }
+impl_gather! { i32xN, u32xN, i32xN }
+impl_gather! { i64xN, u64xN, i64xN, u32xN, i32xN }
+
+vector_type! { m8xN, m8, i8, m8xN }
+vector_type! { m16xN, m16, i16, m16xN }
+vector_type! { m32xN, m32, i32, m32xN }
+vector_type! { m64xN, m64, i64, m64xN }
+impl_bitwise_ops! { m8xN }
+impl_bitwise_ops! { m16xN }
+impl_bitwise_ops! { m32xN }
+impl_bitwise_ops! { m64xN }
+impl_mask! { m8xN, i8 }
+impl_mask! { m16xN, i16 }
+impl_mask! { m32xN, i32 }
+impl_mask! { m64xN, i64 }
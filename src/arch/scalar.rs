@@ -5,11 +5,15 @@ use core::num::Wrapping;
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
 use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
 use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
 use core::slice;
 
 use crate::mask::*;
-use crate::simd::{Bitwise, Float, Int, LanesEq, LanesOrd, Select, Simd};
+use crate::simd::{
+    Array, Bitwise, Cast, Float, Gather, Int, LanesEq, LanesOrd, Mask, RoundCast, Select, Shuffle,
+    Simd, Widen,
+};
 use crate::{Arch, Task};
 
 pub struct ScalarImpl;
@@ -47,6 +51,8 @@ macro_rules! scalar_type {
         #[repr(transparent)]
         pub struct $scalar($inner);
 
+        impl Shuffle for $scalar {}
+
         impl Simd for $scalar {
             type Elem = $inner;
             type Mask = $mask;
@@ -59,13 +65,8 @@ macro_rules! scalar_type {
             }
 
             #[inline]
-            fn as_slice(&self) -> &[Self::Elem] {
-                slice::from_ref(&self.0)
-            }
-
-            #[inline]
-            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
-                slice::from_mut(&mut self.0)
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
             }
 
             #[inline]
@@ -77,6 +78,18 @@ macro_rules! scalar_type {
             fn write_to_slice(&self, slice: &mut [Self::Elem]) {
                 slice[0] = self.0;
             }
+        }
+
+        impl Array for $scalar {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                slice::from_ref(&self.0)
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                slice::from_mut(&mut self.0)
+            }
 
             #[inline]
             fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
@@ -151,6 +164,8 @@ macro_rules! wrapping_scalar_type {
         #[repr(transparent)]
         pub struct $scalar(Wrapping<$inner>);
 
+        impl Shuffle for $scalar {}
+
         impl Simd for $scalar {
             type Elem = $inner;
             type Mask = $mask;
@@ -163,13 +178,8 @@ macro_rules! wrapping_scalar_type {
             }
 
             #[inline]
-            fn as_slice(&self) -> &[Self::Elem] {
-                slice::from_ref(&self.0 .0)
-            }
-
-            #[inline]
-            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
-                slice::from_mut(&mut self.0 .0)
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
             }
 
             #[inline]
@@ -181,6 +191,18 @@ macro_rules! wrapping_scalar_type {
             fn write_to_slice(&self, slice: &mut [Self::Elem]) {
                 slice[0] = self.0 .0;
             }
+        }
+
+        impl Array for $scalar {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                slice::from_ref(&self.0 .0)
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                slice::from_mut(&mut self.0 .0)
+            }
 
             #[inline]
             fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
@@ -250,8 +272,110 @@ macro_rules! wrapping_scalar_type {
 }
 
 macro_rules! impl_float {
-    ($float:ident) => {
-        impl Float for $float {}
+    ($float:ident, $bits:ident) => {
+        impl Float for $float {
+            type Bits = $bits;
+
+            #[inline]
+            fn to_bits(self) -> Self::Bits {
+                $bits::new(self.0.to_bits())
+            }
+
+            #[inline]
+            fn from_bits(bits: Self::Bits) -> Self {
+                $float::new(<$float as Simd>::Elem::from_bits(bits[0]))
+            }
+
+            #[inline]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                self * a + b
+            }
+
+            #[inline]
+            fn sqrt(self) -> Self {
+                $float(self.0.sqrt())
+            }
+
+            #[inline]
+            fn recip(self) -> Self {
+                $float(self.0.recip())
+            }
+
+            #[inline]
+            fn recip_approx(self) -> Self {
+                self.recip()
+            }
+
+            #[inline]
+            fn rsqrt(self) -> Self {
+                $float(self.0.sqrt().recip())
+            }
+
+            #[inline]
+            fn rsqrt_approx(self) -> Self {
+                self.rsqrt()
+            }
+
+            #[inline]
+            fn abs(self) -> Self {
+                $float(self.0.abs())
+            }
+
+            #[inline]
+            fn copysign(self, sign: Self) -> Self {
+                $float(self.0.copysign(sign.0))
+            }
+
+            #[inline]
+            fn signum(self) -> Self {
+                $float(self.0.signum())
+            }
+
+            #[inline]
+            fn floor(self) -> Self {
+                $float(self.0.floor())
+            }
+
+            #[inline]
+            fn ceil(self) -> Self {
+                $float(self.0.ceil())
+            }
+
+            #[inline]
+            fn round(self) -> Self {
+                $float(self.0.round())
+            }
+
+            #[inline]
+            fn trunc(self) -> Self {
+                $float(self.0.trunc())
+            }
+
+            #[inline]
+            fn fract(self) -> Self {
+                $float(self.0.fract())
+            }
+
+            #[inline]
+            fn reduce_sum(self) -> Self::Elem {
+                self[0]
+            }
+
+            #[inline]
+            fn reduce_product(self) -> Self::Elem {
+                self[0]
+            }
+
+            #[inline]
+            fn reduce_min(self) -> Self::Elem {
+                self[0]
+            }
+
+            #[inline]
+            fn reduce_max(self) -> Self::Elem {
+                self[0]
+            }
+        }
 
         impl Add for $float {
             type Output = Self;
@@ -329,8 +453,69 @@ macro_rules! impl_float {
 }
 
 macro_rules! impl_int {
-    ($int:ident) => {
-        impl Int for $int {}
+    ($int:ident, $elem:ident, $wide:ident) => {
+        impl Int for $int {
+            #[inline]
+            fn saturating_add(self, rhs: Self) -> Self {
+                $int::new(self[0].saturating_add(rhs[0]))
+            }
+
+            #[inline]
+            fn saturating_sub(self, rhs: Self) -> Self {
+                $int::new(self[0].saturating_sub(rhs[0]))
+            }
+
+            #[inline]
+            fn avg(self, rhs: Self) -> Self {
+                $int::new(((self[0] as $wide + rhs[0] as $wide + 1) >> 1) as $elem)
+            }
+
+            #[inline]
+            fn mulhi(self, rhs: Self) -> Self {
+                let product = self[0] as $wide * rhs[0] as $wide;
+                $int::new((product >> $elem::BITS) as $elem)
+            }
+
+            #[inline]
+            fn shl_by(self, counts: Self) -> Self {
+                $int(self.0 << counts[0] as usize)
+            }
+
+            #[inline]
+            fn shr_by(self, counts: Self) -> Self {
+                $int(self.0 >> counts[0] as usize)
+            }
+
+            #[inline]
+            fn rotate_left(self, n: usize) -> Self {
+                $int::new(self[0].rotate_left(n as u32))
+            }
+
+            #[inline]
+            fn rotate_right(self, n: usize) -> Self {
+                $int::new(self[0].rotate_right(n as u32))
+            }
+
+            #[inline]
+            fn reduce_sum(self) -> Self::Elem {
+                self[0]
+            }
+
+            #[inline]
+            fn reduce_product(self) -> Self::Elem {
+                self[0]
+            }
+
+            #[inline]
+            fn reduce_min(self) -> Self::Elem {
+                self[0]
+            }
+
+            #[inline]
+            fn reduce_max(self) -> Self::Elem {
+                self[0]
+            }
+        }
 
         impl Add for $int {
             type Output = Self;
@@ -385,14 +570,38 @@ macro_rules! impl_int {
 
             #[inline]
             fn div(self, rhs: Self) -> Self {
-                $int(self.0 / rhs.0)
+                if rhs[0] == 0 {
+                    $int::new(0)
+                } else {
+                    $int(self.0 / rhs.0)
+                }
             }
         }
 
         impl DivAssign for $int {
             #[inline]
             fn div_assign(&mut self, rhs: Self) {
-                self.0 /= rhs.0;
+                *self = *self / rhs;
+            }
+        }
+
+        impl Rem for $int {
+            type Output = Self;
+
+            #[inline]
+            fn rem(self, rhs: Self) -> Self {
+                if rhs[0] == 0 {
+                    self
+                } else {
+                    $int(self.0 % rhs.0)
+                }
+            }
+        }
+
+        impl RemAssign for $int {
+            #[inline]
+            fn rem_assign(&mut self, rhs: Self) {
+                *self = *self % rhs;
             }
         }
 
@@ -441,7 +650,22 @@ macro_rules! impl_int {
 
 macro_rules! impl_bitwise {
     ($bitwise:ident) => {
-        impl Bitwise for $bitwise {}
+        impl Bitwise for $bitwise {
+            #[inline]
+            fn reduce_and(self) -> Self::Elem {
+                self[0]
+            }
+
+            #[inline]
+            fn reduce_or(self) -> Self::Elem {
+                self[0]
+            }
+
+            #[inline]
+            fn reduce_xor(self) -> Self::Elem {
+                self[0]
+            }
+        }
 
         impl BitAnd for $bitwise {
             type Output = Self;
@@ -502,36 +726,142 @@ macro_rules! impl_bitwise {
     };
 }
 
+macro_rules! impl_mask {
+    ($mask:ident) => {
+        impl Mask for $mask {
+            #[inline]
+            fn to_bitmask(self) -> u64 {
+                bool::from(self.0) as u64
+            }
+
+            #[inline]
+            fn from_bitmask(bits: u64) -> Self {
+                $mask((bits & 1 != 0).into())
+            }
+        }
+    };
+}
+
+macro_rules! impl_cast {
+    ($from:ident, $to:ident, $elem:ident) => {
+        impl Cast<$to> for $from {
+            #[inline]
+            fn cast(self) -> $to {
+                $to::new(self[0] as $elem)
+            }
+        }
+    };
+}
+
+macro_rules! impl_round_cast {
+    ($from:ident, $to:ident, $elem:ident) => {
+        impl RoundCast<$to> for $from {
+            #[inline]
+            fn round_cast(self) -> $to {
+                $to::new(self[0].round_ties_even() as $elem)
+            }
+        }
+    };
+}
+
+macro_rules! impl_widen {
+    ($narrow:ident, $elem:ident, $wide:ident, $wide_elem:ident) => {
+        impl Widen for $narrow {
+            type Wide = $wide;
+            type Parts = [$wide; 1];
+
+            #[inline]
+            fn widen(self) -> Self::Parts {
+                [$wide::new(self[0] as $wide_elem)]
+            }
+
+            #[inline]
+            fn narrow(parts: Self::Parts) -> Self {
+                $narrow::new(parts[0][0] as $elem)
+            }
+        }
+    };
+}
+
+macro_rules! impl_gather {
+    ($vec:ident, $($index:ident),*) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = $index;
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+}
+
 scalar_type! { f32x1, f32, m32x1 }
 scalar_type! { f64x1, f64, m64x1 }
-impl_float! { f64x1 }
-impl_float! { f32x1 }
+impl_float! { f64x1, u64x1 }
+impl_float! { f32x1, u32x1 }
+impl_cast! { f32x1, i32x1, i32 }
+impl_cast! { f32x1, u32x1, u32 }
+impl_cast! { f64x1, i64x1, i64 }
+impl_cast! { f64x1, u64x1, u64 }
+impl_round_cast! { f32x1, i32x1, i32 }
+impl_round_cast! { f32x1, u32x1, u32 }
+impl_round_cast! { f64x1, i64x1, i64 }
+impl_round_cast! { f64x1, u64x1, u64 }
+impl_widen! { f32x1, f32, f64x1, f64 }
+impl_gather! { f32x1, u32x1, i32x1 }
+impl_gather! { f64x1, u64x1, i64x1, u32x1, i32x1 }
 
 wrapping_scalar_type! { u8x1, u8, m8x1 }
 wrapping_scalar_type! { u16x1, u16, m16x1 }
 wrapping_scalar_type! { u32x1, u32, m32x1 }
 wrapping_scalar_type! { u64x1, u64, m64x1 }
-impl_int! { u8x1 }
-impl_int! { u16x1 }
-impl_int! { u32x1 }
-impl_int! { u64x1 }
+impl_int! { u8x1, u8, u16 }
+impl_int! { u16x1, u16, u32 }
+impl_int! { u32x1, u32, u64 }
+impl_int! { u64x1, u64, u128 }
 impl_bitwise! { u8x1 }
 impl_bitwise! { u16x1 }
 impl_bitwise! { u32x1 }
 impl_bitwise! { u64x1 }
+impl_cast! { u8x1, i8x1, i8 }
+impl_cast! { u16x1, i16x1, i16 }
+impl_cast! { u32x1, i32x1, i32 }
+impl_cast! { u32x1, f32x1, f32 }
+impl_cast! { u64x1, i64x1, i64 }
+impl_cast! { u64x1, f64x1, f64 }
+impl_widen! { u8x1, u8, u16x1, u16 }
+impl_widen! { u16x1, u16, u32x1, u32 }
+impl_widen! { u32x1, u32, u64x1, u64 }
+impl_gather! { u8x1, u8x1, i8x1, u32x1, i32x1 }
+impl_gather! { u16x1, u16x1, i16x1, u32x1, i32x1 }
+impl_gather! { u32x1, u32x1, i32x1 }
+impl_gather! { u64x1, u64x1, i64x1, u32x1, i32x1 }
 
 wrapping_scalar_type! { i8x1, i8, m8x1 }
 wrapping_scalar_type! { i16x1, i16, m16x1 }
 wrapping_scalar_type! { i32x1, i32, m32x1 }
 wrapping_scalar_type! { i64x1, i64, m64x1 }
-impl_int! { i8x1 }
-impl_int! { i16x1 }
-impl_int! { i32x1 }
-impl_int! { i64x1 }
+impl_int! { i8x1, i8, i16 }
+impl_int! { i16x1, i16, i32 }
+impl_int! { i32x1, i32, i64 }
+impl_int! { i64x1, i64, i128 }
 impl_bitwise! { i8x1 }
 impl_bitwise! { i16x1 }
 impl_bitwise! { i32x1 }
 impl_bitwise! { i64x1 }
+impl_cast! { i8x1, u8x1, u8 }
+impl_cast! { i16x1, u16x1, u16 }
+impl_cast! { i32x1, u32x1, u32 }
+impl_cast! { i32x1, f32x1, f32 }
+impl_cast! { i64x1, u64x1, u64 }
+impl_cast! { i64x1, f64x1, f64 }
+impl_widen! { i8x1, i8, i16x1, i16 }
+impl_widen! { i16x1, i16, i32x1, i32 }
+impl_widen! { i32x1, i32, i64x1, i64 }
+impl_gather! { i8x1, u8x1, i8x1, u32x1, i32x1 }
+impl_gather! { i16x1, u16x1, i16x1, u32x1, i32x1 }
+impl_gather! { i32x1, u32x1, i32x1 }
+impl_gather! { i64x1, u64x1, i64x1, u32x1, i32x1 }
 
 scalar_type! { m8x1, m8, m8x1 }
 scalar_type! { m16x1, m16, m16x1 }
@@ -541,3 +871,7 @@ impl_bitwise! { m8x1 }
 impl_bitwise! { m16x1 }
 impl_bitwise! { m32x1 }
 impl_bitwise! { m64x1 }
+impl_mask! { m8x1 }
+impl_mask! { m16x1 }
+impl_mask! { m32x1 }
+impl_mask! { m64x1 }
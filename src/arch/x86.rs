@@ -1,38 +1,114 @@
-use crate::{Arch, Possible, Supported, Task};
-
-mod avx2;
-mod sse2;
-
-pub struct Sse2;
-
-impl Possible for Sse2 {
-    #[inline]
-    fn supported() -> bool {
-        is_x86_feature_detected!("sse2")
-    }
-
-    #[inline]
-    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
-        sse2::Sse2Impl::invoke(task)
-    }
-}
-
-#[cfg(target_feature = "sse2")]
-unsafe impl Supported for Sse2 {}
-
-pub struct Avx2;
-
-impl Possible for Avx2 {
-    #[inline]
-    fn supported() -> bool {
-        is_x86_feature_detected!("avx2")
-    }
-
-    #[inline]
-    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
-        avx2::Avx2Impl::invoke(task)
-    }
-}
-
-#[cfg(target_feature = "avx2")]
-unsafe impl Supported for Avx2 {}
+use crate::{Arch, Possible, Supported, Task};
+
+mod avx;
+mod avx2;
+mod avx512;
+#[macro_use]
+mod sse2;
+mod sse41;
+
+pub struct Sse2;
+
+impl Possible for Sse2 {
+    const NAME: &'static str = sse2::Sse2Impl::NAME;
+
+    #[inline]
+    fn supported() -> bool {
+        is_x86_feature_detected!("sse2")
+    }
+
+    #[inline]
+    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+        sse2::Sse2Impl::invoke(task)
+    }
+}
+
+#[cfg(target_feature = "sse2")]
+unsafe impl Supported for Sse2 {}
+
+pub struct Sse41;
+
+impl Possible for Sse41 {
+    const NAME: &'static str = sse41::Sse41Impl::NAME;
+
+    #[inline]
+    fn supported() -> bool {
+        is_x86_feature_detected!("sse4.1")
+    }
+
+    #[inline]
+    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+        sse41::Sse41Impl::invoke(task)
+    }
+}
+
+#[cfg(target_feature = "sse4.1")]
+unsafe impl Supported for Sse41 {}
+
+pub struct Avx;
+
+impl Possible for Avx {
+    const NAME: &'static str = avx::AvxImpl::NAME;
+
+    #[inline]
+    fn supported() -> bool {
+        is_x86_feature_detected!("avx")
+    }
+
+    #[inline]
+    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+        avx::AvxImpl::invoke(task)
+    }
+}
+
+#[cfg(target_feature = "avx")]
+unsafe impl Supported for Avx {}
+
+/// AVX2 together with FMA, which every CPU supporting AVX2 also provides in practice.
+pub struct Avx2;
+
+impl Possible for Avx2 {
+    const NAME: &'static str = avx2::Avx2Impl::NAME;
+
+    #[inline]
+    fn supported() -> bool {
+        is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")
+    }
+
+    #[inline]
+    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+        avx2::Avx2Impl::invoke(task)
+    }
+}
+
+#[cfg(all(target_feature = "avx2", target_feature = "fma"))]
+unsafe impl Supported for Avx2 {}
+
+/// AVX-512 Foundation together with the Byte and Word, Doubleword and Quadword, and Vector Length
+/// extensions.
+pub struct Avx512;
+
+impl Possible for Avx512 {
+    const NAME: &'static str = avx512::Avx512Impl::NAME;
+
+    #[inline]
+    fn supported() -> bool {
+        is_x86_feature_detected!("avx512f")
+            && is_x86_feature_detected!("avx512bw")
+            && is_x86_feature_detected!("avx512dq")
+            && is_x86_feature_detected!("avx512vl")
+    }
+
+    #[inline]
+    unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+        avx512::Avx512Impl::invoke(task)
+    }
+}
+
+#[cfg(all(
+    target_feature = "avx512f",
+    target_feature = "avx512bw",
+    target_feature = "avx512dq",
+    target_feature = "avx512vl"
+))]
+unsafe impl Supported for Avx512 {}
@@ -0,0 +1,1345 @@
+#![allow(non_camel_case_types)]
+
+use core::fmt::{self, Debug};
+use core::mem;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
+use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+use core::slice;
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+use super::sse41;
+use crate::mask::*;
+use crate::simd::{
+    check_aligned, check_mask_len, Array, Bitwise, Cast, Float, Gather, Int, LanesEq, LanesOrd,
+    Mask, RoundCast, Select, Shuffle, Simd, Widen,
+};
+use crate::{Arch, Task};
+
+pub struct AvxImpl;
+
+impl Arch for AvxImpl {
+    type f32 = f32x8;
+    type f64 = f64x4;
+
+    type u8 = u8x32;
+    type u16 = u16x16;
+    type u32 = u32x8;
+    type u64 = u64x4;
+
+    type i8 = i8x32;
+    type i16 = i16x16;
+    type i32 = i32x8;
+    type i64 = i64x4;
+
+    type m8 = m8x32;
+    type m16 = m16x16;
+    type m32 = m32x8;
+    type m64 = m64x4;
+
+    const NAME: &'static str = "avx";
+
+    #[inline(always)]
+    fn invoke<T: Task>(task: T) -> T::Result {
+        #[inline]
+        #[target_feature(enable = "avx")]
+        unsafe fn inner<T: Task>(task: T) -> T::Result {
+            task.run::<AvxImpl>()
+        }
+
+        unsafe { inner(task) }
+    }
+
+    #[inline(always)]
+    fn prefetch<T>(ptr: *const T) {
+        #[inline]
+        #[target_feature(enable = "avx")]
+        unsafe fn inner(ptr: *const i8) {
+            _mm_prefetch::<_MM_HINT_T0>(ptr);
+        }
+
+        unsafe { inner(ptr as *const i8) }
+    }
+
+    #[inline(always)]
+    fn stream_fence() {
+        #[inline]
+        #[target_feature(enable = "avx")]
+        unsafe fn inner() {
+            _mm_sfence();
+        }
+
+        unsafe { inner() }
+    }
+}
+
+// AVX only has 256-bit floating-point instructions, so integer and mask vectors are made up of
+// two SSE4.1 vectors (which AVX implies), and float vectors are split into halves or joined back
+// together wherever they meet them.
+
+macro_rules! float_type {
+    (
+        $float:ident, $inner:ident, $elem:ident, $lanes:literal, $half:path,
+        $mask:ident, $mask_half:path, $bits:ident, $bits_half:path,
+        $set:ident, $load:ident, $load_aligned:ident, $store:ident, $stream:ident, $cast_to_int:ident, $cast_from_int:ident,
+        $cast_low:ident, $extract:ident, $set_halves:ident, $blend:ident,
+        $cmp:ident, $max:ident, $min:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $xor:ident,
+        $and:ident, $or:ident, $andnot:ident, $sqrt:ident, $rcp:ident, $rsqrt:ident,
+        $round:ident, $prefix_mask:ident, $maskload:ident, $maskstore:ident,
+    ) => {
+        #[derive(Copy, Clone)]
+        #[repr(transparent)]
+        pub struct $float($inner);
+
+        impl Simd for $float {
+            type Elem = $elem;
+            type Mask = $mask;
+
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            fn new(elem: Self::Elem) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(elem: $elem) -> $float {
+                    $float($set(elem))
+                }
+
+                unsafe { inner(elem) }
+            }
+
+            #[inline]
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
+            }
+
+            #[inline(always)]
+            fn from_slice(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    assert!(slice.len() == <$float as Simd>::LANES);
+                    $float($load(slice.as_ptr()))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn write_to_slice(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    assert!(slice.len() == <$float as Simd>::LANES);
+                    $store(slice.as_mut_ptr(), vec.0);
+                }
+
+                unsafe { inner(self, slice) }
+            }
+
+            #[inline(always)]
+            fn from_slice_aligned(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    check_aligned::<$float>(slice);
+                    $float($load_aligned(slice.as_ptr()))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            unsafe fn store_stream(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    check_aligned::<$float>(slice);
+                    $stream(slice.as_mut_ptr(), vec.0);
+                }
+
+                inner(self, slice);
+            }
+
+            #[inline(always)]
+            fn load_partial(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    $float($maskload(slice.as_ptr(), $prefix_mask(slice.len())))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn load_or(slice: &[Self::Elem], or: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(slice: &[$elem], or: $float) -> $float {
+                    let mask = $prefix_mask(slice.len());
+                    let loaded = $maskload(slice.as_ptr(), mask);
+                    $float($blend(or.0, loaded, $cast_from_int(mask)))
+                }
+
+                unsafe { inner(slice, or) }
+            }
+
+            #[inline(always)]
+            fn store_partial(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    $maskstore(slice.as_mut_ptr(), $prefix_mask(slice.len()), vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice);
+                }
+            }
+
+            #[inline(always)]
+            fn load_masked(slice: &[Self::Elem], mask: Self::Mask) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(slice: &[$elem], mask: <$float as Simd>::Mask) -> $float {
+                    check_mask_len(slice.len(), mask);
+                    $float($maskload(
+                        slice.as_ptr(),
+                        join_si256(mask.0.map(|half| half.0)),
+                    ))
+                }
+
+                unsafe { inner(slice, mask) }
+            }
+
+            #[inline(always)]
+            fn store_masked(&self, slice: &mut [Self::Elem], mask: Self::Mask) {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem], mask: <$float as Simd>::Mask) {
+                    check_mask_len(slice.len(), mask);
+                    let mask = join_si256(mask.0.map(|half| half.0));
+                    $maskstore(slice.as_mut_ptr(), mask, vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice, mask);
+                }
+            }
+        }
+
+        impl Array for $float {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
+                unsafe { slice.align_to::<Self>() }
+            }
+
+            #[inline]
+            fn align_mut_slice(
+                slice: &mut [Self::Elem],
+            ) -> (&mut [Self::Elem], &mut [Self], &mut [Self::Elem]) {
+                unsafe { slice.align_to_mut::<Self>() }
+            }
+        }
+
+        impl Debug for $float {
+            #[inline]
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Debug::fmt(self.as_slice(), fmt)
+            }
+        }
+
+        impl Default for $float {
+            #[inline]
+            fn default() -> Self {
+                unsafe { mem::zeroed() }
+            }
+        }
+
+        impl LanesEq for $float {
+            type Output = $mask;
+
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    let res = $cmp(lhs.0, rhs.0, _CMP_EQ_OQ);
+                    $mask(split_si256($cast_to_int(res)).map($mask_half))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn ne(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    let res = $cmp(lhs.0, rhs.0, _CMP_NEQ_UQ);
+                    $mask(split_si256($cast_to_int(res)).map($mask_half))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        impl LanesOrd for $float {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    let res = $cmp(lhs.0, rhs.0, _CMP_LT_OQ);
+                    $mask(split_si256($cast_to_int(res)).map($mask_half))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    let res = $cmp(lhs.0, rhs.0, _CMP_LE_OQ);
+                    $mask(split_si256($cast_to_int(res)).map($mask_half))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn gt(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    let res = $cmp(lhs.0, rhs.0, _CMP_GT_OQ);
+                    $mask(split_si256($cast_to_int(res)).map($mask_half))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn ge(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    let res = $cmp(lhs.0, rhs.0, _CMP_GE_OQ);
+                    $mask(split_si256($cast_to_int(res)).map($mask_half))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($max(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($min(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        impl Index<usize> for $float {
+            type Output = <Self as Simd>::Elem;
+
+            #[inline]
+            fn index(&self, index: usize) -> &Self::Output {
+                &self.as_slice()[index]
+            }
+        }
+
+        impl IndexMut<usize> for $float {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+                &mut self.as_mut_slice()[index]
+            }
+        }
+
+        impl Select<$float> for $mask {
+            #[inline(always)]
+            fn select(self, if_true: $float, if_false: $float) -> $float {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(mask: $mask, if_true: $float, if_false: $float) -> $float {
+                    let mask = $cast_from_int(join_si256(mask.0.map(|half| half.0)));
+                    $float($blend(if_false.0, if_true.0, mask))
+                }
+
+                unsafe { inner(self, if_true, if_false) }
+            }
+        }
+
+        impl $float {
+            #[inline(always)]
+            fn split(self) -> [$half; 2] {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> [$half; 2] {
+                    [$half($cast_low(vec.0)), $half($extract(vec.0, 1))]
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn join(halves: [$half; 2]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(halves: [$half; 2]) -> $float {
+                    $float($set_halves(halves[1].0, halves[0].0))
+                }
+
+                unsafe { inner(halves) }
+            }
+        }
+
+        impl Float for $float {
+            type Bits = $bits;
+
+            #[inline(always)]
+            fn to_bits(self) -> Self::Bits {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $bits {
+                    $bits(split_si256($cast_to_int(vec.0)).map($bits_half))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn from_bits(bits: Self::Bits) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(bits: $bits) -> $float {
+                    $float($cast_from_int(join_si256(bits.0.map(|half| half.0))))
+                }
+
+                unsafe { inner(bits) }
+            }
+
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(x: $float, a: $float, b: $float) -> $float {
+                    $float($add($mul(x.0, a.0), b.0))
+                }
+
+                unsafe { inner(self, a, b) }
+            }
+
+            #[inline(always)]
+            fn sqrt(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($sqrt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn recip(self) -> Self {
+                Self::new(1.0) / self
+            }
+
+            #[inline(always)]
+            fn recip_approx(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($rcp(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn rsqrt(self) -> Self {
+                Self::new(1.0) / self.sqrt()
+            }
+
+            #[inline(always)]
+            fn rsqrt_approx(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($rsqrt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn abs(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($andnot($set(-0.0), vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float, sign: $float) -> $float {
+                    let mask = $set(-0.0);
+                    $float($or($andnot(mask, vec.0), $and(mask, sign.0)))
+                }
+
+                unsafe { inner(self, sign) }
+            }
+
+            #[inline(always)]
+            fn signum(self) -> Self {
+                let signum = Self::new(1.0).copysign(self);
+                self.eq(&self).select(signum, Self::new($elem::NAN))
+            }
+
+            #[inline(always)]
+            fn floor(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round(vec.0, _MM_FROUND_TO_NEG_INF | _MM_FROUND_NO_EXC))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn ceil(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round(vec.0, _MM_FROUND_TO_POS_INF | _MM_FROUND_NO_EXC))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn round(self) -> Self {
+                // Adding the largest value below one half before truncating rounds ties away from
+                // zero without rounding anything smaller than a tie upwards
+                let half = Self::new(0.5 - $elem::EPSILON / 4.0);
+                (self + half.copysign(self)).trunc()
+            }
+
+            #[inline(always)]
+            fn trunc(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round(vec.0, _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn fract(self) -> Self {
+                self - self.trunc()
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                let [low, high] = self.split();
+                (low + high).reduce_sum()
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                let [low, high] = self.split();
+                (low * high).reduce_product()
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                let [low, high] = self.split();
+                low.min(high).reduce_min()
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                let [low, high] = self.split();
+                low.max(high).reduce_max()
+            }
+        }
+
+        // Shuffles only move lanes around, so they can be done on the bits
+        impl Shuffle for $float {
+            #[inline(always)]
+            fn reverse(self) -> Self {
+                Self::from_bits(self.to_bits().reverse())
+            }
+
+            #[inline(always)]
+            fn interleave_lo(self, other: Self) -> Self {
+                Self::from_bits(self.to_bits().interleave_lo(other.to_bits()))
+            }
+
+            #[inline(always)]
+            fn interleave_hi(self, other: Self) -> Self {
+                Self::from_bits(self.to_bits().interleave_hi(other.to_bits()))
+            }
+
+            #[inline(always)]
+            fn deinterleave(self, other: Self) -> (Self, Self) {
+                let (even, odd) = self.to_bits().deinterleave(other.to_bits());
+                (Self::from_bits(even), Self::from_bits(odd))
+            }
+        }
+
+        impl Add for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($add(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl AddAssign for $float {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl Sub for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($sub(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl SubAssign for $float {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl Mul for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($mul(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl MulAssign for $float {
+            #[inline(always)]
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl Div for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($div(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl DivAssign for $float {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl Neg for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($xor(vec.0, $set(-0.0)))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+    };
+}
+
+macro_rules! split_type {
+    ($vec:ident, $half:path, $elem:ident, $lanes:literal, $mask:ident) => {
+        #[derive(Copy, Clone, Default)]
+        #[repr(transparent)]
+        pub struct $vec([$half; 2]);
+
+        impl Simd for $vec {
+            type Elem = $elem;
+            type Mask = $mask;
+
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            fn new(elem: Self::Elem) -> Self {
+                $vec([<$half>::new(elem); 2])
+            }
+
+            #[inline]
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
+            }
+
+            #[inline(always)]
+            fn from_slice(slice: &[Self::Elem]) -> Self {
+                assert!(slice.len() == Self::LANES);
+                let (low, high) = slice.split_at(Self::LANES / 2);
+                $vec([<$half>::from_slice(low), <$half>::from_slice(high)])
+            }
+
+            #[inline(always)]
+            fn write_to_slice(&self, slice: &mut [Self::Elem]) {
+                assert!(slice.len() == Self::LANES);
+                let (low, high) = slice.split_at_mut(Self::LANES / 2);
+                self.0[0].write_to_slice(low);
+                self.0[1].write_to_slice(high);
+            }
+        }
+
+        impl Array for $vec {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
+                unsafe { slice.align_to::<Self>() }
+            }
+
+            #[inline]
+            fn align_mut_slice(
+                slice: &mut [Self::Elem],
+            ) -> (&mut [Self::Elem], &mut [Self], &mut [Self::Elem]) {
+                unsafe { slice.align_to_mut::<Self>() }
+            }
+        }
+
+        impl Debug for $vec {
+            #[inline]
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Debug::fmt(self.as_slice(), fmt)
+            }
+        }
+
+        impl LanesEq for $vec {
+            type Output = $mask;
+
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> Self::Output {
+                $mask([self.0[0].eq(&other.0[0]), self.0[1].eq(&other.0[1])])
+            }
+
+            #[inline(always)]
+            fn ne(&self, other: &Self) -> Self::Output {
+                $mask([self.0[0].ne(&other.0[0]), self.0[1].ne(&other.0[1])])
+            }
+        }
+
+        impl LanesOrd for $vec {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                $mask([self.0[0].lt(&other.0[0]), self.0[1].lt(&other.0[1])])
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                $mask([self.0[0].le(&other.0[0]), self.0[1].le(&other.0[1])])
+            }
+
+            #[inline(always)]
+            fn gt(&self, other: &Self) -> Self::Output {
+                $mask([self.0[0].gt(&other.0[0]), self.0[1].gt(&other.0[1])])
+            }
+
+            #[inline(always)]
+            fn ge(&self, other: &Self) -> Self::Output {
+                $mask([self.0[0].ge(&other.0[0]), self.0[1].ge(&other.0[1])])
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                $vec([self.0[0].max(other.0[0]), self.0[1].max(other.0[1])])
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                $vec([self.0[0].min(other.0[0]), self.0[1].min(other.0[1])])
+            }
+        }
+
+        impl Index<usize> for $vec {
+            type Output = <Self as Simd>::Elem;
+
+            #[inline]
+            fn index(&self, index: usize) -> &Self::Output {
+                &self.as_slice()[index]
+            }
+        }
+
+        impl IndexMut<usize> for $vec {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+                &mut self.as_mut_slice()[index]
+            }
+        }
+
+        impl Select<$vec> for $mask {
+            #[inline(always)]
+            fn select(self, if_true: $vec, if_false: $vec) -> $vec {
+                $vec([
+                    self.0[0].select(if_true.0[0], if_false.0[0]),
+                    self.0[1].select(if_true.0[1], if_false.0[1]),
+                ])
+            }
+        }
+
+        impl Shuffle for $vec {
+            #[inline(always)]
+            fn reverse(self) -> Self {
+                $vec([self.0[1].reverse(), self.0[0].reverse()])
+            }
+
+            #[inline(always)]
+            fn interleave_lo(self, other: Self) -> Self {
+                let [low, _] = self.0;
+                let [other_low, _] = other.0;
+                $vec([low.interleave_lo(other_low), low.interleave_hi(other_low)])
+            }
+
+            #[inline(always)]
+            fn interleave_hi(self, other: Self) -> Self {
+                let [_, high] = self.0;
+                let [_, other_high] = other.0;
+                $vec([
+                    high.interleave_lo(other_high),
+                    high.interleave_hi(other_high),
+                ])
+            }
+
+            #[inline(always)]
+            fn deinterleave(self, other: Self) -> (Self, Self) {
+                let (even_low, odd_low) = self.0[0].deinterleave(self.0[1]);
+                let (even_high, odd_high) = other.0[0].deinterleave(other.0[1]);
+                ($vec([even_low, even_high]), $vec([odd_low, odd_high]))
+            }
+        }
+
+        impl_split_binary! { $vec, BitAnd, bitand, BitAndAssign, bitand_assign }
+        impl_split_binary! { $vec, BitOr, bitor, BitOrAssign, bitor_assign }
+        impl_split_binary! { $vec, BitXor, bitxor, BitXorAssign, bitxor_assign }
+
+        impl Not for $vec {
+            type Output = Self;
+
+            #[inline(always)]
+            fn not(self) -> Self::Output {
+                $vec([!self.0[0], !self.0[1]])
+            }
+        }
+
+        impl Bitwise for $vec {
+            #[inline(always)]
+            fn reduce_and(self) -> Self::Elem {
+                (self.0[0] & self.0[1]).reduce_and()
+            }
+
+            #[inline(always)]
+            fn reduce_or(self) -> Self::Elem {
+                (self.0[0] | self.0[1]).reduce_or()
+            }
+
+            #[inline(always)]
+            fn reduce_xor(self) -> Self::Elem {
+                (self.0[0] ^ self.0[1]).reduce_xor()
+            }
+        }
+    };
+}
+
+macro_rules! impl_split_binary {
+    ($vec:ident, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $trait for $vec {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $method(self, rhs: Self) -> Self::Output {
+                $vec([self.0[0].$method(rhs.0[0]), self.0[1].$method(rhs.0[1])])
+            }
+        }
+
+        impl $assign_trait for $vec {
+            #[inline(always)]
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $trait::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+macro_rules! impl_split_shift {
+    ($vec:ident, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $trait<usize> for $vec {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $method(self, rhs: usize) -> Self::Output {
+                $vec([self.0[0].$method(rhs), self.0[1].$method(rhs)])
+            }
+        }
+
+        impl $assign_trait<usize> for $vec {
+            #[inline(always)]
+            fn $assign_method(&mut self, rhs: usize) {
+                *self = $trait::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+macro_rules! impl_int {
+    ($int:ident) => {
+        impl_split_binary! { $int, Add, add, AddAssign, add_assign }
+        impl_split_binary! { $int, Sub, sub, SubAssign, sub_assign }
+        impl_split_binary! { $int, Mul, mul, MulAssign, mul_assign }
+        impl_split_binary! { $int, Div, div, DivAssign, div_assign }
+        impl_split_binary! { $int, Rem, rem, RemAssign, rem_assign }
+        impl_split_shift! { $int, Shl, shl, ShlAssign, shl_assign }
+        impl_split_shift! { $int, Shr, shr, ShrAssign, shr_assign }
+
+        impl Neg for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self::Output {
+                $int([-self.0[0], -self.0[1]])
+            }
+        }
+
+        impl Int for $int {
+            #[inline(always)]
+            fn saturating_add(self, rhs: Self) -> Self {
+                $int([
+                    self.0[0].saturating_add(rhs.0[0]),
+                    self.0[1].saturating_add(rhs.0[1]),
+                ])
+            }
+
+            #[inline(always)]
+            fn saturating_sub(self, rhs: Self) -> Self {
+                $int([
+                    self.0[0].saturating_sub(rhs.0[0]),
+                    self.0[1].saturating_sub(rhs.0[1]),
+                ])
+            }
+
+            #[inline(always)]
+            fn abs_diff(self, rhs: Self) -> Self {
+                $int([self.0[0].abs_diff(rhs.0[0]), self.0[1].abs_diff(rhs.0[1])])
+            }
+
+            #[inline(always)]
+            fn avg(self, rhs: Self) -> Self {
+                $int([self.0[0].avg(rhs.0[0]), self.0[1].avg(rhs.0[1])])
+            }
+
+            #[inline(always)]
+            fn mulhi(self, rhs: Self) -> Self {
+                $int([self.0[0].mulhi(rhs.0[0]), self.0[1].mulhi(rhs.0[1])])
+            }
+
+            #[inline(always)]
+            fn shl_by(self, counts: Self) -> Self {
+                $int([self.0[0].shl_by(counts.0[0]), self.0[1].shl_by(counts.0[1])])
+            }
+
+            #[inline(always)]
+            fn shr_by(self, counts: Self) -> Self {
+                $int([self.0[0].shr_by(counts.0[0]), self.0[1].shr_by(counts.0[1])])
+            }
+
+            #[inline(always)]
+            fn rotate_left(self, n: usize) -> Self {
+                $int([self.0[0].rotate_left(n), self.0[1].rotate_left(n)])
+            }
+
+            #[inline(always)]
+            fn rotate_right(self, n: usize) -> Self {
+                $int([self.0[0].rotate_right(n), self.0[1].rotate_right(n)])
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                (self.0[0] + self.0[1]).reduce_sum()
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                (self.0[0] * self.0[1]).reduce_product()
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.0[0].min(self.0[1]).reduce_min()
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.0[0].max(self.0[1]).reduce_max()
+            }
+        }
+    };
+}
+
+macro_rules! impl_mask {
+    ($mask:ident) => {
+        impl Mask for $mask {
+            #[inline(always)]
+            fn to_bitmask(self) -> u64 {
+                let half_lanes = Self::LANES / 2;
+                self.0[0].to_bitmask() | self.0[1].to_bitmask() << half_lanes
+            }
+
+            #[inline(always)]
+            fn from_bitmask(bits: u64) -> Self {
+                let half_lanes = Self::LANES / 2;
+                $mask([
+                    Mask::from_bitmask(bits),
+                    Mask::from_bitmask(bits >> half_lanes),
+                ])
+            }
+        }
+    };
+}
+
+macro_rules! impl_gather {
+    ($vec:ident, $($index:ident),*) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = $index;
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+    // Each vector of `$vec` is indexed by `$n` index vectors
+    ($vec:ident, [$($index:ident),*; $n:literal]) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = [$index; $n];
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+    // Each index vector covers `$n` vectors of `$vec`
+    ([$vec:ident; $n:literal], $($index:ident),*) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = $index;
+                type Values = [Self; $n];
+                type Masks = [<Self as Simd>::Mask; $n];
+            }
+        )*
+    };
+}
+
+macro_rules! impl_cast {
+    ($from:ident, $to:ident) => {
+        impl Cast<$to> for $from {
+            #[inline(always)]
+            fn cast(self) -> $to {
+                $to(self.0.map(Cast::cast))
+            }
+        }
+    };
+}
+
+macro_rules! impl_float_cast {
+    ($float:ident, $int:ident) => {
+        impl Cast<$int> for $float {
+            #[inline(always)]
+            fn cast(self) -> $int {
+                $int(self.split().map(Cast::cast))
+            }
+        }
+
+        impl RoundCast<$int> for $float {
+            #[inline(always)]
+            fn round_cast(self) -> $int {
+                $int(self.split().map(RoundCast::round_cast))
+            }
+        }
+
+        impl Cast<$float> for $int {
+            #[inline(always)]
+            fn cast(self) -> $float {
+                $float::join(self.0.map(Cast::cast))
+            }
+        }
+    };
+}
+
+macro_rules! impl_widen_int {
+    ($narrow:ident, $wide:ident) => {
+        impl Widen for $narrow {
+            type Wide = $wide;
+            type Parts = [$wide; 2];
+
+            #[inline(always)]
+            fn widen(self) -> Self::Parts {
+                self.0.map(|half| $wide(half.widen()))
+            }
+
+            #[inline(always)]
+            fn narrow(parts: Self::Parts) -> Self {
+                $narrow(parts.map(|part| Widen::narrow(part.0)))
+            }
+        }
+    };
+}
+
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn split_si256(a: __m256i) -> [__m128i; 2] {
+    [_mm256_castsi256_si128(a), _mm256_extractf128_si256(a, 1)]
+}
+
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn join_si256(halves: [__m128i; 2]) -> __m256i {
+    _mm256_set_m128i(halves[1], halves[0])
+}
+
+// Integer comparisons are AVX2-only, so compare lane indices as floats instead
+
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn prefix_mask_ps(len: usize) -> __m256i {
+    let index = _mm256_setr_ps(0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0);
+    let len = _mm256_set1_ps(len.min(8) as f32);
+    _mm256_castps_si256(_mm256_cmp_ps(index, len, _CMP_LT_OQ))
+}
+
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn prefix_mask_pd(len: usize) -> __m256i {
+    let index = _mm256_setr_pd(0.0, 1.0, 2.0, 3.0);
+    let len = _mm256_set1_pd(len.min(4) as f64);
+    _mm256_castpd_si256(_mm256_cmp_pd(index, len, _CMP_LT_OQ))
+}
+
+// There are no approximate reciprocal instructions for f64, so compute them precisely
+
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn rcp_pd(a: __m256d) -> __m256d {
+    _mm256_div_pd(_mm256_set1_pd(1.0), a)
+}
+
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn rsqrt_pd(a: __m256d) -> __m256d {
+    _mm256_div_pd(_mm256_set1_pd(1.0), _mm256_sqrt_pd(a))
+}
+
+float_type! {
+    f32x8, __m256, f32, 8, sse41::f32x4, m32x8, sse41::m32x4, u32x8, sse41::u32x4,
+    _mm256_set1_ps, _mm256_loadu_ps, _mm256_load_ps, _mm256_storeu_ps, _mm256_stream_ps, _mm256_castps_si256, _mm256_castsi256_ps,
+    _mm256_castps256_ps128, _mm256_extractf128_ps, _mm256_set_m128, _mm256_blendv_ps,
+    _mm256_cmp_ps, _mm256_max_ps, _mm256_min_ps, _mm256_add_ps, _mm256_sub_ps, _mm256_mul_ps, _mm256_div_ps, _mm256_xor_ps,
+    _mm256_and_ps, _mm256_or_ps, _mm256_andnot_ps, _mm256_sqrt_ps, _mm256_rcp_ps, _mm256_rsqrt_ps,
+    _mm256_round_ps, prefix_mask_ps, _mm256_maskload_ps, _mm256_maskstore_ps,
+}
+float_type! {
+    f64x4, __m256d, f64, 4, sse41::f64x2, m64x4, sse41::m64x2, u64x4, sse41::u64x2,
+    _mm256_set1_pd, _mm256_loadu_pd, _mm256_load_pd, _mm256_storeu_pd, _mm256_stream_pd, _mm256_castpd_si256, _mm256_castsi256_pd,
+    _mm256_castpd256_pd128, _mm256_extractf128_pd, _mm256_set_m128d, _mm256_blendv_pd,
+    _mm256_cmp_pd, _mm256_max_pd, _mm256_min_pd, _mm256_add_pd, _mm256_sub_pd, _mm256_mul_pd, _mm256_div_pd, _mm256_xor_pd,
+    _mm256_and_pd, _mm256_or_pd, _mm256_andnot_pd, _mm256_sqrt_pd, rcp_pd, rsqrt_pd,
+    _mm256_round_pd, prefix_mask_pd, _mm256_maskload_pd, _mm256_maskstore_pd,
+}
+
+impl_float_cast! { f32x8, u32x8 }
+impl_float_cast! { f32x8, i32x8 }
+impl_float_cast! { f64x4, u64x4 }
+impl_float_cast! { f64x4, i64x4 }
+impl_gather! { f32x8, u32x8, i32x8 }
+impl_gather! { f64x4, u64x4, i64x4 }
+impl_gather! { [f64x4; 2], u32x8, i32x8 }
+
+impl Widen for f32x8 {
+    type Wide = f64x4;
+    type Parts = [f64x4; 2];
+
+    #[inline(always)]
+    fn widen(self) -> Self::Parts {
+        #[inline]
+        #[target_feature(enable = "avx")]
+        unsafe fn inner(vec: f32x8) -> [f64x4; 2] {
+            let low = _mm256_castps256_ps128(vec.0);
+            let high = _mm256_extractf128_ps(vec.0, 1);
+            [f64x4(_mm256_cvtps_pd(low)), f64x4(_mm256_cvtps_pd(high))]
+        }
+
+        unsafe { inner(self) }
+    }
+
+    #[inline(always)]
+    fn narrow(parts: Self::Parts) -> Self {
+        #[inline]
+        #[target_feature(enable = "avx")]
+        unsafe fn inner(parts: [f64x4; 2]) -> f32x8 {
+            let low = _mm256_cvtpd_ps(parts[0].0);
+            let high = _mm256_cvtpd_ps(parts[1].0);
+            f32x8(_mm256_set_m128(high, low))
+        }
+
+        unsafe { inner(parts) }
+    }
+}
+
+split_type! { u8x32, sse41::u8x16, u8, 32, m8x32 }
+split_type! { u16x16, sse41::u16x8, u16, 16, m16x16 }
+split_type! { u32x8, sse41::u32x4, u32, 8, m32x8 }
+split_type! { u64x4, sse41::u64x2, u64, 4, m64x4 }
+impl_int! { u8x32 }
+impl_int! { u16x16 }
+impl_int! { u32x8 }
+impl_int! { u64x4 }
+impl_cast! { u8x32, i8x32 }
+impl_cast! { u16x16, i16x16 }
+impl_cast! { u32x8, i32x8 }
+impl_cast! { u64x4, i64x4 }
+impl_widen_int! { u8x32, u16x16 }
+impl_widen_int! { u16x16, u32x8 }
+impl_widen_int! { u32x8, u64x4 }
+impl_gather! { u8x32, u8x32, i8x32 }
+impl_gather! { u8x32, [u32x8, i32x8; 4] }
+impl_gather! { u16x16, u16x16, i16x16 }
+impl_gather! { u16x16, [u32x8, i32x8; 2] }
+impl_gather! { u32x8, u32x8, i32x8 }
+impl_gather! { u64x4, u64x4, i64x4 }
+impl_gather! { [u64x4; 2], u32x8, i32x8 }
+
+split_type! { i8x32, sse41::i8x16, i8, 32, m8x32 }
+split_type! { i16x16, sse41::i16x8, i16, 16, m16x16 }
+split_type! { i32x8, sse41::i32x4, i32, 8, m32x8 }
+split_type! { i64x4, sse41::i64x2, i64, 4, m64x4 }
+impl_int! { i8x32 }
+impl_int! { i16x16 }
+impl_int! { i32x8 }
+impl_int! { i64x4 }
+impl_cast! { i8x32, u8x32 }
+impl_cast! { i16x16, u16x16 }
+impl_cast! { i32x8, u32x8 }
+impl_cast! { i64x4, u64x4 }
+impl_widen_int! { i8x32, i16x16 }
+impl_widen_int! { i16x16, i32x8 }
+impl_widen_int! { i32x8, i64x4 }
+impl_gather! { i8x32, u8x32, i8x32 }
+impl_gather! { i8x32, [u32x8, i32x8; 4] }
+impl_gather! { i16x16, u16x16, i16x16 }
+impl_gather! { i16x16, [u32x8, i32x8; 2] }
+impl_gather! { i32x8, u32x8, i32x8 }
+impl_gather! { i64x4, u64x4, i64x4 }
+impl_gather! { [i64x4; 2], u32x8, i32x8 }
+
+split_type! { m8x32, sse41::m8x16, m8, 32, m8x32 }
+split_type! { m16x16, sse41::m16x8, m16, 16, m16x16 }
+split_type! { m32x8, sse41::m32x4, m32, 8, m32x8 }
+split_type! { m64x4, sse41::m64x2, m64, 4, m64x4 }
+impl_mask! { m8x32 }
+impl_mask! { m16x16 }
+impl_mask! { m32x8 }
+impl_mask! { m64x4 }
@@ -1,10 +1,13 @@
 #![allow(non_camel_case_types)]
 
+use core::array;
+use core::convert::identity;
 use core::fmt::{self, Debug};
 use core::mem;
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
 use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
 use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
 use core::slice;
 
@@ -14,7 +17,10 @@ use std::arch::x86::*;
 use std::arch::x86_64::*;
 
 use crate::mask::*;
-use crate::simd::{Bitwise, Float, Int, LanesEq, LanesOrd, Select, Simd};
+use crate::simd::{
+    check_aligned, check_index, check_mask_len, Array, Bitwise, Cast, Float, Gather, Int, LanesEq,
+    LanesOrd, Mask, RoundCast, Select, Shuffle, Simd, Swizzle, Widen,
+};
 use crate::{Arch, Task};
 
 pub struct Avx2Impl;
@@ -43,20 +49,44 @@ impl Arch for Avx2Impl {
     #[inline(always)]
     fn invoke<T: Task>(task: T) -> T::Result {
         #[inline]
-        #[target_feature(enable = "avx2")]
+        #[target_feature(enable = "avx2,fma")]
         unsafe fn inner<T: Task>(task: T) -> T::Result {
             task.run::<Avx2Impl>()
         }
 
         unsafe { inner(task) }
     }
+
+    #[inline(always)]
+    fn prefetch<T>(ptr: *const T) {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn inner(ptr: *const i8) {
+            _mm_prefetch::<_MM_HINT_T0>(ptr);
+        }
+
+        unsafe { inner(ptr as *const i8) }
+    }
+
+    #[inline(always)]
+    fn stream_fence() {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn inner() {
+            _mm_sfence();
+        }
+
+        unsafe { inner() }
+    }
 }
 
 macro_rules! float_type {
     (
-        $float:ident, $inner:ident, $elem:ident, $lanes:literal, $mask:ident,
-        $set:ident, $load:ident, $store:ident, $cast_to_int:ident, $cast_from_int:ident, $blend:ident,
+        $float:ident, $inner:ident, $elem:ident, $lanes:literal, $mask:ident, $bits:ident,
+        $set:ident, $load:ident, $load_aligned:ident, $store:ident, $stream:ident, $cast_to_int:ident, $cast_from_int:ident, $blend:ident,
         $cmp:ident, $max:ident, $min:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $xor:ident,
+        $and:ident, $or:ident, $andnot:ident, $fmadd:ident, $sqrt:ident, $rcp:ident, $rsqrt:ident,
+        $round:ident, $prefix_mask:ident, $maskload:ident, $maskstore:ident,
     ) => {
         #[derive(Copy, Clone)]
         #[repr(transparent)]
@@ -80,17 +110,8 @@ macro_rules! float_type {
             }
 
             #[inline]
-            fn as_slice(&self) -> &[Self::Elem] {
-                unsafe {
-                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
-                }
-            }
-
-            #[inline]
-            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
-                unsafe {
-                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
-                }
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
             }
 
             #[inline(always)]
@@ -117,6 +138,109 @@ macro_rules! float_type {
                 unsafe { inner(self, slice) }
             }
 
+            #[inline(always)]
+            fn from_slice_aligned(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    check_aligned::<$float>(slice);
+                    $float($load_aligned(slice.as_ptr()))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            unsafe fn store_stream(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    check_aligned::<$float>(slice);
+                    $stream(slice.as_mut_ptr(), vec.0);
+                }
+
+                inner(self, slice);
+            }
+
+            #[inline(always)]
+            fn load_partial(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    $float($maskload(slice.as_ptr(), $prefix_mask(slice.len())))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn load_or(slice: &[Self::Elem], or: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(slice: &[$elem], or: $float) -> $float {
+                    let mask = $prefix_mask(slice.len());
+                    let loaded = $maskload(slice.as_ptr(), mask);
+                    $float($blend(or.0, loaded, $cast_from_int(mask)))
+                }
+
+                unsafe { inner(slice, or) }
+            }
+
+            #[inline(always)]
+            fn store_partial(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    $maskstore(slice.as_mut_ptr(), $prefix_mask(slice.len()), vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice);
+                }
+            }
+
+            #[inline(always)]
+            fn load_masked(slice: &[Self::Elem], mask: Self::Mask) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(slice: &[$elem], mask: <$float as Simd>::Mask) -> $float {
+                    check_mask_len(slice.len(), mask);
+                    $float($maskload(slice.as_ptr(), mask.0))
+                }
+
+                unsafe { inner(slice, mask) }
+            }
+
+            #[inline(always)]
+            fn store_masked(&self, slice: &mut [Self::Elem], mask: Self::Mask) {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem], mask: <$float as Simd>::Mask) {
+                    check_mask_len(slice.len(), mask);
+                    $maskstore(slice.as_mut_ptr(), mask.0, vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice, mask);
+                }
+            }
+        }
+
+        impl Array for $float {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
+                }
+            }
+
             #[inline]
             fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
                 unsafe { slice.align_to::<Self>() }
@@ -274,7 +398,211 @@ macro_rules! float_type {
             }
         }
 
-        impl Float for $float {}
+        impl $float {
+            #[inline(always)]
+            fn fold_lanes(self, f: impl Fn($float, $float) -> $float) -> $elem {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn shift<const BYTES: i32>(vec: $float) -> $float {
+                    let vec = $cast_to_int(vec.0);
+                    $float($cast_from_int(_mm256_srli_si256::<BYTES>(vec)))
+                }
+
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn swap_halves(vec: $float) -> $float {
+                    let vec = $cast_to_int(vec.0);
+                    $float($cast_from_int(_mm256_permute2x128_si256(vec, vec, 0x01)))
+                }
+
+                // Repeatedly combine each lane with the one half the remaining width above it,
+                // leaving the result in the lowest lane
+                let mut vec = self;
+                unsafe {
+                    vec = f(vec, swap_halves(vec));
+                    vec = f(vec, shift::<8>(vec));
+                    if mem::size_of::<$elem>() <= 4 {
+                        vec = f(vec, shift::<4>(vec));
+                    }
+                }
+                vec[0]
+            }
+        }
+
+        impl Float for $float {
+            type Bits = $bits;
+
+            #[inline(always)]
+            fn to_bits(self) -> Self::Bits {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float) -> $bits {
+                    $bits($cast_to_int(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn from_bits(bits: Self::Bits) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(bits: $bits) -> $float {
+                    $float($cast_from_int(bits.0))
+                }
+
+                unsafe { inner(bits) }
+            }
+
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2,fma")]
+                unsafe fn inner(x: $float, a: $float, b: $float) -> $float {
+                    $float($fmadd(x.0, a.0, b.0))
+                }
+
+                unsafe { inner(self, a, b) }
+            }
+
+            #[inline(always)]
+            fn sqrt(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($sqrt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn recip(self) -> Self {
+                Self::new(1.0) / self
+            }
+
+            #[inline(always)]
+            fn recip_approx(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($rcp(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn rsqrt(self) -> Self {
+                Self::new(1.0) / self.sqrt()
+            }
+
+            #[inline(always)]
+            fn rsqrt_approx(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($rsqrt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn abs(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($andnot($set(-0.0), vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float, sign: $float) -> $float {
+                    let mask = $set(-0.0);
+                    $float($or($andnot(mask, vec.0), $and(mask, sign.0)))
+                }
+
+                unsafe { inner(self, sign) }
+            }
+
+            #[inline(always)]
+            fn signum(self) -> Self {
+                let signum = Self::new(1.0).copysign(self);
+                self.eq(&self).select(signum, Self::new($elem::NAN))
+            }
+
+            #[inline(always)]
+            fn floor(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round(vec.0, _MM_FROUND_TO_NEG_INF | _MM_FROUND_NO_EXC))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn ceil(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round(vec.0, _MM_FROUND_TO_POS_INF | _MM_FROUND_NO_EXC))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn round(self) -> Self {
+                // Adding the largest value below one half before truncating rounds ties away from
+                // zero without rounding anything smaller than a tie upwards
+                let half = Self::new(0.5 - $elem::EPSILON / 4.0);
+                (self + half.copysign(self)).trunc()
+            }
+
+            #[inline(always)]
+            fn trunc(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round(vec.0, _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn fract(self) -> Self {
+                self - self.trunc()
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a + b)
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a * b)
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.min(b))
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.max(b))
+            }
+        }
 
         impl Add for $float {
             type Output = Self;
@@ -382,7 +710,10 @@ macro_rules! float_type {
 }
 
 macro_rules! int_type {
-    ($int:ident, $elem:ident, $lanes:literal, $mask:ident, $set:ident, $cmp:ident) => {
+    (
+        $int:ident, $elem:ident, $lanes:literal, $mask:ident, $set:ident, $cmp:ident
+        $(, $prefix_mask:ident, $maskload:ident, $maskstore:ident)?
+    ) => {
         #[derive(Copy, Clone)]
         #[repr(transparent)]
         pub struct $int(__m256i);
@@ -397,6 +728,8 @@ macro_rules! int_type {
             fn new(elem: Self::Elem) -> Self {
                 #[inline]
                 #[target_feature(enable = "avx2")]
+                #[allow(clippy::missing_transmute_annotations, clippy::useless_transmute)]
+                #[allow(unnecessary_transmutes)]
                 unsafe fn inner(elem: $elem) -> $int {
                     $int($set(mem::transmute(elem)))
                 }
@@ -405,17 +738,8 @@ macro_rules! int_type {
             }
 
             #[inline]
-            fn as_slice(&self) -> &[Self::Elem] {
-                unsafe {
-                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
-                }
-            }
-
-            #[inline]
-            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
-                unsafe {
-                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
-                }
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
             }
 
             #[inline(always)]
@@ -444,12 +768,117 @@ macro_rules! int_type {
                 }
             }
 
-            #[inline]
-            fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
-                unsafe { slice.align_to::<Self>() }
+            #[inline(always)]
+            fn from_slice_aligned(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(slice: &[$elem]) -> $int {
+                    check_aligned::<$int>(slice);
+                    $int(_mm256_load_si256(slice.as_ptr() as *const __m256i))
+                }
+
+                unsafe { inner(slice) }
             }
 
-            #[inline]
+            #[inline(always)]
+            unsafe fn store_stream(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: &$int, slice: &mut [$elem]) {
+                    check_aligned::<$int>(slice);
+                    _mm256_stream_si256(slice.as_mut_ptr() as *mut __m256i, vec.0);
+                }
+
+                inner(self, slice);
+            }
+
+            $(
+                #[inline(always)]
+                fn load_partial(slice: &[Self::Elem]) -> Self {
+                    #[inline]
+                    #[target_feature(enable = "avx2")]
+                    unsafe fn inner(slice: &[$elem]) -> $int {
+                        $int($maskload(slice.as_ptr() as _, $prefix_mask(slice.len())))
+                    }
+
+                    unsafe { inner(slice) }
+                }
+
+                #[inline(always)]
+                fn load_or(slice: &[Self::Elem], or: Self) -> Self {
+                    #[inline]
+                    #[target_feature(enable = "avx2")]
+                    unsafe fn inner(slice: &[$elem], or: $int) -> $int {
+                        let mask = $prefix_mask(slice.len());
+                        let loaded = $maskload(slice.as_ptr() as _, mask);
+                        $int(_mm256_blendv_epi8(or.0, loaded, identity(mask)))
+                    }
+
+                    unsafe { inner(slice, or) }
+                }
+
+                #[inline(always)]
+                fn store_partial(&self, slice: &mut [Self::Elem]) {
+                    #[inline]
+                    #[target_feature(enable = "avx2")]
+                    unsafe fn inner(vec: &$int, slice: &mut [$elem]) {
+                        $maskstore(slice.as_mut_ptr() as _, $prefix_mask(slice.len()), vec.0);
+                    }
+
+                    unsafe {
+                        inner(self, slice);
+                    }
+                }
+
+                #[inline(always)]
+                fn load_masked(slice: &[Self::Elem], mask: Self::Mask) -> Self {
+                    #[inline]
+                    #[target_feature(enable = "avx2")]
+                    unsafe fn inner(slice: &[$elem], mask: <$int as Simd>::Mask) -> $int {
+                        check_mask_len(slice.len(), mask);
+                        $int($maskload(slice.as_ptr() as _, mask.0))
+                    }
+
+                    unsafe { inner(slice, mask) }
+                }
+
+                #[inline(always)]
+                fn store_masked(&self, slice: &mut [Self::Elem], mask: Self::Mask) {
+                    #[inline]
+                    #[target_feature(enable = "avx2")]
+                    unsafe fn inner(vec: &$int, slice: &mut [$elem], mask: <$int as Simd>::Mask) {
+                        check_mask_len(slice.len(), mask);
+                        $maskstore(slice.as_mut_ptr() as _, mask.0, vec.0);
+                    }
+
+                    unsafe {
+                        inner(self, slice, mask);
+                    }
+                }
+            )?
+        }
+
+        impl Array for $int {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
+                unsafe { slice.align_to::<Self>() }
+            }
+
+            #[inline]
             fn align_mut_slice(
                 slice: &mut [Self::Elem],
             ) -> (&mut [Self::Elem], &mut [Self], &mut [Self::Elem]) {
@@ -502,7 +931,57 @@ macro_rules! int_type {
             }
         }
 
-        impl Bitwise for $int {}
+        impl $int {
+            #[inline(always)]
+            fn fold_lanes(self, f: impl Fn($int, $int) -> $int) -> $elem {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn shift<const BYTES: i32>(vec: $int) -> $int {
+                    $int(_mm256_srli_si256::<BYTES>(vec.0))
+                }
+
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn swap_halves(vec: $int) -> $int {
+                    $int(_mm256_permute2x128_si256(vec.0, vec.0, 0x01))
+                }
+
+                // Repeatedly combine each lane with the one half the remaining width above it,
+                // leaving the result in the lowest lane
+                let mut vec = self;
+                unsafe {
+                    vec = f(vec, swap_halves(vec));
+                    vec = f(vec, shift::<8>(vec));
+                    if mem::size_of::<$elem>() <= 4 {
+                        vec = f(vec, shift::<4>(vec));
+                    }
+                    if mem::size_of::<$elem>() <= 2 {
+                        vec = f(vec, shift::<2>(vec));
+                    }
+                    if mem::size_of::<$elem>() <= 1 {
+                        vec = f(vec, shift::<1>(vec));
+                    }
+                }
+                vec[0]
+            }
+        }
+
+        impl Bitwise for $int {
+            #[inline(always)]
+            fn reduce_and(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a & b)
+            }
+
+            #[inline(always)]
+            fn reduce_or(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a | b)
+            }
+
+            #[inline(always)]
+            fn reduce_xor(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a ^ b)
+            }
+        }
 
         impl BitAnd for $int {
             type Output = Self;
@@ -734,9 +1213,147 @@ macro_rules! impl_ord_mask {
     };
 }
 
+macro_rules! impl_mask {
+    ($mask:ident, $movemask:ident, $expand:ident) => {
+        impl Mask for $mask {
+            #[inline(always)]
+            fn to_bitmask(self) -> u64 {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(mask: $mask) -> u64 {
+                    $movemask(mask.0)
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn from_bitmask(bits: u64) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(bits: u64) -> $mask {
+                    $mask($expand(bits))
+                }
+
+                unsafe { inner(bits) }
+            }
+        }
+    };
+}
+
 macro_rules! impl_int {
-    ($int:ident, $elem:ident, $set:ident, $add:ident, $sub:ident, $shl:ident, $shr:ident) => {
-        impl Int for $int {}
+    ($int:ident, $elem:ident, $set:ident, $add:ident, $sub:ident, $shl:ident, $shr:ident, $srl:ident, $shlv:ident, $shrv:ident, $adds:ident, $subs:ident, $avg:ident, $mulhi:ident, $div:ident) => {
+        impl Int for $int {
+            #[inline(always)]
+            fn saturating_add(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($adds(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn saturating_sub(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($subs(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn avg(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($avg(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn mulhi(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($mulhi(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn shl_by(self, counts: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $int, counts: $int) -> $int {
+                    let counts = _mm256_and_si256(counts.0, $set(($elem::BITS - 1) as _));
+                    $int($shlv(lhs.0, counts))
+                }
+
+                unsafe { inner(self, counts) }
+            }
+
+            #[inline(always)]
+            fn shr_by(self, counts: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $int, counts: $int) -> $int {
+                    let counts = _mm256_and_si256(counts.0, $set(($elem::BITS - 1) as _));
+                    $int($shrv(lhs.0, counts))
+                }
+
+                unsafe { inner(self, counts) }
+            }
+
+            #[inline(always)]
+            fn rotate_left(self, n: usize) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $int, n: usize) -> $int {
+                    let bits = $elem::BITS as usize;
+                    let left = n & (bits - 1);
+                    let right = (bits - left) & (bits - 1);
+                    let high = $shl(vec.0, _mm_cvtsi64_si128(left as i64));
+                    let low = $srl(vec.0, _mm_cvtsi64_si128(right as i64));
+                    $int(_mm256_or_si256(high, low))
+                }
+
+                unsafe { inner(self, n) }
+            }
+
+            #[inline(always)]
+            fn rotate_right(self, n: usize) -> Self {
+                let bits = $elem::BITS as usize;
+                self.rotate_left(bits - (n & (bits - 1)))
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a + b)
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a * b)
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.min(b))
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.max(b))
+            }
+        }
 
         impl Add for $int {
             type Output = Self;
@@ -789,6 +1406,44 @@ macro_rules! impl_int {
             }
         }
 
+        impl Div for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($div(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl DivAssign for $int {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl Rem for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn rem(self, rhs: Self) -> Self {
+                self - (self / rhs) * rhs
+            }
+        }
+
+        impl RemAssign for $int {
+            #[inline(always)]
+            fn rem_assign(&mut self, rhs: Self) {
+                *self = *self % rhs;
+            }
+        }
+
         impl Neg for $int {
             type Output = Self;
 
@@ -925,91 +1580,1330 @@ macro_rules! impl_int_mul {
     };
 }
 
-#[inline]
-#[target_feature(enable = "sse2")]
-pub unsafe fn _mm_sra_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
-    // Perform a 16-bit logical shift and then mask out garbage from adjacent lanes
-    let shift = _mm_cvtsi128_si64(count);
-    let mask = _mm_set1_epi8((0xFFu8 >> shift) as i8);
-    let shifted = _mm_and_si128(mask, _mm_srl_epi16(a, count));
-    // Manual sign extension
-    let sign = _mm_and_si128(a, _mm_set1_epi8(1 << 7));
-    let extended = _mm_sub_epi8(_mm_setzero_si128(), _mm_srl_epi16(sign, count));
-    _mm_or_si128(extended, shifted)
-}
+macro_rules! impl_shuffle {
+    (
+        $vec:ident, $to_int:ident, $from_int:ident,
+        $reverse:ident, $unpacklo:ident, $unpackhi:ident, $deinterleave:ident
+        $(, $permute:ident)?
+    ) => {
+        impl Shuffle for $vec {
+            $(
+                #[inline(always)]
+                fn swizzle<S: Swizzle<N>, const N: usize>(self) -> Self {
+                    const { check_index(&S::INDEX, Self::LANES, 1) };
+                    self.$permute(self, |i| S::INDEX[i])
+                }
 
-#[inline]
-#[target_feature(enable = "sse2")]
-pub unsafe fn _mm_sra_epi64_fallback(a: __m128i, count: __m128i) -> __m128i {
-    // Perform a logical shift followed by manual sign extension
-    let sign = _mm_and_si128(a, _mm_set1_epi64x(1 << 63));
-    let extended = _mm_sub_epi64(_mm_setzero_si128(), _mm_srl_epi64(sign, count));
-    _mm_or_si128(extended, _mm_srl_epi64(a, count))
-}
+                #[inline(always)]
+                fn shuffle<S: Swizzle<N>, const N: usize>(self, other: Self) -> Self {
+                    const { check_index(&S::INDEX, Self::LANES, 2) };
+                    self.$permute(other, |i| S::INDEX[i])
+                }
 
-#[inline]
-#[target_feature(enable = "avx2")]
-pub unsafe fn _mm256_sll_epi8_fallback(a: __m256i, count: __m128i) -> __m256i {
-    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
-    let shift = _mm_cvtsi128_si64(count);
-    let mask = _mm256_set1_epi8((0xFFu8 << shift) as i8);
-    _mm256_and_si256(mask, _mm256_sll_epi16(a, count))
-}
+                #[inline(always)]
+                fn rotate_lanes_left<const N: usize>(self) -> Self {
+                    self.$permute(self, |i| (i + N) % Self::LANES)
+                }
 
-#[inline]
-#[target_feature(enable = "avx2")]
-pub unsafe fn _mm256_srl_epi8_fallback(a: __m256i, count: __m128i) -> __m256i {
-    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
-    let shift = _mm_cvtsi128_si64(count);
-    let mask = _mm256_set1_epi8((0xFFu8 >> shift) as i8);
-    _mm256_and_si256(mask, _mm256_srl_epi16(a, count))
-}
+                #[inline(always)]
+                fn rotate_lanes_right<const N: usize>(self) -> Self {
+                    self.$permute(self, |i| (i + Self::LANES - N % Self::LANES) % Self::LANES)
+                }
 
-#[inline]
-#[target_feature(enable = "avx2")]
-pub unsafe fn _mm256_sra_epi8_fallback(a: __m256i, count: __m128i) -> __m256i {
-    // Perform a 16-bit logical shift and then mask out garbage from adjacent lanes
-    let shift = _mm_cvtsi128_si64(count);
-    let mask = _mm256_set1_epi8((0xFFu8 >> shift) as i8);
-    let shifted = _mm256_and_si256(mask, _mm256_srl_epi16(a, count));
-    // Manual sign extension
-    let sign = _mm256_and_si256(a, _mm256_set1_epi8(1 << 7));
-    let extended = _mm256_sub_epi8(_mm256_setzero_si256(), _mm256_srl_epi16(sign, count));
-    _mm256_or_si256(extended, shifted)
-}
+                #[inline(always)]
+                fn concat_shift<const OFFSET: usize>(self, other: Self) -> Self {
+                    const { assert!(OFFSET <= Self::LANES, "offset out of bounds") };
+                    self.$permute(other, |i| i + OFFSET)
+                }
+            )?
 
-#[inline]
-#[target_feature(enable = "avx2")]
-pub unsafe fn _mm256_sra_epi64_fallback(a: __m256i, count: __m128i) -> __m256i {
-    // Perform a logical shift followed by manual sign extension
-    let sign = _mm256_and_si256(a, _mm256_set1_epi64x(1 << 63));
-    let extended = _mm256_sub_epi64(_mm256_setzero_si256(), _mm256_srl_epi64(sign, count));
-    _mm256_or_si256(extended, _mm256_srl_epi64(a, count))
-}
+            #[inline(always)]
+            fn reverse(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $vec) -> $vec {
+                    $vec($from_int($reverse($to_int(vec.0))))
+                }
 
-float_type! {
-    f32x8, __m256, f32, 8, m32x8,
-    _mm256_set1_ps, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_castps_si256, _mm256_castsi256_ps, _mm256_blendv_ps,
-    _mm256_cmp_ps, _mm256_max_ps, _mm256_min_ps, _mm256_add_ps, _mm256_sub_ps, _mm256_mul_ps, _mm256_div_ps, _mm256_xor_ps,
-}
-float_type! {
-    f64x4, __m256d, f64, 4, m64x4,
-    _mm256_set1_pd, _mm256_loadu_pd, _mm256_storeu_pd, _mm256_castpd_si256, _mm256_castsi256_pd, _mm256_blendv_pd,
-    _mm256_cmp_pd, _mm256_max_pd, _mm256_min_pd, _mm256_add_pd, _mm256_sub_pd, _mm256_mul_pd, _mm256_div_pd, _mm256_xor_pd,
-}
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn interleave_lo(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> $vec {
+                    // Unpacking works within 128-bit lanes, so first move the two low quarters of
+                    // each input into the same 128-bit lane
+                    let lhs = _mm256_permute4x64_epi64($to_int(lhs.0), 0xD8);
+                    let rhs = _mm256_permute4x64_epi64($to_int(rhs.0), 0xD8);
+                    $vec($from_int($unpacklo(lhs, rhs)))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn interleave_hi(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> $vec {
+                    let lhs = _mm256_permute4x64_epi64($to_int(lhs.0), 0xD8);
+                    let rhs = _mm256_permute4x64_epi64($to_int(rhs.0), 0xD8);
+                    $vec($from_int($unpackhi(lhs, rhs)))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn deinterleave(self, other: Self) -> (Self, Self) {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> ($vec, $vec) {
+                    let (even, odd) = $deinterleave($to_int(lhs.0), $to_int(rhs.0));
+                    ($vec($from_int(even)), $vec($from_int(odd)))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        $(
+            impl $vec {
+                /// Selects lane `index(i)` of `[self, other]` for each lane `i`.
+                #[inline(always)]
+                fn $permute(self, other: Self, index: impl Fn(usize) -> usize) -> Self {
+                    #[inline]
+                    #[target_feature(enable = "avx2")]
+                    unsafe fn inner(lhs: $vec, rhs: $vec, index: [usize; 8]) -> $vec {
+                        $vec($from_int(permute_epi32($to_int(lhs.0), $to_int(rhs.0), index)))
+                    }
+
+                    // Expand lane indices into indices of 32-bit lanes
+                    let dwords = 8 / Self::LANES;
+                    let index = array::from_fn(|i| index(i / dwords) * dwords + i % dwords);
+                    unsafe { inner(self, other, index) }
+                }
+            }
+        )?
+    };
+}
+
+macro_rules! impl_gather {
+    (
+        $vec:ident, $index:ident, $bias:ident, $from_int:ident, $gather:ident, $mask_gather:ident,
+        $scale:literal
+    ) => {
+        impl Gather<$index> for $vec {
+            type Index = $index;
+            type Values = Self;
+            type Masks = <Self as Simd>::Mask;
+
+            #[inline(always)]
+            unsafe fn gather_unchecked(base: &[Self::Elem], index: $index) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(base: &[<$vec as Simd>::Elem], index: $index) -> $vec {
+                    let (ptr, offsets) = $bias(base.as_ptr(), index.0);
+                    $vec($gather::<$scale>(ptr as _, offsets))
+                }
+
+                inner(base, index)
+            }
+
+            #[inline(always)]
+            unsafe fn gather_masked_unchecked(
+                base: &[Self::Elem],
+                index: $index,
+                mask: Self::Mask,
+                or: Self,
+            ) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(
+                    base: &[<$vec as Simd>::Elem],
+                    index: $index,
+                    mask: <$vec as Simd>::Mask,
+                    or: $vec,
+                ) -> $vec {
+                    let (ptr, offsets) = $bias(base.as_ptr(), index.0);
+                    $vec($mask_gather::<$scale>(or.0, ptr as _, offsets, $from_int(mask.0)))
+                }
+
+                inner(base, index, mask, or)
+            }
+        }
+    };
+    // 64-bit lanes through 32-bit indices, gathering one vector from each half of the index
+    (
+        [$vec:ident; 2], $index:ident, $bias:ident, $from_int:ident, $gather:ident,
+        $mask_gather:ident
+    ) => {
+        impl Gather<$index> for $vec {
+            type Index = $index;
+            type Values = [Self; 2];
+            type Masks = [<Self as Simd>::Mask; 2];
+
+            #[inline(always)]
+            unsafe fn gather_unchecked(base: &[Self::Elem], index: $index) -> [Self; 2] {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(base: &[<$vec as Simd>::Elem], index: $index) -> [$vec; 2] {
+                    let (ptr, offsets) = $bias(base.as_ptr(), index.0);
+                    let lo = _mm256_castsi256_si128(offsets);
+                    let hi = _mm256_extracti128_si256::<1>(offsets);
+                    [$vec($gather::<8>(ptr as _, lo)), $vec($gather::<8>(ptr as _, hi))]
+                }
+
+                inner(base, index)
+            }
+
+            #[inline(always)]
+            unsafe fn gather_masked_unchecked(
+                base: &[Self::Elem],
+                index: $index,
+                mask: [Self::Mask; 2],
+                or: [Self; 2],
+            ) -> [Self; 2] {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(
+                    base: &[<$vec as Simd>::Elem],
+                    index: $index,
+                    mask: [<$vec as Simd>::Mask; 2],
+                    or: [$vec; 2],
+                ) -> [$vec; 2] {
+                    let (ptr, offsets) = $bias(base.as_ptr(), index.0);
+                    let lo = _mm256_castsi256_si128(offsets);
+                    let hi = _mm256_extracti128_si256::<1>(offsets);
+                    [
+                        $vec($mask_gather::<8>(or[0].0, ptr as _, lo, $from_int(mask[0].0))),
+                        $vec($mask_gather::<8>(or[1].0, ptr as _, hi, $from_int(mask[1].0))),
+                    ]
+                }
+
+                inner(base, index, mask, or)
+            }
+        }
+    };
+    ($vec:ident, $($index:ident),*) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = $index;
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+    // Each vector of `$vec` is indexed by `$n` index vectors
+    ($vec:ident, [$($index:ident),*; $n:literal]) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = [$index; $n];
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+}
+
+macro_rules! impl_cast_bits {
+    ($from:ident, $to:ident) => {
+        impl Cast<$to> for $from {
+            #[inline(always)]
+            fn cast(self) -> $to {
+                $to(self.0)
+            }
+        }
+    };
+}
+
+macro_rules! impl_cast_lanes {
+    ($from:ident, $to:ident, $elem:ident) => {
+        // There are no packed conversions between 64-bit floats and integers before AVX-512, so
+        // convert each lane individually (this lowers to one cvt(t)sd2si per lane).
+        impl Cast<$to> for $from {
+            #[inline(always)]
+            fn cast(self) -> $to {
+                let mut res = $to::default();
+                for (out, x) in res.as_mut_slice().iter_mut().zip(self.as_slice()) {
+                    *out = *x as $elem;
+                }
+                res
+            }
+        }
+
+        impl RoundCast<$to> for $from {
+            #[inline(always)]
+            fn round_cast(self) -> $to {
+                let mut res = $to::default();
+                for (out, x) in res.as_mut_slice().iter_mut().zip(self.as_slice()) {
+                    *out = x.round_ties_even() as $elem;
+                }
+                res
+            }
+        }
+    };
+}
+
+macro_rules! impl_f32_to_int {
+    ($trait:ident, $method:ident, $cvt:ident) => {
+        impl $trait<i32x8> for f32x8 {
+            #[inline(always)]
+            fn $method(self) -> i32x8 {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: f32x8) -> i32x8 {
+                    // The conversion returns i32::MIN for NaN and out-of-range lanes, so saturate
+                    // positive overflow to i32::MAX and zero out NaNs to match `as`
+                    let res = $cvt(vec.0);
+                    let bound = _mm256_set1_ps(2147483648.0);
+                    let overflow = _mm256_castps_si256(_mm256_cmp_ps(vec.0, bound, _CMP_GE_OQ));
+                    let not_nan = _mm256_castps_si256(_mm256_cmp_ps(vec.0, vec.0, _CMP_ORD_Q));
+                    i32x8(_mm256_and_si256(_mm256_xor_si256(res, overflow), not_nan))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+
+        impl $trait<u32x8> for f32x8 {
+            #[inline(always)]
+            fn $method(self) -> u32x8 {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: f32x8) -> u32x8 {
+                    // maxps returns its second operand for NaN, so this also zeroes out NaNs
+                    let vec = _mm256_max_ps(vec.0, _mm256_setzero_ps());
+                    // Only a signed conversion is available, so bring lanes >= 2^31 into range
+                    // first and then restore the high bit
+                    let bias = _mm256_set1_ps(2147483648.0);
+                    let high = _mm256_cmp_ps(vec, bias, _CMP_GE_OQ);
+                    let res = $cvt(_mm256_sub_ps(vec, _mm256_and_ps(high, bias)));
+                    let res =
+                        _mm256_xor_si256(res, _mm256_slli_epi32(_mm256_castps_si256(high), 31));
+                    let bound = _mm256_set1_ps(4294967296.0);
+                    let overflow = _mm256_cmp_ps(vec, bound, _CMP_GE_OQ);
+                    u32x8(_mm256_or_si256(res, _mm256_castps_si256(overflow)))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_widen_int {
+    ($narrow:ident, $wide:ident, $extend:ident, $truncate:ident) => {
+        impl Widen for $narrow {
+            type Wide = $wide;
+            type Parts = [$wide; 2];
+
+            #[inline(always)]
+            fn widen(self) -> Self::Parts {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(vec: $narrow) -> [$wide; 2] {
+                    let low = _mm256_castsi256_si128(vec.0);
+                    let high = _mm256_extracti128_si256(vec.0, 1);
+                    [$wide($extend(low)), $wide($extend(high))]
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn narrow(parts: Self::Parts) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                unsafe fn inner(parts: [$wide; 2]) -> $narrow {
+                    $narrow($truncate(parts[0].0, parts[1].0))
+                }
+
+                unsafe { inner(parts) }
+            }
+        }
+    };
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_sra_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Perform a 16-bit logical shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm_set1_epi8((0xFFu8 >> shift) as i8);
+    let shifted = _mm_and_si128(mask, _mm_srl_epi16(a, count));
+    // Manual sign extension
+    let sign = _mm_and_si128(a, _mm_set1_epi8(1 << 7));
+    let extended = _mm_sub_epi8(_mm_setzero_si128(), _mm_srl_epi16(sign, count));
+    _mm_or_si128(extended, shifted)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_sra_epi64_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Perform a logical shift followed by manual sign extension
+    let sign = _mm_and_si128(a, _mm_set1_epi64x(1 << 63));
+    let extended = _mm_sub_epi64(_mm_setzero_si128(), _mm_srl_epi64(sign, count));
+    _mm_or_si128(extended, _mm_srl_epi64(a, count))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_sll_epi8_fallback(a: __m256i, count: __m128i) -> __m256i {
+    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm256_set1_epi8((0xFFu8 << shift) as i8);
+    _mm256_and_si256(mask, _mm256_sll_epi16(a, count))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_srl_epi8_fallback(a: __m256i, count: __m128i) -> __m256i {
+    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm256_set1_epi8((0xFFu8 >> shift) as i8);
+    _mm256_and_si256(mask, _mm256_srl_epi16(a, count))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_sra_epi8_fallback(a: __m256i, count: __m128i) -> __m256i {
+    // Perform a 16-bit logical shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm256_set1_epi8((0xFFu8 >> shift) as i8);
+    let shifted = _mm256_and_si256(mask, _mm256_srl_epi16(a, count));
+    // Manual sign extension
+    let sign = _mm256_and_si256(a, _mm256_set1_epi8(1 << 7));
+    let extended = _mm256_sub_epi8(_mm256_setzero_si256(), _mm256_srl_epi16(sign, count));
+    _mm256_or_si256(extended, shifted)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_sra_epi64_fallback(a: __m256i, count: __m128i) -> __m256i {
+    // Perform a logical shift followed by manual sign extension
+    let sign = _mm256_and_si256(a, _mm256_set1_epi64x(1 << 63));
+    let extended = _mm256_sub_epi64(_mm256_setzero_si256(), _mm256_srl_epi64(sign, count));
+    _mm256_or_si256(extended, _mm256_srl_epi64(a, count))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_sllv_epi8_fallback(a: __m256i, count: __m256i) -> __m256i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two. Moving the count into place with a 16-bit shift leaves garbage
+    // in the low bits of each byte, but those bits are never tested.
+    let mut count = _mm256_slli_epi16(count, 5);
+    let mut res = a;
+    for shift in [4, 2, 1] {
+        let mask = _mm256_cmpgt_epi8(_mm256_setzero_si256(), count);
+        let shifted = _mm256_sll_epi8_fallback(res, _mm_cvtsi32_si128(shift));
+        res = _mm256_or_si256(
+            _mm256_and_si256(mask, shifted),
+            _mm256_andnot_si256(mask, res),
+        );
+        count = _mm256_add_epi8(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_srlv_epi8_fallback(a: __m256i, count: __m256i) -> __m256i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two. Moving the count into place with a 16-bit shift leaves garbage
+    // in the low bits of each byte, but those bits are never tested.
+    let mut count = _mm256_slli_epi16(count, 5);
+    let mut res = a;
+    for shift in [4, 2, 1] {
+        let mask = _mm256_cmpgt_epi8(_mm256_setzero_si256(), count);
+        let shifted = _mm256_srl_epi8_fallback(res, _mm_cvtsi32_si128(shift));
+        res = _mm256_or_si256(
+            _mm256_and_si256(mask, shifted),
+            _mm256_andnot_si256(mask, res),
+        );
+        count = _mm256_add_epi8(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_srav_epi8_fallback(a: __m256i, count: __m256i) -> __m256i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two. Moving the count into place with a 16-bit shift leaves garbage
+    // in the low bits of each byte, but those bits are never tested.
+    let mut count = _mm256_slli_epi16(count, 5);
+    let mut res = a;
+    for shift in [4, 2, 1] {
+        let mask = _mm256_cmpgt_epi8(_mm256_setzero_si256(), count);
+        let shifted = _mm256_sra_epi8_fallback(res, _mm_cvtsi32_si128(shift));
+        res = _mm256_or_si256(
+            _mm256_and_si256(mask, shifted),
+            _mm256_andnot_si256(mask, res),
+        );
+        count = _mm256_add_epi8(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_sllv_epi16_fallback(a: __m256i, count: __m256i) -> __m256i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm256_slli_epi16(count, 12);
+    let mut res = a;
+    for shift in [8, 4, 2, 1] {
+        let mask = _mm256_srai_epi16(count, 15);
+        let shifted = _mm256_sll_epi16(res, _mm_cvtsi32_si128(shift));
+        res = _mm256_or_si256(
+            _mm256_and_si256(mask, shifted),
+            _mm256_andnot_si256(mask, res),
+        );
+        count = _mm256_add_epi16(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_srlv_epi16_fallback(a: __m256i, count: __m256i) -> __m256i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm256_slli_epi16(count, 12);
+    let mut res = a;
+    for shift in [8, 4, 2, 1] {
+        let mask = _mm256_srai_epi16(count, 15);
+        let shifted = _mm256_srl_epi16(res, _mm_cvtsi32_si128(shift));
+        res = _mm256_or_si256(
+            _mm256_and_si256(mask, shifted),
+            _mm256_andnot_si256(mask, res),
+        );
+        count = _mm256_add_epi16(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_srav_epi16_fallback(a: __m256i, count: __m256i) -> __m256i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm256_slli_epi16(count, 12);
+    let mut res = a;
+    for shift in [8, 4, 2, 1] {
+        let mask = _mm256_srai_epi16(count, 15);
+        let shifted = _mm256_sra_epi16(res, _mm_cvtsi32_si128(shift));
+        res = _mm256_or_si256(
+            _mm256_and_si256(mask, shifted),
+            _mm256_andnot_si256(mask, res),
+        );
+        count = _mm256_add_epi16(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_srav_epi64_fallback(a: __m256i, count: __m256i) -> __m256i {
+    // Flip negative lanes so that a logical shift fills in sign bits, and then flip them back
+    let sign = broadcast_sign_epi64(a);
+    _mm256_xor_si256(_mm256_srlv_epi64(_mm256_xor_si256(a, sign), count), sign)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn broadcast_sign_epi64(a: __m256i) -> __m256i {
+    _mm256_cmpgt_epi64(_mm256_setzero_si256(), a)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_adds_epu32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Saturate wherever the addition carries out of the top bit
+    let sum = _mm256_add_epi32(a, b);
+    let carry = _mm256_or_si256(
+        _mm256_and_si256(a, b),
+        _mm256_andnot_si256(sum, _mm256_or_si256(a, b)),
+    );
+    _mm256_or_si256(sum, _mm256_srai_epi32(carry, 31))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_subs_epu32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Saturate wherever the subtraction borrows from beyond the top bit
+    let diff = _mm256_sub_epi32(a, b);
+    let borrow = _mm256_or_si256(
+        _mm256_andnot_si256(a, b),
+        _mm256_andnot_si256(_mm256_xor_si256(a, b), diff),
+    );
+    _mm256_andnot_si256(_mm256_srai_epi32(borrow, 31), diff)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_adds_epi32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Overflow occurs when the inputs have the same sign and the result has the opposite sign,
+    // in which case saturate toward the sign of the inputs
+    let sum = _mm256_add_epi32(a, b);
+    let overflow = _mm256_srai_epi32(
+        _mm256_and_si256(_mm256_xor_si256(sum, a), _mm256_xor_si256(sum, b)),
+        31,
+    );
+    let saturated = _mm256_xor_si256(_mm256_srai_epi32(a, 31), _mm256_set1_epi32(i32::MAX));
+    _mm256_or_si256(
+        _mm256_and_si256(overflow, saturated),
+        _mm256_andnot_si256(overflow, sum),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_subs_epi32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Overflow occurs when the inputs have opposite signs and the result has the sign of the
+    // subtrahend, in which case saturate toward the sign of the minuend
+    let diff = _mm256_sub_epi32(a, b);
+    let overflow = _mm256_srai_epi32(
+        _mm256_and_si256(_mm256_xor_si256(a, b), _mm256_xor_si256(a, diff)),
+        31,
+    );
+    let saturated = _mm256_xor_si256(_mm256_srai_epi32(a, 31), _mm256_set1_epi32(i32::MAX));
+    _mm256_or_si256(
+        _mm256_and_si256(overflow, saturated),
+        _mm256_andnot_si256(overflow, diff),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_adds_epu64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Saturate wherever the addition carries out of the top bit
+    let sum = _mm256_add_epi64(a, b);
+    let carry = _mm256_or_si256(
+        _mm256_and_si256(a, b),
+        _mm256_andnot_si256(sum, _mm256_or_si256(a, b)),
+    );
+    _mm256_or_si256(sum, broadcast_sign_epi64(carry))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_subs_epu64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Saturate wherever the subtraction borrows from beyond the top bit
+    let diff = _mm256_sub_epi64(a, b);
+    let borrow = _mm256_or_si256(
+        _mm256_andnot_si256(a, b),
+        _mm256_andnot_si256(_mm256_xor_si256(a, b), diff),
+    );
+    _mm256_andnot_si256(broadcast_sign_epi64(borrow), diff)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_adds_epi64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Overflow occurs when the inputs have the same sign and the result has the opposite sign,
+    // in which case saturate toward the sign of the inputs
+    let sum = _mm256_add_epi64(a, b);
+    let overflow = broadcast_sign_epi64(_mm256_and_si256(
+        _mm256_xor_si256(sum, a),
+        _mm256_xor_si256(sum, b),
+    ));
+    let saturated = _mm256_xor_si256(broadcast_sign_epi64(a), _mm256_set1_epi64x(i64::MAX));
+    _mm256_or_si256(
+        _mm256_and_si256(overflow, saturated),
+        _mm256_andnot_si256(overflow, sum),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_subs_epi64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Overflow occurs when the inputs have opposite signs and the result has the sign of the
+    // subtrahend, in which case saturate toward the sign of the minuend
+    let diff = _mm256_sub_epi64(a, b);
+    let overflow = broadcast_sign_epi64(_mm256_and_si256(
+        _mm256_xor_si256(a, b),
+        _mm256_xor_si256(a, diff),
+    ));
+    let saturated = _mm256_xor_si256(broadcast_sign_epi64(a), _mm256_set1_epi64x(i64::MAX));
+    _mm256_or_si256(
+        _mm256_and_si256(overflow, saturated),
+        _mm256_andnot_si256(overflow, diff),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_avg_epi8_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Bias into the unsigned range, average, and then remove the bias
+    let bias = _mm256_set1_epi8(i8::MIN);
+    _mm256_xor_si256(
+        _mm256_avg_epu8(_mm256_xor_si256(a, bias), _mm256_xor_si256(b, bias)),
+        bias,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_avg_epi16_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Bias into the unsigned range, average, and then remove the bias
+    let bias = _mm256_set1_epi16(i16::MIN);
+    _mm256_xor_si256(
+        _mm256_avg_epu16(_mm256_xor_si256(a, bias), _mm256_xor_si256(b, bias)),
+        bias,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_avg_epu32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // (a | b) - ((a ^ b) >> 1) rounds the average up without overflowing
+    _mm256_sub_epi32(
+        _mm256_or_si256(a, b),
+        _mm256_srli_epi32(_mm256_xor_si256(a, b), 1),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_avg_epi32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // See _mm256_avg_epu32_fallback
+    _mm256_sub_epi32(
+        _mm256_or_si256(a, b),
+        _mm256_srai_epi32(_mm256_xor_si256(a, b), 1),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_avg_epu64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // See _mm256_avg_epu32_fallback
+    _mm256_sub_epi64(
+        _mm256_or_si256(a, b),
+        _mm256_srli_epi64(_mm256_xor_si256(a, b), 1),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_avg_epi64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // See _mm256_avg_epu32_fallback
+    _mm256_sub_epi64(
+        _mm256_or_si256(a, b),
+        _mm256_sra_epi64_fallback(_mm256_xor_si256(a, b), _mm_cvtsi32_si128(1)),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_mulhi_epu8_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Multiply even and odd bytes separately in 16-bit lanes and keep the high byte of each
+    let mask = _mm256_set1_epi16(0xFF);
+    let even = _mm256_mullo_epi16(_mm256_and_si256(a, mask), _mm256_and_si256(b, mask));
+    let odd = _mm256_mullo_epi16(_mm256_srli_epi16(a, 8), _mm256_srli_epi16(b, 8));
+    _mm256_or_si256(_mm256_srli_epi16(even, 8), _mm256_andnot_si256(mask, odd))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_mulhi_epi8_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Multiply even and odd bytes separately in 16-bit lanes and keep the high byte of each
+    let mask = _mm256_set1_epi16(0xFF);
+    let a_even = _mm256_srai_epi16(_mm256_slli_epi16(a, 8), 8);
+    let b_even = _mm256_srai_epi16(_mm256_slli_epi16(b, 8), 8);
+    let even = _mm256_mullo_epi16(a_even, b_even);
+    let odd = _mm256_mullo_epi16(_mm256_srai_epi16(a, 8), _mm256_srai_epi16(b, 8));
+    _mm256_or_si256(_mm256_srli_epi16(even, 8), _mm256_andnot_si256(mask, odd))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_mulhi_epu32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Multiply even and odd lanes separately in 64-bit lanes and keep the high half of each
+    let even = _mm256_mul_epu32(a, b);
+    let odd = _mm256_mul_epu32(_mm256_srli_epi64(a, 32), _mm256_srli_epi64(b, 32));
+    _mm256_blend_epi32(_mm256_srli_epi64(even, 32), odd, 0xAA)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_mulhi_epi32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Multiply even and odd lanes separately in 64-bit lanes and keep the high half of each
+    let even = _mm256_mul_epi32(a, b);
+    let odd = _mm256_mul_epi32(_mm256_srli_epi64(a, 32), _mm256_srli_epi64(b, 32));
+    _mm256_blend_epi32(_mm256_srli_epi64(even, 32), odd, 0xAA)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_mulhi_epu64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Sum the four 32x32-bit partial products
+    let mask = _mm256_set1_epi64x(0xFFFFFFFF);
+    let a_high = _mm256_srli_epi64(a, 32);
+    let b_high = _mm256_srli_epi64(b, 32);
+    let low_low = _mm256_mul_epu32(a, b);
+    let low_high = _mm256_mul_epu32(a, b_high);
+    let high_low = _mm256_mul_epu32(a_high, b);
+    let high_high = _mm256_mul_epu32(a_high, b_high);
+    // The middle column cannot overflow, as it is a sum of three 32-bit values
+    let middle = _mm256_add_epi64(
+        _mm256_add_epi64(
+            _mm256_srli_epi64(low_low, 32),
+            _mm256_and_si256(low_high, mask),
+        ),
+        _mm256_and_si256(high_low, mask),
+    );
+    let high = _mm256_add_epi64(
+        _mm256_add_epi64(high_high, _mm256_srli_epi64(low_high, 32)),
+        _mm256_srli_epi64(high_low, 32),
+    );
+    _mm256_add_epi64(high, _mm256_srli_epi64(middle, 32))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_mulhi_epi64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Correct the unsigned product for negative inputs, each of which contributes an extra
+    // 2^64 times the other input
+    let high = _mm256_mulhi_epu64_fallback(a, b);
+    let a_correction = _mm256_and_si256(broadcast_sign_epi64(a), b);
+    let b_correction = _mm256_and_si256(broadcast_sign_epi64(b), a);
+    _mm256_sub_epi64(_mm256_sub_epi64(high, a_correction), b_correction)
+}
+
+// Integer division is performed in floating point where the result is guaranteed to be exact
+// after truncation, i.e. where the inputs are representable and the gap between the quotient and
+// the next integer always exceeds the rounding error.
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_div_epu8_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Divide in 16-bit lanes and then truncate
+    let low = _mm256_div_epu16_fallback(
+        _mm256_cvtepu8_epi16(_mm256_castsi256_si128(a)),
+        _mm256_cvtepu8_epi16(_mm256_castsi256_si128(b)),
+    );
+    let high = _mm256_div_epu16_fallback(
+        _mm256_cvtepu8_epi16(_mm256_extracti128_si256(a, 1)),
+        _mm256_cvtepu8_epi16(_mm256_extracti128_si256(b, 1)),
+    );
+    truncate_epi16(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_div_epi8_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Divide in 16-bit lanes and then truncate, which also wraps MIN / -1
+    let low = _mm256_div_epi16_fallback(
+        _mm256_cvtepi8_epi16(_mm256_castsi256_si128(a)),
+        _mm256_cvtepi8_epi16(_mm256_castsi256_si128(b)),
+    );
+    let high = _mm256_div_epi16_fallback(
+        _mm256_cvtepi8_epi16(_mm256_extracti128_si256(a, 1)),
+        _mm256_cvtepi8_epi16(_mm256_extracti128_si256(b, 1)),
+    );
+    truncate_epi16(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn div_epi32_ps(a: __m256i, b: __m256i) -> __m256i {
+    _mm256_cvttps_epi32(_mm256_div_ps(_mm256_cvtepi32_ps(a), _mm256_cvtepi32_ps(b)))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_div_epu16_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Divide in single precision. Division by zero converts to 0x80000000, which truncates to
+    // zero.
+    let low = div_epi32_ps(
+        _mm256_cvtepu16_epi32(_mm256_castsi256_si128(a)),
+        _mm256_cvtepu16_epi32(_mm256_castsi256_si128(b)),
+    );
+    let high = div_epi32_ps(
+        _mm256_cvtepu16_epi32(_mm256_extracti128_si256(a, 1)),
+        _mm256_cvtepu16_epi32(_mm256_extracti128_si256(b, 1)),
+    );
+    truncate_epi32(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_div_epi16_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Divide in single precision. Truncating the result wraps MIN / -1, and division by zero
+    // converts to 0x80000000, which truncates to zero.
+    let low = div_epi32_ps(
+        _mm256_cvtepi16_epi32(_mm256_castsi256_si128(a)),
+        _mm256_cvtepi16_epi32(_mm256_castsi256_si128(b)),
+    );
+    let high = div_epi32_ps(
+        _mm256_cvtepi16_epi32(_mm256_extracti128_si256(a, 1)),
+        _mm256_cvtepi16_epi32(_mm256_extracti128_si256(b, 1)),
+    );
+    truncate_epi32(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn div_epu32_pd(a: __m128i, b: __m128i) -> __m128i {
+    // Convert from unsigned by flipping the top bit and adding back 2^31
+    let bias = _mm256_set1_pd(2147483648.0);
+    let flip = _mm_set1_epi32(i32::MIN);
+    let a = _mm256_add_pd(_mm256_cvtepi32_pd(_mm_xor_si128(a, flip)), bias);
+    let b = _mm256_add_pd(_mm256_cvtepi32_pd(_mm_xor_si128(b, flip)), bias);
+    let quotient = _mm256_round_pd(_mm256_div_pd(a, b), _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC);
+    _mm_xor_si128(_mm256_cvttpd_epi32(_mm256_sub_pd(quotient, bias)), flip)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_div_epu32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Divide in double precision
+    let low = div_epu32_pd(_mm256_castsi256_si128(a), _mm256_castsi256_si128(b));
+    let high = div_epu32_pd(
+        _mm256_extracti128_si256(a, 1),
+        _mm256_extracti128_si256(b, 1),
+    );
+    let quotient = _mm256_set_m128i(high, low);
+    _mm256_andnot_si256(_mm256_cmpeq_epi32(b, _mm256_setzero_si256()), quotient)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn div_epi32_pd(a: __m128i, b: __m128i) -> __m128i {
+    _mm256_cvttpd_epi32(_mm256_div_pd(_mm256_cvtepi32_pd(a), _mm256_cvtepi32_pd(b)))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_div_epi32_fallback(a: __m256i, b: __m256i) -> __m256i {
+    // Divide in double precision. MIN / -1 is out of range, and converts to MIN as desired.
+    let low = div_epi32_pd(_mm256_castsi256_si128(a), _mm256_castsi256_si128(b));
+    let high = div_epi32_pd(
+        _mm256_extracti128_si256(a, 1),
+        _mm256_extracti128_si256(b, 1),
+    );
+    let quotient = _mm256_set_m128i(high, low);
+    _mm256_andnot_si256(_mm256_cmpeq_epi32(b, _mm256_setzero_si256()), quotient)
+}
+
+// There is no floating-point type wide enough to divide 64-bit integers exactly, so fall back to
+// scalar division
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_div_epu64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    let a: [u64; 4] = mem::transmute(a);
+    let b: [u64; 4] = mem::transmute(b);
+    let div = |i: usize| a[i].checked_div(b[i]).unwrap_or(0);
+    mem::transmute([div(0), div(1), div(2), div(3)])
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn _mm256_div_epi64_fallback(a: __m256i, b: __m256i) -> __m256i {
+    let a: [i64; 4] = mem::transmute(a);
+    let b: [i64; 4] = mem::transmute(b);
+    let div = |i: usize| {
+        if b[i] == 0 {
+            0
+        } else {
+            a[i].wrapping_div(b[i])
+        }
+    };
+    mem::transmute([div(0), div(1), div(2), div(3)])
+}
+
+// There are no approximate reciprocal instructions for f64, so compute them precisely
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rcp_pd(a: __m256d) -> __m256d {
+    _mm256_div_pd(_mm256_set1_pd(1.0), a)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rsqrt_pd(a: __m256d) -> __m256d {
+    _mm256_div_pd(_mm256_set1_pd(1.0), _mm256_sqrt_pd(a))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn movemask_epi8(a: __m256i) -> u64 {
+    _mm256_movemask_epi8(a) as u32 as u64
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn movemask_epi16(a: __m256i) -> u64 {
+    let packed = _mm_packs_epi16(_mm256_castsi256_si128(a), _mm256_extracti128_si256(a, 1));
+    _mm_movemask_epi8(packed) as u32 as u64
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn movemask_epi32(a: __m256i) -> u64 {
+    _mm256_movemask_ps(_mm256_castsi256_ps(a)) as u32 as u64
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn movemask_epi64(a: __m256i) -> u64 {
+    _mm256_movemask_pd(_mm256_castsi256_pd(a)) as u32 as u64
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn expand_bits_epi8(bits: u64) -> __m256i {
+    // Broadcast each of the four low bytes across one 64-bit lane, then test one bit per byte
+    let spread = |i: u32| (((bits >> (8 * i)) & 0xFF).wrapping_mul(0x0101010101010101)) as i64;
+    let bytes = _mm256_setr_epi64x(spread(0), spread(1), spread(2), spread(3));
+    let select = _mm256_set1_epi64x(0x8040201008040201u64 as i64);
+    _mm256_cmpeq_epi8(_mm256_and_si256(bytes, select), select)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn expand_bits_epi16(bits: u64) -> __m256i {
+    #[rustfmt::skip]
+    let select = _mm256_setr_epi16(
+        1 << 0, 1 << 1, 1 << 2, 1 << 3, 1 << 4, 1 << 5, 1 << 6, 1 << 7,
+        1 << 8, 1 << 9, 1 << 10, 1 << 11, 1 << 12, 1 << 13, 1 << 14, i16::MIN,
+    );
+    _mm256_cmpeq_epi16(
+        _mm256_and_si256(_mm256_set1_epi16(bits as i16), select),
+        select,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn expand_bits_epi32(bits: u64) -> __m256i {
+    let select = _mm256_setr_epi32(1, 2, 4, 8, 16, 32, 64, 128);
+    _mm256_cmpeq_epi32(
+        _mm256_and_si256(_mm256_set1_epi32(bits as i32), select),
+        select,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn expand_bits_epi64(bits: u64) -> __m256i {
+    let select = _mm256_setr_epi64x(1, 2, 4, 8);
+    _mm256_cmpeq_epi64(
+        _mm256_and_si256(_mm256_set1_epi64x(bits as i64), select),
+        select,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn truncate_epi16(a: __m256i, b: __m256i) -> __m256i {
+    // Clear the high bytes so that the saturating pack leaves the low bytes unchanged, then undo
+    // the per-128-bit-lane interleaving of the pack
+    let mask = _mm256_set1_epi16(0x00FF);
+    let res = _mm256_packus_epi16(_mm256_and_si256(a, mask), _mm256_and_si256(b, mask));
+    _mm256_permute4x64_epi64(res, 0xD8)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn truncate_epi32(a: __m256i, b: __m256i) -> __m256i {
+    let mask = _mm256_set1_epi32(0xFFFF);
+    let res = _mm256_packus_epi32(_mm256_and_si256(a, mask), _mm256_and_si256(b, mask));
+    _mm256_permute4x64_epi64(res, 0xD8)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn truncate_epi64(a: __m256i, b: __m256i) -> __m256i {
+    // Gather the low halves of each vector into its low 128 bits, then combine
+    let idx = _mm256_setr_epi32(0, 2, 4, 6, 0, 2, 4, 6);
+    let a = _mm256_permutevar8x32_epi32(a, idx);
+    let b = _mm256_permutevar8x32_epi32(b, idx);
+    _mm256_permute2x128_si256(a, b, 0x20)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn permute_epi32(a: __m256i, b: __m256i, index: [usize; 8]) -> __m256i {
+    // Permute both inputs by the low three bits of each index, and then take lanes from `b` where
+    // the index is 8 or greater
+    let index = index.map(|i| i as i32);
+    let index = _mm256_loadu_si256(index.as_ptr() as *const __m256i);
+    let from_a = _mm256_permutevar8x32_epi32(a, index);
+    let from_b = _mm256_permutevar8x32_epi32(b, index);
+    _mm256_blendv_epi8(
+        from_a,
+        from_b,
+        _mm256_cmpgt_epi32(index, _mm256_set1_epi32(7)),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn reverse_epi8(a: __m256i) -> __m256i {
+    // Reverse bytes within each 128-bit lane, then swap the 128-bit lanes
+    #[rustfmt::skip]
+    let index = _mm256_setr_epi8(
+        15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+        15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    );
+    _mm256_permute4x64_epi64(_mm256_shuffle_epi8(a, index), 0x4E)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn reverse_epi16(a: __m256i) -> __m256i {
+    // Reverse 16-bit lanes within each 128-bit lane, then swap the 128-bit lanes
+    #[rustfmt::skip]
+    let index = _mm256_setr_epi8(
+        14, 15, 12, 13, 10, 11, 8, 9, 6, 7, 4, 5, 2, 3, 0, 1,
+        14, 15, 12, 13, 10, 11, 8, 9, 6, 7, 4, 5, 2, 3, 0, 1,
+    );
+    _mm256_permute4x64_epi64(_mm256_shuffle_epi8(a, index), 0x4E)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn reverse_epi32(a: __m256i) -> __m256i {
+    _mm256_permutevar8x32_epi32(a, _mm256_setr_epi32(7, 6, 5, 4, 3, 2, 1, 0))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn reverse_epi64(a: __m256i) -> __m256i {
+    _mm256_permute4x64_epi64(a, 0x1B)
+}
+
+// Even lanes are the low halves of lanes twice as wide, and odd lanes are the high halves
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn deinterleave_epi8(a: __m256i, b: __m256i) -> (__m256i, __m256i) {
+    let even = truncate_epi16(a, b);
+    let odd = truncate_epi16(_mm256_srli_epi16(a, 8), _mm256_srli_epi16(b, 8));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn deinterleave_epi16(a: __m256i, b: __m256i) -> (__m256i, __m256i) {
+    let even = truncate_epi32(a, b);
+    let odd = truncate_epi32(_mm256_srli_epi32(a, 16), _mm256_srli_epi32(b, 16));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn deinterleave_epi32(a: __m256i, b: __m256i) -> (__m256i, __m256i) {
+    let even = truncate_epi64(a, b);
+    let odd = truncate_epi64(_mm256_srli_epi64(a, 32), _mm256_srli_epi64(b, 32));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn deinterleave_epi64(a: __m256i, b: __m256i) -> (__m256i, __m256i) {
+    // Unpacking works within 128-bit lanes, so the results need their middle quarters swapped
+    let even = _mm256_permute4x64_epi64(_mm256_unpacklo_epi64(a, b), 0xD8);
+    let odd = _mm256_permute4x64_epi64(_mm256_unpackhi_epi64(a, b), 0xD8);
+    (even, odd)
+}
+
+/// Returns a mask of the 32-bit lanes below `len`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn prefix_mask_epi32(len: usize) -> __m256i {
+    let len = _mm256_set1_epi32(len.min(8) as i32);
+    _mm256_cmpgt_epi32(len, _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7))
+}
+
+/// Returns a mask of the 64-bit lanes below `len`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn prefix_mask_epi64(len: usize) -> __m256i {
+    let len = _mm256_set1_epi64x(len.min(4) as i64);
+    _mm256_cmpgt_epi64(len, _mm256_setr_epi64x(0, 1, 2, 3))
+}
+
+/// Gathers treat 32-bit indices as signed, so move the base pointer forward by 2^31 elements and
+/// flip the sign bit of each index, which reaches the same elements for every `u32` index.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn bias_index_epu32<T>(ptr: *const T, index: __m256i) -> (*const T, __m256i) {
+    let index = _mm256_xor_si256(index, _mm256_set1_epi32(i32::MIN));
+    (ptr.wrapping_add(1 << 31), index)
+}
+
+/// Signed indices, and 64-bit indices that are in bounds, are used as they are.
+#[inline(always)]
+fn unbiased_index<T>(ptr: *const T, index: __m256i) -> (*const T, __m256i) {
+    (ptr, index)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn cvtepu32_ps(a: __m256i) -> __m256 {
+    // Convert the high and low 16 bits separately; both conversions and the multiplication are
+    // exact, so the final addition is the only rounding step
+    let high = _mm256_cvtepi32_ps(_mm256_srli_epi32(a, 16));
+    let low = _mm256_cvtepi32_ps(_mm256_and_si256(a, _mm256_set1_epi32(0xFFFF)));
+    _mm256_add_ps(_mm256_mul_ps(high, _mm256_set1_ps(65536.0)), low)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn cvtepu64_pd(a: __m256i) -> __m256d {
+    // Place the high and low 32 bits in the mantissas of 2^84 and 2^52 respectively, subtract
+    // the offsets exactly, and then perform a single rounding addition
+    let high = _mm256_or_si256(
+        _mm256_srli_epi64(a, 32),
+        _mm256_set1_epi64x(0x4530000000000000),
+    );
+    let low = _mm256_blend_epi32(a, _mm256_set1_epi64x(0x4330000000000000), 0xAA);
+    let offset = _mm256_set1_pd(f64::from_bits(0x4530000000100000));
+    let high = _mm256_sub_pd(_mm256_castsi256_pd(high), offset);
+    _mm256_add_pd(high, _mm256_castsi256_pd(low))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn cvtepi64_pd(a: __m256i) -> __m256d {
+    // Same as cvtepu64_pd, but with the high 32 bits biased by 2^31 to make them unsigned
+    let high = _mm256_xor_si256(
+        _mm256_srli_epi64(a, 32),
+        _mm256_set1_epi64x(0x4530000080000000),
+    );
+    let low = _mm256_blend_epi32(a, _mm256_set1_epi64x(0x4330000000000000), 0xAA);
+    let offset = _mm256_set1_pd(f64::from_bits(0x4530000080100000));
+    let high = _mm256_sub_pd(_mm256_castsi256_pd(high), offset);
+    _mm256_add_pd(high, _mm256_castsi256_pd(low))
+}
+
+float_type! {
+    f32x8, __m256, f32, 8, m32x8, u32x8,
+    _mm256_set1_ps, _mm256_loadu_ps, _mm256_load_ps, _mm256_storeu_ps, _mm256_stream_ps, _mm256_castps_si256, _mm256_castsi256_ps, _mm256_blendv_ps,
+    _mm256_cmp_ps, _mm256_max_ps, _mm256_min_ps, _mm256_add_ps, _mm256_sub_ps, _mm256_mul_ps, _mm256_div_ps, _mm256_xor_ps,
+    _mm256_and_ps, _mm256_or_ps, _mm256_andnot_ps, _mm256_fmadd_ps, _mm256_sqrt_ps, _mm256_rcp_ps,
+    _mm256_rsqrt_ps, _mm256_round_ps, prefix_mask_epi32, _mm256_maskload_ps, _mm256_maskstore_ps,
+}
+float_type! {
+    f64x4, __m256d, f64, 4, m64x4, u64x4,
+    _mm256_set1_pd, _mm256_loadu_pd, _mm256_load_pd, _mm256_storeu_pd, _mm256_stream_pd, _mm256_castpd_si256, _mm256_castsi256_pd, _mm256_blendv_pd,
+    _mm256_cmp_pd, _mm256_max_pd, _mm256_min_pd, _mm256_add_pd, _mm256_sub_pd, _mm256_mul_pd, _mm256_div_pd, _mm256_xor_pd,
+    _mm256_and_pd, _mm256_or_pd, _mm256_andnot_pd, _mm256_fmadd_pd, _mm256_sqrt_pd, rcp_pd,
+    rsqrt_pd, _mm256_round_pd, prefix_mask_epi64, _mm256_maskload_pd, _mm256_maskstore_pd,
+}
+
+impl_f32_to_int! { Cast, cast, _mm256_cvttps_epi32 }
+impl_f32_to_int! { RoundCast, round_cast, _mm256_cvtps_epi32 }
+impl_cast_lanes! { f64x4, i64x4, i64 }
+impl_cast_lanes! { f64x4, u64x4, u64 }
+impl_gather! { f32x8, u32x8, bias_index_epu32, _mm256_castsi256_ps, _mm256_i32gather_ps, _mm256_mask_i32gather_ps, 4 }
+impl_gather! { f32x8, i32x8, unbiased_index, _mm256_castsi256_ps, _mm256_i32gather_ps, _mm256_mask_i32gather_ps, 4 }
+impl_gather! { f64x4, u64x4, unbiased_index, _mm256_castsi256_pd, _mm256_i64gather_pd, _mm256_mask_i64gather_pd, 8 }
+impl_gather! { f64x4, i64x4, unbiased_index, _mm256_castsi256_pd, _mm256_i64gather_pd, _mm256_mask_i64gather_pd, 8 }
+impl_gather! { [f64x4; 2], u32x8, bias_index_epu32, _mm256_castsi256_pd, _mm256_i32gather_pd, _mm256_mask_i32gather_pd }
+impl_gather! { [f64x4; 2], i32x8, unbiased_index, _mm256_castsi256_pd, _mm256_i32gather_pd, _mm256_mask_i32gather_pd }
+impl_shuffle! { f32x8, _mm256_castps_si256, _mm256_castsi256_ps, reverse_epi32, _mm256_unpacklo_epi32, _mm256_unpackhi_epi32, deinterleave_epi32, permute }
+impl_shuffle! { f64x4, _mm256_castpd_si256, _mm256_castsi256_pd, reverse_epi64, _mm256_unpacklo_epi64, _mm256_unpackhi_epi64, deinterleave_epi64, permute }
+
+impl Widen for f32x8 {
+    type Wide = f64x4;
+    type Parts = [f64x4; 2];
+
+    #[inline(always)]
+    fn widen(self) -> Self::Parts {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn inner(vec: f32x8) -> [f64x4; 2] {
+            let low = _mm256_castps256_ps128(vec.0);
+            let high = _mm256_extractf128_ps(vec.0, 1);
+            [f64x4(_mm256_cvtps_pd(low)), f64x4(_mm256_cvtps_pd(high))]
+        }
+
+        unsafe { inner(self) }
+    }
+
+    #[inline(always)]
+    fn narrow(parts: Self::Parts) -> Self {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn inner(parts: [f64x4; 2]) -> f32x8 {
+            let low = _mm256_cvtpd_ps(parts[0].0);
+            let high = _mm256_cvtpd_ps(parts[1].0);
+            f32x8(_mm256_set_m128(high, low))
+        }
+
+        unsafe { inner(parts) }
+    }
+}
 
 int_type! { u8x32, u8, 32, m8x32, _mm256_set1_epi8, _mm256_cmpeq_epi8 }
 int_type! { u16x16, u16, 16, m16x16, _mm256_set1_epi16, _mm256_cmpeq_epi16 }
-int_type! { u32x8, u32, 8, m32x8, _mm256_set1_epi32, _mm256_cmpeq_epi32 }
-int_type! { u64x4, u64, 4, m64x4, _mm256_set1_epi64x, _mm256_cmpeq_epi64 }
+int_type! { u32x8, u32, 8, m32x8, _mm256_set1_epi32, _mm256_cmpeq_epi32, prefix_mask_epi32, _mm256_maskload_epi32, _mm256_maskstore_epi32 }
+int_type! { u64x4, u64, 4, m64x4, _mm256_set1_epi64x, _mm256_cmpeq_epi64, prefix_mask_epi64, _mm256_maskload_epi64, _mm256_maskstore_epi64 }
 impl_ord_uint! { u8x32, m8x32, _mm256_cmpeq_epi8, _mm256_max_epu8, _mm256_min_epu8 }
 impl_ord_uint! { u16x16, m16x16, _mm256_cmpeq_epi16, _mm256_max_epu16, _mm256_min_epu16 }
 impl_ord_uint! { u32x8, m32x8, _mm256_cmpeq_epi32, _mm256_max_epu32, _mm256_min_epu32 }
-impl_int! { u8x32, u8, _mm256_set1_epi8, _mm256_add_epi8, _mm256_sub_epi8, _mm256_sll_epi8_fallback, _mm256_srl_epi8_fallback }
-impl_int! { u16x16, u16, _mm256_set1_epi16, _mm256_add_epi16, _mm256_sub_epi16, _mm256_sll_epi16, _mm256_srl_epi16 }
-impl_int! { u32x8, u32, _mm256_set1_epi32, _mm256_add_epi32, _mm256_sub_epi32, _mm256_sll_epi32, _mm256_srl_epi32 }
-impl_int! { u64x4, u64, _mm256_set1_epi64x, _mm256_add_epi64, _mm256_sub_epi64, _mm256_sll_epi64, _mm256_srl_epi64 }
+impl_int! { u8x32, u8, _mm256_set1_epi8, _mm256_add_epi8, _mm256_sub_epi8, _mm256_sll_epi8_fallback, _mm256_srl_epi8_fallback, _mm256_srl_epi8_fallback, _mm256_sllv_epi8_fallback, _mm256_srlv_epi8_fallback, _mm256_adds_epu8, _mm256_subs_epu8, _mm256_avg_epu8, _mm256_mulhi_epu8_fallback, _mm256_div_epu8_fallback }
+impl_int! { u16x16, u16, _mm256_set1_epi16, _mm256_add_epi16, _mm256_sub_epi16, _mm256_sll_epi16, _mm256_srl_epi16, _mm256_srl_epi16, _mm256_sllv_epi16_fallback, _mm256_srlv_epi16_fallback, _mm256_adds_epu16, _mm256_subs_epu16, _mm256_avg_epu16, _mm256_mulhi_epu16, _mm256_div_epu16_fallback }
+impl_int! { u32x8, u32, _mm256_set1_epi32, _mm256_add_epi32, _mm256_sub_epi32, _mm256_sll_epi32, _mm256_srl_epi32, _mm256_srl_epi32, _mm256_sllv_epi32, _mm256_srlv_epi32, _mm256_adds_epu32_fallback, _mm256_subs_epu32_fallback, _mm256_avg_epu32_fallback, _mm256_mulhi_epu32_fallback, _mm256_div_epu32_fallback }
+impl_int! { u64x4, u64, _mm256_set1_epi64x, _mm256_add_epi64, _mm256_sub_epi64, _mm256_sll_epi64, _mm256_srl_epi64, _mm256_srl_epi64, _mm256_sllv_epi64, _mm256_srlv_epi64, _mm256_adds_epu64_fallback, _mm256_subs_epu64_fallback, _mm256_avg_epu64_fallback, _mm256_mulhi_epu64_fallback, _mm256_div_epu64_fallback }
 impl_int_mul! { u8x32, u16x16, u32x8, u64x4 }
+impl_shuffle! { u8x32, identity, identity, reverse_epi8, _mm256_unpacklo_epi8, _mm256_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { u16x16, identity, identity, reverse_epi16, _mm256_unpacklo_epi16, _mm256_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { u32x8, identity, identity, reverse_epi32, _mm256_unpacklo_epi32, _mm256_unpackhi_epi32, deinterleave_epi32, permute }
+impl_shuffle! { u64x4, identity, identity, reverse_epi64, _mm256_unpacklo_epi64, _mm256_unpackhi_epi64, deinterleave_epi64, permute }
+impl_cast_bits! { u8x32, i8x32 }
+impl_cast_bits! { u16x16, i16x16 }
+impl_cast_bits! { u32x8, i32x8 }
+impl_cast_bits! { u64x4, i64x4 }
+impl_widen_int! { u8x32, u16x16, _mm256_cvtepu8_epi16, truncate_epi16 }
+impl_widen_int! { u16x16, u32x8, _mm256_cvtepu16_epi32, truncate_epi32 }
+impl_widen_int! { u32x8, u64x4, _mm256_cvtepu32_epi64, truncate_epi64 }
+impl_gather! { u8x32, u8x32, i8x32 }
+impl_gather! { u8x32, [u32x8, i32x8; 4] }
+impl_gather! { u16x16, u16x16, i16x16 }
+impl_gather! { u16x16, [u32x8, i32x8; 2] }
+impl_gather! { u32x8, u32x8, bias_index_epu32, identity, _mm256_i32gather_epi32, _mm256_mask_i32gather_epi32, 4 }
+impl_gather! { u32x8, i32x8, unbiased_index, identity, _mm256_i32gather_epi32, _mm256_mask_i32gather_epi32, 4 }
+impl_gather! { u64x4, u64x4, unbiased_index, identity, _mm256_i64gather_epi64, _mm256_mask_i64gather_epi64, 8 }
+impl_gather! { u64x4, i64x4, unbiased_index, identity, _mm256_i64gather_epi64, _mm256_mask_i64gather_epi64, 8 }
+impl_gather! { [u64x4; 2], u32x8, bias_index_epu32, identity, _mm256_i32gather_epi64, _mm256_mask_i32gather_epi64 }
+impl_gather! { [u64x4; 2], i32x8, unbiased_index, identity, _mm256_i32gather_epi64, _mm256_mask_i32gather_epi64 }
+
+impl Cast<f32x8> for u32x8 {
+    #[inline(always)]
+    fn cast(self) -> f32x8 {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn inner(vec: u32x8) -> f32x8 {
+            f32x8(cvtepu32_ps(vec.0))
+        }
+
+        unsafe { inner(self) }
+    }
+}
+
+impl Cast<f64x4> for u64x4 {
+    #[inline(always)]
+    fn cast(self) -> f64x4 {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn inner(vec: u64x4) -> f64x4 {
+            f64x4(cvtepu64_pd(vec.0))
+        }
+
+        unsafe { inner(self) }
+    }
+}
 
 // AVX2 lacks unsigned integer compares, but it does have unsigned integer min/max for 8, 16, and
 // 32 bits. The impl_ord_uint macro thus implements le in terms of min and cmpeq. However, 64-bit
@@ -1035,16 +2929,63 @@ impl LanesOrd for u64x4 {
 
 int_type! { i8x32, i8, 32, m8x32, _mm256_set1_epi8, _mm256_cmpeq_epi8 }
 int_type! { i16x16, i16, 16, m16x16, _mm256_set1_epi16, _mm256_cmpeq_epi16 }
-int_type! { i32x8, i32, 8, m32x8, _mm256_set1_epi32, _mm256_cmpeq_epi32 }
-int_type! { i64x4, i64, 4, m64x4, _mm256_set1_epi64x, _mm256_cmpeq_epi64 }
+int_type! { i32x8, i32, 8, m32x8, _mm256_set1_epi32, _mm256_cmpeq_epi32, prefix_mask_epi32, _mm256_maskload_epi32, _mm256_maskstore_epi32 }
+int_type! { i64x4, i64, 4, m64x4, _mm256_set1_epi64x, _mm256_cmpeq_epi64, prefix_mask_epi64, _mm256_maskload_epi64, _mm256_maskstore_epi64 }
 impl_ord_int! { i8x32, m8x32, _mm256_cmpgt_epi8, _mm256_max_epi8, _mm256_min_epi8 }
 impl_ord_int! { i16x16, m16x16, _mm256_cmpgt_epi16, _mm256_max_epi16, _mm256_min_epi16 }
 impl_ord_int! { i32x8, m32x8, _mm256_cmpgt_epi32, _mm256_max_epi32, _mm256_min_epi32 }
-impl_int! { i8x32, i8, _mm256_set1_epi8, _mm256_add_epi8, _mm256_sub_epi8, _mm256_sll_epi8_fallback, _mm256_sra_epi8_fallback }
-impl_int! { i16x16, i16, _mm256_set1_epi16, _mm256_add_epi16, _mm256_sub_epi16, _mm256_sll_epi16, _mm256_sra_epi16 }
-impl_int! { i32x8, i32, _mm256_set1_epi32, _mm256_add_epi32, _mm256_sub_epi32, _mm256_sll_epi32, _mm256_sra_epi32 }
-impl_int! { i64x4, i64, _mm256_set1_epi64x, _mm256_add_epi64, _mm256_sub_epi64, _mm256_sll_epi64, _mm256_sra_epi64_fallback }
+impl_int! { i8x32, i8, _mm256_set1_epi8, _mm256_add_epi8, _mm256_sub_epi8, _mm256_sll_epi8_fallback, _mm256_sra_epi8_fallback, _mm256_srl_epi8_fallback, _mm256_sllv_epi8_fallback, _mm256_srav_epi8_fallback, _mm256_adds_epi8, _mm256_subs_epi8, _mm256_avg_epi8_fallback, _mm256_mulhi_epi8_fallback, _mm256_div_epi8_fallback }
+impl_int! { i16x16, i16, _mm256_set1_epi16, _mm256_add_epi16, _mm256_sub_epi16, _mm256_sll_epi16, _mm256_sra_epi16, _mm256_srl_epi16, _mm256_sllv_epi16_fallback, _mm256_srav_epi16_fallback, _mm256_adds_epi16, _mm256_subs_epi16, _mm256_avg_epi16_fallback, _mm256_mulhi_epi16, _mm256_div_epi16_fallback }
+impl_int! { i32x8, i32, _mm256_set1_epi32, _mm256_add_epi32, _mm256_sub_epi32, _mm256_sll_epi32, _mm256_sra_epi32, _mm256_srl_epi32, _mm256_sllv_epi32, _mm256_srav_epi32, _mm256_adds_epi32_fallback, _mm256_subs_epi32_fallback, _mm256_avg_epi32_fallback, _mm256_mulhi_epi32_fallback, _mm256_div_epi32_fallback }
+impl_int! { i64x4, i64, _mm256_set1_epi64x, _mm256_add_epi64, _mm256_sub_epi64, _mm256_sll_epi64, _mm256_sra_epi64_fallback, _mm256_srl_epi64, _mm256_sllv_epi64, _mm256_srav_epi64_fallback, _mm256_adds_epi64_fallback, _mm256_subs_epi64_fallback, _mm256_avg_epi64_fallback, _mm256_mulhi_epi64_fallback, _mm256_div_epi64_fallback }
 impl_int_mul! { i8x32, i16x16, i32x8, i64x4 }
+impl_shuffle! { i8x32, identity, identity, reverse_epi8, _mm256_unpacklo_epi8, _mm256_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { i16x16, identity, identity, reverse_epi16, _mm256_unpacklo_epi16, _mm256_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { i32x8, identity, identity, reverse_epi32, _mm256_unpacklo_epi32, _mm256_unpackhi_epi32, deinterleave_epi32, permute }
+impl_shuffle! { i64x4, identity, identity, reverse_epi64, _mm256_unpacklo_epi64, _mm256_unpackhi_epi64, deinterleave_epi64, permute }
+impl_cast_bits! { i8x32, u8x32 }
+impl_cast_bits! { i16x16, u16x16 }
+impl_cast_bits! { i32x8, u32x8 }
+impl_cast_bits! { i64x4, u64x4 }
+impl_widen_int! { i8x32, i16x16, _mm256_cvtepi8_epi16, truncate_epi16 }
+impl_widen_int! { i16x16, i32x8, _mm256_cvtepi16_epi32, truncate_epi32 }
+impl_widen_int! { i32x8, i64x4, _mm256_cvtepi32_epi64, truncate_epi64 }
+impl_gather! { i8x32, u8x32, i8x32 }
+impl_gather! { i8x32, [u32x8, i32x8; 4] }
+impl_gather! { i16x16, u16x16, i16x16 }
+impl_gather! { i16x16, [u32x8, i32x8; 2] }
+impl_gather! { i32x8, u32x8, bias_index_epu32, identity, _mm256_i32gather_epi32, _mm256_mask_i32gather_epi32, 4 }
+impl_gather! { i32x8, i32x8, unbiased_index, identity, _mm256_i32gather_epi32, _mm256_mask_i32gather_epi32, 4 }
+impl_gather! { i64x4, u64x4, unbiased_index, identity, _mm256_i64gather_epi64, _mm256_mask_i64gather_epi64, 8 }
+impl_gather! { i64x4, i64x4, unbiased_index, identity, _mm256_i64gather_epi64, _mm256_mask_i64gather_epi64, 8 }
+impl_gather! { [i64x4; 2], u32x8, bias_index_epu32, identity, _mm256_i32gather_epi64, _mm256_mask_i32gather_epi64 }
+impl_gather! { [i64x4; 2], i32x8, unbiased_index, identity, _mm256_i32gather_epi64, _mm256_mask_i32gather_epi64 }
+
+impl Cast<f32x8> for i32x8 {
+    #[inline(always)]
+    fn cast(self) -> f32x8 {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn inner(vec: i32x8) -> f32x8 {
+            f32x8(_mm256_cvtepi32_ps(vec.0))
+        }
+
+        unsafe { inner(self) }
+    }
+}
+
+impl Cast<f64x4> for i64x4 {
+    #[inline(always)]
+    fn cast(self) -> f64x4 {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn inner(vec: i64x4) -> f64x4 {
+            f64x4(cvtepi64_pd(vec.0))
+        }
+
+        unsafe { inner(self) }
+    }
+}
 
 // 64-bit integer min/max ops (_mm256_{min,max}_epi64) require AVX512, so for i64x4 we just fall
 // back to the default impls of min and max in terms of le and select.
@@ -1063,9 +3004,68 @@ impl LanesOrd for i64x4 {
 
 int_type! { m8x32, m8, 32, m8x32, _mm256_set1_epi8, _mm256_cmpeq_epi8 }
 int_type! { m16x16, m16, 16, m16x16, _mm256_set1_epi16, _mm256_cmpeq_epi16 }
-int_type! { m32x8, m32, 8, m32x8, _mm256_set1_epi32, _mm256_cmpeq_epi32 }
-int_type! { m64x4, m64, 4, m64x4, _mm256_set1_epi64x, _mm256_cmpeq_epi64 }
+int_type! { m32x8, m32, 8, m32x8, _mm256_set1_epi32, _mm256_cmpeq_epi32, prefix_mask_epi32, _mm256_maskload_epi32, _mm256_maskstore_epi32 }
+int_type! { m64x4, m64, 4, m64x4, _mm256_set1_epi64x, _mm256_cmpeq_epi64, prefix_mask_epi64, _mm256_maskload_epi64, _mm256_maskstore_epi64 }
 impl_ord_mask! { m8x32 }
 impl_ord_mask! { m16x16 }
 impl_ord_mask! { m32x8 }
 impl_ord_mask! { m64x4 }
+impl_mask! { m8x32, movemask_epi8, expand_bits_epi8 }
+impl_mask! { m16x16, movemask_epi16, expand_bits_epi16 }
+impl_mask! { m32x8, movemask_epi32, expand_bits_epi32 }
+impl_mask! { m64x4, movemask_epi64, expand_bits_epi64 }
+impl_shuffle! { m8x32, identity, identity, reverse_epi8, _mm256_unpacklo_epi8, _mm256_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { m16x16, identity, identity, reverse_epi16, _mm256_unpacklo_epi16, _mm256_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { m32x8, identity, identity, reverse_epi32, _mm256_unpacklo_epi32, _mm256_unpackhi_epi32, deinterleave_epi32, permute }
+impl_shuffle! { m64x4, identity, identity, reverse_epi64, _mm256_unpacklo_epi64, _mm256_unpackhi_epi64, deinterleave_epi64, permute }
+
+#[test]
+fn mul_add_fused() {
+    if !(is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")) {
+        return;
+    }
+
+    // (1 + e)(1 - e) - 1 == -e^2, which is lost if the product is rounded first
+    let x = f32x8::new(1.0 + f32::EPSILON);
+    let res = x.mul_add(f32x8::new(1.0 - f32::EPSILON), f32x8::new(-1.0));
+    assert_eq!(res[0], -f32::EPSILON * f32::EPSILON);
+}
+
+#[test]
+fn swizzle() {
+    if !is_x86_feature_detected!("avx2") {
+        return;
+    }
+
+    struct Pattern;
+
+    impl Swizzle<4> for Pattern {
+        const INDEX: [usize; 4] = [2, 0, 3, 3];
+    }
+
+    impl Swizzle<8> for Pattern {
+        const INDEX: [usize; 8] = [6, 0, 7, 7, 1, 2, 4, 3];
+    }
+
+    struct Pick;
+
+    impl Swizzle<4> for Pick {
+        const INDEX: [usize; 4] = [7, 0, 5, 2];
+    }
+
+    impl Swizzle<8> for Pick {
+        const INDEX: [usize; 8] = [15, 0, 9, 2, 8, 14, 3, 3];
+    }
+
+    let a = f32x8::from_slice(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+    let b = f32x8::from_slice(&[8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0]);
+    let res = a.swizzle::<Pattern, 8>();
+    assert_eq!(res.as_slice(), &[6.0, 0.0, 7.0, 7.0, 1.0, 2.0, 4.0, 3.0]);
+    let res = a.shuffle::<Pick, 8>(b);
+    assert_eq!(res.as_slice(), &[15.0, 0.0, 9.0, 2.0, 8.0, 14.0, 3.0, 3.0]);
+
+    let a = i64x4::from_slice(&[0, 1, 2, 3]);
+    let b = i64x4::from_slice(&[4, 5, 6, 7]);
+    assert_eq!(a.swizzle::<Pattern, 4>().as_slice(), &[2, 0, 3, 3]);
+    assert_eq!(a.shuffle::<Pick, 4>(b).as_slice(), &[7, 0, 5, 2]);
+}
@@ -0,0 +1,2912 @@
+#![allow(non_camel_case_types)]
+
+use core::array;
+use core::convert::identity;
+use core::fmt::{self, Debug};
+use core::mem;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
+use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+use core::slice;
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+use crate::mask::*;
+use crate::simd::{
+    check_aligned, check_index, check_mask_len, Array, Bitwise, Cast, Float, Gather, Int, LanesEq,
+    LanesOrd, Mask, RoundCast, Select, Shuffle, Simd, Swizzle, Widen,
+};
+use crate::{Arch, Task};
+
+pub struct Avx512Impl;
+
+impl Arch for Avx512Impl {
+    type f32 = f32x16;
+    type f64 = f64x8;
+
+    type u8 = u8x64;
+    type u16 = u16x32;
+    type u32 = u32x16;
+    type u64 = u64x8;
+
+    type i8 = i8x64;
+    type i16 = i16x32;
+    type i32 = i32x16;
+    type i64 = i64x8;
+
+    type m8 = m8x64;
+    type m16 = m16x32;
+    type m32 = m32x16;
+    type m64 = m64x8;
+
+    const NAME: &'static str = "avx512";
+
+    #[inline(always)]
+    fn invoke<T: Task>(task: T) -> T::Result {
+        #[inline]
+        #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+        unsafe fn inner<T: Task>(task: T) -> T::Result {
+            task.run::<Avx512Impl>()
+        }
+
+        unsafe { inner(task) }
+    }
+
+    #[inline(always)]
+    fn prefetch<T>(ptr: *const T) {
+        #[inline]
+        #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+        unsafe fn inner(ptr: *const i8) {
+            _mm_prefetch::<_MM_HINT_T0>(ptr);
+        }
+
+        unsafe { inner(ptr as *const i8) }
+    }
+
+    #[inline(always)]
+    fn stream_fence() {
+        #[inline]
+        #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+        unsafe fn inner() {
+            _mm_sfence();
+        }
+
+        unsafe { inner() }
+    }
+}
+
+// Masks are kept in k-registers (__mmask8 through __mmask64, one bit per lane), which comparisons
+// produce and blends, masked loads and masked stores consume directly. Lanes are read and written
+// with bit operations, so mask types do not implement `Array`. Loading, storing and shuffling
+// masks goes through full-width vectors (vpmovm2* and vpmov*2m).
+
+macro_rules! float_type {
+    (
+        $float:ident, $inner:ident, $elem:ident, $lanes:literal, $mask:ident, $kmask:ident, $bits:ident,
+        $set:ident, $load:ident, $load_aligned:ident, $store:ident, $stream:ident,
+        $maskz_load:ident, $mask_load:ident, $mask_store:ident,
+        $cast_to_int:ident, $cast_from_int:ident, $blend:ident,
+        $cmp:ident, $max:ident, $min:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $xor:ident,
+        $and:ident, $or:ident, $andnot:ident, $fmadd:ident, $sqrt:ident, $rcp:ident, $rsqrt:ident,
+        $roundscale:ident,
+    ) => {
+        #[derive(Copy, Clone)]
+        #[repr(transparent)]
+        pub struct $float($inner);
+
+        impl Simd for $float {
+            type Elem = $elem;
+            type Mask = $mask;
+
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            fn new(elem: Self::Elem) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(elem: $elem) -> $float {
+                    $float($set(elem))
+                }
+
+                unsafe { inner(elem) }
+            }
+
+            #[inline]
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
+            }
+
+            #[inline(always)]
+            fn from_slice(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    assert!(slice.len() == <$float as Simd>::LANES);
+                    $float($load(slice.as_ptr()))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn write_to_slice(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    assert!(slice.len() == <$float as Simd>::LANES);
+                    $store(slice.as_mut_ptr(), vec.0);
+                }
+
+                unsafe { inner(self, slice) }
+            }
+
+            #[inline(always)]
+            fn from_slice_aligned(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    check_aligned::<$float>(slice);
+                    $float($load_aligned(slice.as_ptr()))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            unsafe fn store_stream(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    check_aligned::<$float>(slice);
+                    $stream(slice.as_mut_ptr(), vec.0);
+                }
+
+                inner(self, slice);
+            }
+
+            #[inline(always)]
+            fn load_partial(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    let mask = prefix_mask(slice.len()) as $kmask;
+                    $float($maskz_load(mask, slice.as_ptr()))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn load_or(slice: &[Self::Elem], or: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem], or: $float) -> $float {
+                    let mask = prefix_mask(slice.len()) as $kmask;
+                    $float($mask_load(or.0, mask, slice.as_ptr()))
+                }
+
+                unsafe { inner(slice, or) }
+            }
+
+            #[inline(always)]
+            fn store_partial(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    let mask = prefix_mask(slice.len()) as $kmask;
+                    $mask_store(slice.as_mut_ptr(), mask, vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice);
+                }
+            }
+
+            #[inline(always)]
+            fn load_masked(slice: &[Self::Elem], mask: Self::Mask) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem], mask: <$float as Simd>::Mask) -> $float {
+                    check_mask_len(slice.len(), mask);
+                    $float($maskz_load(mask.0, slice.as_ptr()))
+                }
+
+                unsafe { inner(slice, mask) }
+            }
+
+            #[inline(always)]
+            fn store_masked(&self, slice: &mut [Self::Elem], mask: Self::Mask) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem], mask: <$float as Simd>::Mask) {
+                    check_mask_len(slice.len(), mask);
+                    $mask_store(slice.as_mut_ptr(), mask.0, vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice, mask);
+                }
+            }
+        }
+
+        impl Array for $float {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
+                unsafe { slice.align_to::<Self>() }
+            }
+
+            #[inline]
+            fn align_mut_slice(
+                slice: &mut [Self::Elem],
+            ) -> (&mut [Self::Elem], &mut [Self], &mut [Self::Elem]) {
+                unsafe { slice.align_to_mut::<Self>() }
+            }
+        }
+
+        impl Debug for $float {
+            #[inline]
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Debug::fmt(self.as_slice(), fmt)
+            }
+        }
+
+        impl Default for $float {
+            #[inline]
+            fn default() -> Self {
+                unsafe { mem::zeroed() }
+            }
+        }
+
+        impl LanesEq for $float {
+            type Output = $mask;
+
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    $mask($cmp(lhs.0, rhs.0, _CMP_EQ_OQ))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn ne(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    $mask($cmp(lhs.0, rhs.0, _CMP_NEQ_UQ))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        impl LanesOrd for $float {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    $mask($cmp(lhs.0, rhs.0, _CMP_LT_OQ))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    $mask($cmp(lhs.0, rhs.0, _CMP_LE_OQ))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn gt(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    $mask($cmp(lhs.0, rhs.0, _CMP_GT_OQ))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn ge(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$float, rhs: &$float) -> $mask {
+                    $mask($cmp(lhs.0, rhs.0, _CMP_GE_OQ))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($max(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($min(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        impl Index<usize> for $float {
+            type Output = <Self as Simd>::Elem;
+
+            #[inline]
+            fn index(&self, index: usize) -> &Self::Output {
+                &self.as_slice()[index]
+            }
+        }
+
+        impl IndexMut<usize> for $float {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+                &mut self.as_mut_slice()[index]
+            }
+        }
+
+        impl Select<$float> for $mask {
+            #[inline(always)]
+            fn select(self, if_true: $float, if_false: $float) -> $float {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(mask: $mask, if_true: $float, if_false: $float) -> $float {
+                    $float($blend(mask.0, if_false.0, if_true.0))
+                }
+
+                unsafe { inner(self, if_true, if_false) }
+            }
+        }
+
+        impl $float {
+            #[inline(always)]
+            fn fold_lanes(self, f: impl Fn($float, $float) -> $float) -> $elem {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn shift<const BYTES: i32>(vec: $float) -> $float {
+                    let vec = $cast_to_int(vec.0);
+                    $float($cast_from_int(_mm512_bsrli_epi128::<BYTES>(vec)))
+                }
+
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn swap_blocks<const MASK: i32>(vec: $float) -> $float {
+                    let vec = $cast_to_int(vec.0);
+                    $float($cast_from_int(_mm512_shuffle_i64x2::<MASK>(vec, vec)))
+                }
+
+                // Repeatedly combine each lane with the one half the remaining width above it,
+                // leaving the result in the lowest lane
+                let mut vec = self;
+                unsafe {
+                    vec = f(vec, swap_blocks::<0x4E>(vec));
+                    vec = f(vec, swap_blocks::<0xB1>(vec));
+                    vec = f(vec, shift::<8>(vec));
+                    if mem::size_of::<$elem>() <= 4 {
+                        vec = f(vec, shift::<4>(vec));
+                    }
+                }
+                vec[0]
+            }
+        }
+
+        impl Float for $float {
+            type Bits = $bits;
+
+            #[inline(always)]
+            fn to_bits(self) -> Self::Bits {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $bits {
+                    $bits($cast_to_int(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn from_bits(bits: Self::Bits) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(bits: $bits) -> $float {
+                    $float($cast_from_int(bits.0))
+                }
+
+                unsafe { inner(bits) }
+            }
+
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(x: $float, a: $float, b: $float) -> $float {
+                    $float($fmadd(x.0, a.0, b.0))
+                }
+
+                unsafe { inner(self, a, b) }
+            }
+
+            #[inline(always)]
+            fn sqrt(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($sqrt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn recip(self) -> Self {
+                Self::new(1.0) / self
+            }
+
+            #[inline(always)]
+            fn recip_approx(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($rcp(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn rsqrt(self) -> Self {
+                Self::new(1.0) / self.sqrt()
+            }
+
+            #[inline(always)]
+            fn rsqrt_approx(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($rsqrt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn abs(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($andnot($set(-0.0), vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float, sign: $float) -> $float {
+                    let mask = $set(-0.0);
+                    $float($or($andnot(mask, vec.0), $and(mask, sign.0)))
+                }
+
+                unsafe { inner(self, sign) }
+            }
+
+            #[inline(always)]
+            fn signum(self) -> Self {
+                let signum = Self::new(1.0).copysign(self);
+                self.eq(&self).select(signum, Self::new($elem::NAN))
+            }
+
+            #[inline(always)]
+            fn floor(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($roundscale(
+                        vec.0,
+                        _MM_FROUND_TO_NEG_INF | _MM_FROUND_NO_EXC,
+                    ))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn ceil(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($roundscale(
+                        vec.0,
+                        _MM_FROUND_TO_POS_INF | _MM_FROUND_NO_EXC,
+                    ))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn round(self) -> Self {
+                // Adding the largest value below one half before truncating rounds ties away from
+                // zero without rounding anything smaller than a tie upwards
+                let half = Self::new(0.5 - $elem::EPSILON / 4.0);
+                (self + half.copysign(self)).trunc()
+            }
+
+            #[inline(always)]
+            fn trunc(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($roundscale(vec.0, _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn fract(self) -> Self {
+                self - self.trunc()
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a + b)
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a * b)
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.min(b))
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.max(b))
+            }
+        }
+
+        impl Add for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($add(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl AddAssign for $float {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl Sub for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($sub(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl SubAssign for $float {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl Mul for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($mul(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl MulAssign for $float {
+            #[inline(always)]
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl Div for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $float, rhs: $float) -> $float {
+                    $float($div(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl DivAssign for $float {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl Neg for $float {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($xor(vec.0, $set(-0.0)))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+    };
+}
+
+macro_rules! int_type {
+    (
+        $int:ident, $elem:ident, $lanes:literal, $mask:ident, $kmask:ident, $set:ident, $cmpeq:ident,
+        $blend:ident, $maskz_load:ident, $mask_load:ident, $mask_store:ident
+    ) => {
+        #[derive(Copy, Clone)]
+        #[repr(transparent)]
+        pub struct $int(__m512i);
+
+        impl Simd for $int {
+            type Elem = $elem;
+            type Mask = $mask;
+
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            fn new(elem: Self::Elem) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                #[allow(clippy::missing_transmute_annotations, clippy::useless_transmute)]
+                #[allow(unnecessary_transmutes)]
+                unsafe fn inner(elem: $elem) -> $int {
+                    $int($set(mem::transmute(elem)))
+                }
+
+                unsafe { inner(elem) }
+            }
+
+            #[inline]
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
+            }
+
+            #[inline(always)]
+            fn from_slice(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem]) -> $int {
+                    assert!(slice.len() == <$int as Simd>::LANES);
+                    $int(_mm512_loadu_si512(slice.as_ptr() as *const __m512i))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn write_to_slice(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$int, slice: &mut [$elem]) {
+                    assert!(slice.len() == <$int as Simd>::LANES);
+                    _mm512_storeu_si512(slice.as_mut_ptr() as *mut __m512i, vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice);
+                }
+            }
+
+            #[inline(always)]
+            fn from_slice_aligned(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem]) -> $int {
+                    check_aligned::<$int>(slice);
+                    $int(_mm512_load_si512(slice.as_ptr() as *const __m512i))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            unsafe fn store_stream(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$int, slice: &mut [$elem]) {
+                    check_aligned::<$int>(slice);
+                    _mm512_stream_si512(slice.as_mut_ptr() as *mut __m512i, vec.0);
+                }
+
+                inner(self, slice);
+            }
+
+            #[inline(always)]
+            fn load_partial(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem]) -> $int {
+                    let mask = prefix_mask(slice.len()) as $kmask;
+                    $int($maskz_load(mask, slice.as_ptr() as _))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn load_or(slice: &[Self::Elem], or: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem], or: $int) -> $int {
+                    let mask = prefix_mask(slice.len()) as $kmask;
+                    $int($mask_load(or.0, mask, slice.as_ptr() as _))
+                }
+
+                unsafe { inner(slice, or) }
+            }
+
+            #[inline(always)]
+            fn store_partial(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$int, slice: &mut [$elem]) {
+                    let mask = prefix_mask(slice.len()) as $kmask;
+                    $mask_store(slice.as_mut_ptr() as _, mask, vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice);
+                }
+            }
+
+            #[inline(always)]
+            fn load_masked(slice: &[Self::Elem], mask: Self::Mask) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem], mask: <$int as Simd>::Mask) -> $int {
+                    check_mask_len(slice.len(), mask);
+                    $int($maskz_load(mask.0, slice.as_ptr() as _))
+                }
+
+                unsafe { inner(slice, mask) }
+            }
+
+            #[inline(always)]
+            fn store_masked(&self, slice: &mut [Self::Elem], mask: Self::Mask) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$int, slice: &mut [$elem], mask: <$int as Simd>::Mask) {
+                    check_mask_len(slice.len(), mask);
+                    $mask_store(slice.as_mut_ptr() as _, mask.0, vec.0);
+                }
+
+                unsafe {
+                    inner(self, slice, mask);
+                }
+            }
+        }
+
+        impl Array for $int {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
+                unsafe { slice.align_to::<Self>() }
+            }
+
+            #[inline]
+            fn align_mut_slice(
+                slice: &mut [Self::Elem],
+            ) -> (&mut [Self::Elem], &mut [Self], &mut [Self::Elem]) {
+                unsafe { slice.align_to_mut::<Self>() }
+            }
+        }
+
+        impl Debug for $int {
+            #[inline]
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Debug::fmt(self.as_slice(), fmt)
+            }
+        }
+
+        impl Default for $int {
+            #[inline]
+            fn default() -> Self {
+                unsafe { mem::zeroed() }
+            }
+        }
+
+        impl LanesEq for $int {
+            type Output = $mask;
+
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$int, rhs: &$int) -> $mask {
+                    $mask($cmpeq(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        impl Index<usize> for $int {
+            type Output = <Self as Simd>::Elem;
+
+            #[inline]
+            fn index(&self, index: usize) -> &Self::Output {
+                &self.as_slice()[index]
+            }
+        }
+
+        impl IndexMut<usize> for $int {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+                &mut self.as_mut_slice()[index]
+            }
+        }
+
+        impl $int {
+            #[inline(always)]
+            fn fold_lanes(self, f: impl Fn($int, $int) -> $int) -> $elem {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn shift<const BYTES: i32>(vec: $int) -> $int {
+                    $int(_mm512_bsrli_epi128::<BYTES>(vec.0))
+                }
+
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn swap_blocks<const MASK: i32>(vec: $int) -> $int {
+                    $int(_mm512_shuffle_i64x2::<MASK>(vec.0, vec.0))
+                }
+
+                // Repeatedly combine each lane with the one half the remaining width above it,
+                // leaving the result in the lowest lane
+                let mut vec = self;
+                unsafe {
+                    vec = f(vec, swap_blocks::<0x4E>(vec));
+                    vec = f(vec, swap_blocks::<0xB1>(vec));
+                    vec = f(vec, shift::<8>(vec));
+                    if mem::size_of::<$elem>() <= 4 {
+                        vec = f(vec, shift::<4>(vec));
+                    }
+                    if mem::size_of::<$elem>() <= 2 {
+                        vec = f(vec, shift::<2>(vec));
+                    }
+                    if mem::size_of::<$elem>() <= 1 {
+                        vec = f(vec, shift::<1>(vec));
+                    }
+                }
+                vec[0]
+            }
+        }
+
+        impl Bitwise for $int {
+            #[inline(always)]
+            fn reduce_and(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a & b)
+            }
+
+            #[inline(always)]
+            fn reduce_or(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a | b)
+            }
+
+            #[inline(always)]
+            fn reduce_xor(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a ^ b)
+            }
+        }
+
+        impl BitAnd for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitand(self, rhs: Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int(_mm512_and_si512(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl BitAndAssign for $int {
+            #[inline(always)]
+            fn bitand_assign(&mut self, rhs: Self) {
+                *self = *self & rhs;
+            }
+        }
+
+        impl BitOr for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitor(self, rhs: Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int(_mm512_or_si512(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl BitOrAssign for $int {
+            #[inline(always)]
+            fn bitor_assign(&mut self, rhs: Self) {
+                *self = *self | rhs;
+            }
+        }
+
+        impl BitXor for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitxor(self, rhs: Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int(_mm512_xor_si512(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl BitXorAssign for $int {
+            #[inline(always)]
+            fn bitxor_assign(&mut self, rhs: Self) {
+                *self = *self ^ rhs;
+            }
+        }
+
+        impl Not for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn not(self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $int) -> $int {
+                    $int(_mm512_xor_si512(vec.0, _mm512_set1_epi32(-1)))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+
+        impl Select<$int> for $mask {
+            #[inline(always)]
+            fn select(self, if_true: $int, if_false: $int) -> $int {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(mask: $mask, if_true: $int, if_false: $int) -> $int {
+                    $int($blend(mask.0, if_false.0, if_true.0))
+                }
+
+                unsafe { inner(self, if_true, if_false) }
+            }
+        }
+    };
+}
+
+macro_rules! mask_type {
+    (
+        $mask:ident, $elem:ident, $lanes:literal, $kmask:ident, $movm:ident, $movepi_mask:ident,
+        $maskz_load:ident, $mask_store:ident
+    ) => {
+        #[derive(Copy, Clone, Default)]
+        #[repr(transparent)]
+        pub struct $mask($kmask);
+
+        impl Simd for $mask {
+            type Elem = $elem;
+            type Mask = $mask;
+
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            fn new(elem: Self::Elem) -> Self {
+                $mask(if elem.into() { !0 } else { 0 })
+            }
+
+            #[inline(always)]
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                assert!(index < Self::LANES);
+                if elem.into() {
+                    self.0 |= 1 << index;
+                } else {
+                    self.0 &= !(1 << index);
+                }
+            }
+
+            #[inline(always)]
+            fn from_slice(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem]) -> $mask {
+                    assert!(slice.len() == <$mask as Simd>::LANES);
+                    $mask($movepi_mask(_mm512_loadu_si512(
+                        slice.as_ptr() as *const __m512i
+                    )))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn write_to_slice(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(mask: &$mask, slice: &mut [$elem]) {
+                    assert!(slice.len() == <$mask as Simd>::LANES);
+                    _mm512_storeu_si512(slice.as_mut_ptr() as *mut __m512i, $movm(mask.0));
+                }
+
+                unsafe {
+                    inner(self, slice);
+                }
+            }
+
+            #[inline(always)]
+            fn load_partial(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem]) -> $mask {
+                    let mask = prefix_mask(slice.len()) as $kmask;
+                    $mask($movepi_mask($maskz_load(mask, slice.as_ptr() as _)))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            fn load_or(slice: &[Self::Elem], or: Self) -> Self {
+                let mask = prefix_mask(slice.len()) as $kmask;
+                $mask(Self::load_partial(slice).0 | or.0 & !mask)
+            }
+
+            #[inline(always)]
+            fn store_partial(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(mask: &$mask, slice: &mut [$elem]) {
+                    let prefix = prefix_mask(slice.len()) as $kmask;
+                    $mask_store(slice.as_mut_ptr() as _, prefix, $movm(mask.0));
+                }
+
+                unsafe {
+                    inner(self, slice);
+                }
+            }
+
+            #[inline(always)]
+            fn load_masked(slice: &[Self::Elem], mask: Self::Mask) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(slice: &[$elem], mask: $mask) -> $mask {
+                    check_mask_len(slice.len(), mask);
+                    $mask($movepi_mask($maskz_load(mask.0, slice.as_ptr() as _)))
+                }
+
+                unsafe { inner(slice, mask) }
+            }
+
+            #[inline(always)]
+            fn store_masked(&self, slice: &mut [Self::Elem], mask: Self::Mask) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: &$mask, slice: &mut [$elem], mask: $mask) {
+                    check_mask_len(slice.len(), mask);
+                    $mask_store(slice.as_mut_ptr() as _, mask.0, $movm(vec.0));
+                }
+
+                unsafe {
+                    inner(self, slice, mask);
+                }
+            }
+        }
+
+        impl Debug for $mask {
+            #[inline]
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.debug_list()
+                    .entries((0..Self::LANES).map(|i| self[i]))
+                    .finish()
+            }
+        }
+
+        impl LanesEq for $mask {
+            type Output = $mask;
+
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> Self::Output {
+                $mask(!(self.0 ^ other.0))
+            }
+
+            #[inline(always)]
+            fn ne(&self, other: &Self) -> Self::Output {
+                $mask(self.0 ^ other.0)
+            }
+        }
+
+        // `false` orders before `true`
+        impl LanesOrd for $mask {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                $mask(!self.0 & other.0)
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                $mask(!self.0 | other.0)
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                self | other
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                self & other
+            }
+        }
+
+        impl Index<usize> for $mask {
+            type Output = $elem;
+
+            #[inline]
+            fn index(&self, index: usize) -> &Self::Output {
+                assert!(index < Self::LANES);
+                if self.0 >> index & 1 != 0 {
+                    &$elem::TRUE
+                } else {
+                    &$elem::FALSE
+                }
+            }
+        }
+
+        impl Bitwise for $mask {
+            #[inline(always)]
+            fn reduce_and(self) -> Self::Elem {
+                (self.0 == !0).into()
+            }
+
+            #[inline(always)]
+            fn reduce_or(self) -> Self::Elem {
+                (self.0 != 0).into()
+            }
+
+            #[inline(always)]
+            fn reduce_xor(self) -> Self::Elem {
+                (self.0.count_ones() % 2 != 0).into()
+            }
+        }
+
+        impl BitAnd for $mask {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitand(self, rhs: Self) -> Self::Output {
+                $mask(self.0 & rhs.0)
+            }
+        }
+
+        impl BitAndAssign for $mask {
+            #[inline(always)]
+            fn bitand_assign(&mut self, rhs: Self) {
+                self.0 &= rhs.0;
+            }
+        }
+
+        impl BitOr for $mask {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitor(self, rhs: Self) -> Self::Output {
+                $mask(self.0 | rhs.0)
+            }
+        }
+
+        impl BitOrAssign for $mask {
+            #[inline(always)]
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl BitXor for $mask {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitxor(self, rhs: Self) -> Self::Output {
+                $mask(self.0 ^ rhs.0)
+            }
+        }
+
+        impl BitXorAssign for $mask {
+            #[inline(always)]
+            fn bitxor_assign(&mut self, rhs: Self) {
+                self.0 ^= rhs.0;
+            }
+        }
+
+        impl Not for $mask {
+            type Output = Self;
+
+            #[inline(always)]
+            fn not(self) -> Self::Output {
+                $mask(!self.0)
+            }
+        }
+
+        impl Mask for $mask {
+            #[inline(always)]
+            fn to_bitmask(self) -> u64 {
+                self.0 as u64
+            }
+
+            #[inline(always)]
+            fn from_bitmask(bits: u64) -> Self {
+                $mask(bits as $kmask)
+            }
+        }
+
+        impl Select<$mask> for $mask {
+            #[inline(always)]
+            fn select(self, if_true: $mask, if_false: $mask) -> $mask {
+                (self & if_true) | (!self & if_false)
+            }
+        }
+    };
+}
+
+// Unlike SSE and AVX2, AVX-512 has both signed and unsigned comparisons, and min/max, for every
+// lane width
+macro_rules! impl_ord {
+    ($int:ident, $mask:ident, $cmplt:ident, $cmple:ident, $max:ident, $min:ident) => {
+        impl LanesOrd for $int {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$int, rhs: &$int) -> $mask {
+                    $mask($cmplt(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: &$int, rhs: &$int) -> $mask {
+                    $mask($cmple(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($max(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($min(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_int {
+    ($int:ident, $elem:ident, $set:ident, $add:ident, $sub:ident, $shl:ident, $shr:ident, $srl:ident, $shlv:ident, $shrv:ident, $adds:ident, $subs:ident, $avg:ident, $mulhi:ident, $div:ident) => {
+        impl Int for $int {
+            #[inline(always)]
+            fn saturating_add(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($adds(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn saturating_sub(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($subs(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn avg(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($avg(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn mulhi(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($mulhi(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn shl_by(self, counts: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, counts: $int) -> $int {
+                    let counts = _mm512_and_si512(counts.0, $set(($elem::BITS - 1) as _));
+                    $int($shlv(lhs.0, counts))
+                }
+
+                unsafe { inner(self, counts) }
+            }
+
+            #[inline(always)]
+            fn shr_by(self, counts: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, counts: $int) -> $int {
+                    let counts = _mm512_and_si512(counts.0, $set(($elem::BITS - 1) as _));
+                    $int($shrv(lhs.0, counts))
+                }
+
+                unsafe { inner(self, counts) }
+            }
+
+            #[inline(always)]
+            fn rotate_left(self, n: usize) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $int, n: usize) -> $int {
+                    let bits = $elem::BITS as usize;
+                    let left = n & (bits - 1);
+                    let right = (bits - left) & (bits - 1);
+                    let high = $shl(vec.0, _mm_cvtsi64_si128(left as i64));
+                    let low = $srl(vec.0, _mm_cvtsi64_si128(right as i64));
+                    $int(_mm512_or_si512(high, low))
+                }
+
+                unsafe { inner(self, n) }
+            }
+
+            #[inline(always)]
+            fn rotate_right(self, n: usize) -> Self {
+                let bits = $elem::BITS as usize;
+                self.rotate_left(bits - (n & (bits - 1)))
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a + b)
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a * b)
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.min(b))
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.max(b))
+            }
+        }
+
+        impl Add for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($add(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl AddAssign for $int {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl Sub for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($sub(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl SubAssign for $int {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl MulAssign for $int {
+            #[inline(always)]
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl Div for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($div(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl DivAssign for $int {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl Rem for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn rem(self, rhs: Self) -> Self {
+                self - (self / rhs) * rhs
+            }
+        }
+
+        impl RemAssign for $int {
+            #[inline(always)]
+            fn rem_assign(&mut self, rhs: Self) {
+                *self = *self % rhs;
+            }
+        }
+
+        impl Neg for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $int) -> $int {
+                    $int($sub($set(0), vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+
+        impl Shl<usize> for $int {
+            type Output = Self;
+
+            #[inline]
+            fn shl(self, rhs: usize) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: usize) -> $int {
+                    let shift = rhs & ($elem::BITS as usize - 1);
+                    $int($shl(lhs.0, _mm_cvtsi64_si128(shift as i64)))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl ShlAssign<usize> for $int {
+            #[inline]
+            fn shl_assign(&mut self, rhs: usize) {
+                *self = *self << rhs;
+            }
+        }
+
+        impl Shr<usize> for $int {
+            type Output = Self;
+
+            #[inline]
+            fn shr(self, rhs: usize) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int, rhs: usize) -> $int {
+                    let shift = rhs & ($elem::BITS as usize - 1);
+                    $int($shr(lhs.0, _mm_cvtsi64_si128(shift as i64)))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl ShrAssign<usize> for $int {
+            #[inline]
+            fn shr_assign(&mut self, rhs: usize) {
+                *self = *self >> rhs;
+            }
+        }
+    };
+}
+
+macro_rules! impl_int_mul {
+    ($int8:ident, $int16:ident, $int32:ident, $int64:ident) => {
+        impl Mul for $int8 {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int8, rhs: $int8) -> $int8 {
+                    let lhs_odd = _mm512_srli_epi16(lhs.0, 8);
+                    let rhs_odd = _mm512_srli_epi16(rhs.0, 8);
+                    let even = _mm512_mullo_epi16(lhs.0, rhs.0);
+                    let odd = _mm512_slli_epi16(_mm512_mullo_epi16(lhs_odd, rhs_odd), 8);
+                    $int8(_mm512_mask_blend_epi8(ODD_BYTES, even, odd))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl Mul for $int16 {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int16, rhs: $int16) -> $int16 {
+                    $int16(_mm512_mullo_epi16(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl Mul for $int32 {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int32, rhs: $int32) -> $int32 {
+                    $int32(_mm512_mullo_epi32(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl Mul for $int64 {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $int64, rhs: $int64) -> $int64 {
+                    $int64(_mm512_mullo_epi64(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_shuffle {
+    (
+        $vec:ident, $to_int:ident, $from_int:ident,
+        $reverse:ident, $unpacklo:ident, $unpackhi:ident, $deinterleave:ident
+        $(, $permute:ident)?
+    ) => {
+        impl Shuffle for $vec {
+            $(
+                #[inline(always)]
+                fn swizzle<S: Swizzle<N>, const N: usize>(self) -> Self {
+                    const { check_index(&S::INDEX, Self::LANES, 1) };
+                    self.$permute(self, |i| S::INDEX[i])
+                }
+
+                #[inline(always)]
+                fn shuffle<S: Swizzle<N>, const N: usize>(self, other: Self) -> Self {
+                    const { check_index(&S::INDEX, Self::LANES, 2) };
+                    self.$permute(other, |i| S::INDEX[i])
+                }
+
+                #[inline(always)]
+                fn rotate_lanes_left<const N: usize>(self) -> Self {
+                    self.$permute(self, |i| (i + N) % Self::LANES)
+                }
+
+                #[inline(always)]
+                fn rotate_lanes_right<const N: usize>(self) -> Self {
+                    self.$permute(self, |i| (i + Self::LANES - N % Self::LANES) % Self::LANES)
+                }
+
+                #[inline(always)]
+                fn concat_shift<const OFFSET: usize>(self, other: Self) -> Self {
+                    const { assert!(OFFSET <= Self::LANES, "offset out of bounds") };
+                    self.$permute(other, |i| i + OFFSET)
+                }
+            )?
+
+            #[inline(always)]
+            fn reverse(self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $vec) -> $vec {
+                    $vec($from_int($reverse($to_int(vec.0))))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn interleave_lo(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> $vec {
+                    // Unpacking works within 128-bit lanes, so first give each 128-bit lane one
+                    // quarter from the low half of each input and one from the high half
+                    let index = _mm512_setr_epi64(0, 4, 1, 5, 2, 6, 3, 7);
+                    let lhs = _mm512_permutexvar_epi64(index, $to_int(lhs.0));
+                    let rhs = _mm512_permutexvar_epi64(index, $to_int(rhs.0));
+                    $vec($from_int($unpacklo(lhs, rhs)))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn interleave_hi(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> $vec {
+                    let index = _mm512_setr_epi64(0, 4, 1, 5, 2, 6, 3, 7);
+                    let lhs = _mm512_permutexvar_epi64(index, $to_int(lhs.0));
+                    let rhs = _mm512_permutexvar_epi64(index, $to_int(rhs.0));
+                    $vec($from_int($unpackhi(lhs, rhs)))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn deinterleave(self, other: Self) -> (Self, Self) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> ($vec, $vec) {
+                    let (even, odd) = $deinterleave($to_int(lhs.0), $to_int(rhs.0));
+                    ($vec($from_int(even)), $vec($from_int(odd)))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        $(
+            impl $vec {
+                /// Selects lane `index(i)` of `[self, other]` for each lane `i`.
+                #[inline(always)]
+                fn $permute(self, other: Self, index: impl Fn(usize) -> usize) -> Self {
+                    #[inline]
+                    #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                    unsafe fn inner(lhs: $vec, rhs: $vec, index: [usize; 16]) -> $vec {
+                        $vec($from_int(permute_epi32($to_int(lhs.0), $to_int(rhs.0), index)))
+                    }
+
+                    // Expand lane indices into indices of 32-bit lanes
+                    let dwords = 16 / Self::LANES;
+                    let index = array::from_fn(|i| index(i / dwords) * dwords + i % dwords);
+                    unsafe { inner(self, other, index) }
+                }
+            }
+        )?
+    };
+}
+
+macro_rules! impl_gather {
+    (
+        $vec:ident, $index:ident, $bias:ident, $gather:ident, $mask_gather:ident,
+        $scatter:ident, $mask_scatter:ident, $scale:literal
+    ) => {
+        impl Gather<$index> for $vec {
+            type Index = $index;
+            type Values = Self;
+            type Masks = <Self as Simd>::Mask;
+
+            #[inline(always)]
+            unsafe fn gather_unchecked(base: &[Self::Elem], index: $index) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(base: &[<$vec as Simd>::Elem], index: $index) -> $vec {
+                    let (offset, index) = $bias(index.0);
+                    let ptr = base.as_ptr().wrapping_add(offset);
+                    $vec($gather::<$scale>(index, ptr as _))
+                }
+
+                inner(base, index)
+            }
+
+            #[inline(always)]
+            unsafe fn gather_masked_unchecked(
+                base: &[Self::Elem],
+                index: $index,
+                mask: Self::Mask,
+                or: Self,
+            ) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(
+                    base: &[<$vec as Simd>::Elem],
+                    index: $index,
+                    mask: <$vec as Simd>::Mask,
+                    or: $vec,
+                ) -> $vec {
+                    let (offset, index) = $bias(index.0);
+                    let ptr = base.as_ptr().wrapping_add(offset);
+                    $vec($mask_gather::<$scale>(or.0, mask.0, index, ptr as _))
+                }
+
+                inner(base, index, mask, or)
+            }
+
+            #[inline(always)]
+            unsafe fn scatter_unchecked(values: Self, base: &mut [Self::Elem], index: $index) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $vec, base: &mut [<$vec as Simd>::Elem], index: $index) {
+                    // Scatters write lanes in order, so the highest lane wins for repeated indices
+                    let (offset, index) = $bias(index.0);
+                    let ptr = base.as_mut_ptr().wrapping_add(offset);
+                    $scatter::<$scale>(ptr as _, index, vec.0);
+                }
+
+                inner(values, base, index);
+            }
+
+            #[inline(always)]
+            unsafe fn scatter_masked_unchecked(
+                values: Self,
+                base: &mut [Self::Elem],
+                index: $index,
+                mask: Self::Mask,
+            ) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(
+                    vec: $vec,
+                    base: &mut [<$vec as Simd>::Elem],
+                    index: $index,
+                    mask: <$vec as Simd>::Mask,
+                ) {
+                    let (offset, index) = $bias(index.0);
+                    let ptr = base.as_mut_ptr().wrapping_add(offset);
+                    $mask_scatter::<$scale>(ptr as _, mask.0, index, vec.0);
+                }
+
+                inner(values, base, index, mask);
+            }
+        }
+    };
+    // 64-bit lanes through 32-bit indices, with one vector for each half of the index
+    (
+        [$vec:ident; 2], $index:ident, $bias:ident, $gather:ident,
+        $mask_gather:ident, $scatter:ident, $mask_scatter:ident
+    ) => {
+        impl Gather<$index> for $vec {
+            type Index = $index;
+            type Values = [Self; 2];
+            type Masks = [<Self as Simd>::Mask; 2];
+
+            #[inline(always)]
+            unsafe fn gather_unchecked(base: &[Self::Elem], index: $index) -> [Self; 2] {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(base: &[<$vec as Simd>::Elem], index: $index) -> [$vec; 2] {
+                    let (offset, index) = $bias(index.0);
+                    let ptr = base.as_ptr().wrapping_add(offset);
+                    let lo = _mm512_castsi512_si256(index);
+                    let hi = _mm512_extracti64x4_epi64::<1>(index);
+                    [$vec($gather::<8>(lo, ptr as _)), $vec($gather::<8>(hi, ptr as _))]
+                }
+
+                inner(base, index)
+            }
+
+            #[inline(always)]
+            unsafe fn gather_masked_unchecked(
+                base: &[Self::Elem],
+                index: $index,
+                mask: [Self::Mask; 2],
+                or: [Self; 2],
+            ) -> [Self; 2] {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(
+                    base: &[<$vec as Simd>::Elem],
+                    index: $index,
+                    mask: [<$vec as Simd>::Mask; 2],
+                    or: [$vec; 2],
+                ) -> [$vec; 2] {
+                    let (offset, index) = $bias(index.0);
+                    let ptr = base.as_ptr().wrapping_add(offset);
+                    let lo = _mm512_castsi512_si256(index);
+                    let hi = _mm512_extracti64x4_epi64::<1>(index);
+                    [
+                        $vec($mask_gather::<8>(or[0].0, mask[0].0, lo, ptr as _)),
+                        $vec($mask_gather::<8>(or[1].0, mask[1].0, hi, ptr as _)),
+                    ]
+                }
+
+                inner(base, index, mask, or)
+            }
+
+            #[inline(always)]
+            unsafe fn scatter_unchecked(values: [Self; 2], base: &mut [Self::Elem], index: $index) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(
+                    values: [$vec; 2],
+                    base: &mut [<$vec as Simd>::Elem],
+                    index: $index,
+                ) {
+                    // Scatter the low half first so that later lanes win for repeated indices
+                    let (offset, index) = $bias(index.0);
+                    let ptr = base.as_mut_ptr().wrapping_add(offset);
+                    $scatter::<8>(ptr as _, _mm512_castsi512_si256(index), values[0].0);
+                    $scatter::<8>(ptr as _, _mm512_extracti64x4_epi64::<1>(index), values[1].0);
+                }
+
+                inner(values, base, index);
+            }
+
+            #[inline(always)]
+            unsafe fn scatter_masked_unchecked(
+                values: [Self; 2],
+                base: &mut [Self::Elem],
+                index: $index,
+                mask: [Self::Mask; 2],
+            ) {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(
+                    values: [$vec; 2],
+                    base: &mut [<$vec as Simd>::Elem],
+                    index: $index,
+                    mask: [<$vec as Simd>::Mask; 2],
+                ) {
+                    let (offset, index) = $bias(index.0);
+                    let ptr = base.as_mut_ptr().wrapping_add(offset);
+                    let lo = _mm512_castsi512_si256(index);
+                    let hi = _mm512_extracti64x4_epi64::<1>(index);
+                    $mask_scatter::<8>(ptr as _, mask[0].0, lo, values[0].0);
+                    $mask_scatter::<8>(ptr as _, mask[1].0, hi, values[1].0);
+                }
+
+                inner(values, base, index, mask);
+            }
+        }
+    };
+    ($vec:ident, $($index:ident),*) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = $index;
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+    // Each vector of `$vec` is indexed by `$n` index vectors
+    ($vec:ident, [$($index:ident),*; $n:literal]) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = [$index; $n];
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+}
+
+macro_rules! impl_cast_bits {
+    ($from:ident, $to:ident) => {
+        impl Cast<$to> for $from {
+            #[inline(always)]
+            fn cast(self) -> $to {
+                $to(self.0)
+            }
+        }
+    };
+}
+
+macro_rules! impl_float_to_int {
+    (
+        $trait:ident, $method:ident, $float:ident, $int:ident, $int_elem:ident, $uint:ident,
+        $cvt:ident, $cvtu:ident, $cmp:ident, $set:ident, $max:ident, $setzero:ident,
+        $mask_mov:ident, $maskz_mov:ident, $set_int:ident
+    ) => {
+        impl $trait<$int> for $float {
+            #[inline(always)]
+            fn $method(self) -> $int {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $int {
+                    // The conversion returns MIN for NaN and out-of-range lanes, so saturate
+                    // positive overflow to MAX and zero out NaNs to match `as`
+                    let res = $cvt(vec.0);
+                    let bound = $set(-($int_elem::MIN as <$float as Simd>::Elem));
+                    let overflow = $cmp(vec.0, bound, _CMP_GE_OQ);
+                    let res = $mask_mov(res, overflow, $set_int($int_elem::MAX as _));
+                    $int($maskz_mov($cmp(vec.0, vec.0, _CMP_ORD_Q), res))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+
+        impl $trait<$uint> for $float {
+            #[inline(always)]
+            fn $method(self) -> $uint {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $float) -> $uint {
+                    // The conversion returns MAX for out-of-range lanes, which is already the
+                    // saturated result for positive overflow. Negative lanes and NaNs are clamped
+                    // to zero first, since max returns its second operand for NaN.
+                    $uint($cvtu($max(vec.0, $setzero())))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_int_to_float {
+    ($int:ident, $float:ident, $cvt:ident) => {
+        impl Cast<$float> for $int {
+            #[inline(always)]
+            fn cast(self) -> $float {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $int) -> $float {
+                    $float($cvt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_widen_int {
+    ($narrow:ident, $wide:ident, $extend:ident, $truncate:ident) => {
+        impl Widen for $narrow {
+            type Wide = $wide;
+            type Parts = [$wide; 2];
+
+            #[inline(always)]
+            fn widen(self) -> Self::Parts {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(vec: $narrow) -> [$wide; 2] {
+                    let low = _mm512_castsi512_si256(vec.0);
+                    let high = _mm512_extracti64x4_epi64(vec.0, 1);
+                    [$wide($extend(low)), $wide($extend(high))]
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn narrow(parts: Self::Parts) -> Self {
+                #[inline]
+                #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+                unsafe fn inner(parts: [$wide; 2]) -> $narrow {
+                    $narrow($truncate(parts[0].0, parts[1].0))
+                }
+
+                unsafe { inner(parts) }
+            }
+        }
+    };
+}
+
+const ODD_BYTES: __mmask64 = 0xAAAAAAAAAAAAAAAA;
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_sll_epi8_fallback(a: __m512i, count: __m128i) -> __m512i {
+    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm512_set1_epi8((0xFFu8 << shift) as i8);
+    _mm512_and_si512(mask, _mm512_sll_epi16(a, count))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_srl_epi8_fallback(a: __m512i, count: __m128i) -> __m512i {
+    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm512_set1_epi8((0xFFu8 >> shift) as i8);
+    _mm512_and_si512(mask, _mm512_srl_epi16(a, count))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_sra_epi8_fallback(a: __m512i, count: __m128i) -> __m512i {
+    // Shift the odd bytes in place, and the even bytes after moving them into the high byte of
+    // each 16-bit lane, so that the arithmetic shift fills in the right sign bits
+    let even = _mm512_srai_epi16(_mm512_sra_epi16(_mm512_slli_epi16(a, 8), count), 8);
+    let odd = _mm512_sra_epi16(a, count);
+    _mm512_mask_blend_epi8(ODD_BYTES, even, odd)
+}
+
+// Variable shifts of bytes shift even and odd bytes separately in 16-bit lanes, each by the count
+// in the corresponding byte
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_sllv_epi8_fallback(a: __m512i, count: __m512i) -> __m512i {
+    let low_bytes = _mm512_set1_epi16(0xFF);
+    let even = _mm512_sllv_epi16(a, _mm512_and_si512(count, low_bytes));
+    let odd = _mm512_sllv_epi16(
+        _mm512_andnot_si512(low_bytes, a),
+        _mm512_srli_epi16(count, 8),
+    );
+    _mm512_mask_blend_epi8(ODD_BYTES, even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_srlv_epi8_fallback(a: __m512i, count: __m512i) -> __m512i {
+    let low_bytes = _mm512_set1_epi16(0xFF);
+    let even = _mm512_srlv_epi16(
+        _mm512_and_si512(a, low_bytes),
+        _mm512_and_si512(count, low_bytes),
+    );
+    let odd = _mm512_srlv_epi16(a, _mm512_srli_epi16(count, 8));
+    _mm512_mask_blend_epi8(ODD_BYTES, even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_srav_epi8_fallback(a: __m512i, count: __m512i) -> __m512i {
+    let low_bytes = _mm512_set1_epi16(0xFF);
+    let even = _mm512_srav_epi16(
+        _mm512_srai_epi16(_mm512_slli_epi16(a, 8), 8),
+        _mm512_and_si512(count, low_bytes),
+    );
+    let odd = _mm512_srav_epi16(a, _mm512_srli_epi16(count, 8));
+    _mm512_mask_blend_epi8(ODD_BYTES, even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_adds_epu32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // a + !a is the maximum value, so clamping b to !a saturates the sum
+    _mm512_add_epi32(
+        a,
+        _mm512_min_epu32(b, _mm512_xor_si512(a, _mm512_set1_epi32(-1))),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_subs_epu32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_sub_epi32(_mm512_max_epu32(a, b), b)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_adds_epi32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Overflow occurs when the inputs have the same sign and the result has the opposite sign,
+    // in which case saturate toward the sign of the inputs
+    let sum = _mm512_add_epi32(a, b);
+    let overflow = _mm512_movepi32_mask(_mm512_and_si512(
+        _mm512_xor_si512(sum, a),
+        _mm512_xor_si512(sum, b),
+    ));
+    let saturated = _mm512_xor_si512(_mm512_srai_epi32(a, 31), _mm512_set1_epi32(i32::MAX));
+    _mm512_mask_mov_epi32(sum, overflow, saturated)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_subs_epi32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Overflow occurs when the inputs have opposite signs and the result has the sign of the
+    // subtrahend, in which case saturate toward the sign of the minuend
+    let diff = _mm512_sub_epi32(a, b);
+    let overflow = _mm512_movepi32_mask(_mm512_and_si512(
+        _mm512_xor_si512(a, b),
+        _mm512_xor_si512(a, diff),
+    ));
+    let saturated = _mm512_xor_si512(_mm512_srai_epi32(a, 31), _mm512_set1_epi32(i32::MAX));
+    _mm512_mask_mov_epi32(diff, overflow, saturated)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_adds_epu64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // See _mm512_adds_epu32_fallback
+    _mm512_add_epi64(
+        a,
+        _mm512_min_epu64(b, _mm512_xor_si512(a, _mm512_set1_epi32(-1))),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_subs_epu64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_sub_epi64(_mm512_max_epu64(a, b), b)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_adds_epi64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // See _mm512_adds_epi32_fallback
+    let sum = _mm512_add_epi64(a, b);
+    let overflow = _mm512_movepi64_mask(_mm512_and_si512(
+        _mm512_xor_si512(sum, a),
+        _mm512_xor_si512(sum, b),
+    ));
+    let saturated = _mm512_xor_si512(_mm512_srai_epi64(a, 63), _mm512_set1_epi64(i64::MAX));
+    _mm512_mask_mov_epi64(sum, overflow, saturated)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_subs_epi64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // See _mm512_subs_epi32_fallback
+    let diff = _mm512_sub_epi64(a, b);
+    let overflow = _mm512_movepi64_mask(_mm512_and_si512(
+        _mm512_xor_si512(a, b),
+        _mm512_xor_si512(a, diff),
+    ));
+    let saturated = _mm512_xor_si512(_mm512_srai_epi64(a, 63), _mm512_set1_epi64(i64::MAX));
+    _mm512_mask_mov_epi64(diff, overflow, saturated)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_avg_epi8_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Bias into the unsigned range, average, and then remove the bias
+    let bias = _mm512_set1_epi8(i8::MIN);
+    _mm512_xor_si512(
+        _mm512_avg_epu8(_mm512_xor_si512(a, bias), _mm512_xor_si512(b, bias)),
+        bias,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_avg_epi16_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Bias into the unsigned range, average, and then remove the bias
+    let bias = _mm512_set1_epi16(i16::MIN);
+    _mm512_xor_si512(
+        _mm512_avg_epu16(_mm512_xor_si512(a, bias), _mm512_xor_si512(b, bias)),
+        bias,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_avg_epu32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // (a | b) - ((a ^ b) >> 1) rounds the average up without overflowing
+    _mm512_sub_epi32(
+        _mm512_or_si512(a, b),
+        _mm512_srli_epi32(_mm512_xor_si512(a, b), 1),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_avg_epi32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // See _mm512_avg_epu32_fallback
+    _mm512_sub_epi32(
+        _mm512_or_si512(a, b),
+        _mm512_srai_epi32(_mm512_xor_si512(a, b), 1),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_avg_epu64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // See _mm512_avg_epu32_fallback
+    _mm512_sub_epi64(
+        _mm512_or_si512(a, b),
+        _mm512_srli_epi64(_mm512_xor_si512(a, b), 1),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_avg_epi64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // See _mm512_avg_epu32_fallback
+    _mm512_sub_epi64(
+        _mm512_or_si512(a, b),
+        _mm512_srai_epi64(_mm512_xor_si512(a, b), 1),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_mulhi_epu8_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Multiply even and odd bytes separately in 16-bit lanes and keep the high byte of each
+    let mask = _mm512_set1_epi16(0xFF);
+    let even = _mm512_mullo_epi16(_mm512_and_si512(a, mask), _mm512_and_si512(b, mask));
+    let odd = _mm512_mullo_epi16(_mm512_srli_epi16(a, 8), _mm512_srli_epi16(b, 8));
+    _mm512_mask_blend_epi8(ODD_BYTES, _mm512_srli_epi16(even, 8), odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_mulhi_epi8_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Multiply even and odd bytes separately in 16-bit lanes and keep the high byte of each
+    let a_even = _mm512_srai_epi16(_mm512_slli_epi16(a, 8), 8);
+    let b_even = _mm512_srai_epi16(_mm512_slli_epi16(b, 8), 8);
+    let even = _mm512_mullo_epi16(a_even, b_even);
+    let odd = _mm512_mullo_epi16(_mm512_srai_epi16(a, 8), _mm512_srai_epi16(b, 8));
+    _mm512_mask_blend_epi8(ODD_BYTES, _mm512_srli_epi16(even, 8), odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_mulhi_epu32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Multiply even and odd lanes separately in 64-bit lanes and keep the high half of each
+    let even = _mm512_mul_epu32(a, b);
+    let odd = _mm512_mul_epu32(_mm512_srli_epi64(a, 32), _mm512_srli_epi64(b, 32));
+    _mm512_mask_blend_epi32(0xAAAA, _mm512_srli_epi64(even, 32), odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_mulhi_epi32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Multiply even and odd lanes separately in 64-bit lanes and keep the high half of each
+    let even = _mm512_mul_epi32(a, b);
+    let odd = _mm512_mul_epi32(_mm512_srli_epi64(a, 32), _mm512_srli_epi64(b, 32));
+    _mm512_mask_blend_epi32(0xAAAA, _mm512_srli_epi64(even, 32), odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_mulhi_epu64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Sum the four 32x32-bit partial products
+    let mask = _mm512_set1_epi64(0xFFFFFFFF);
+    let a_high = _mm512_srli_epi64(a, 32);
+    let b_high = _mm512_srli_epi64(b, 32);
+    let low_low = _mm512_mul_epu32(a, b);
+    let low_high = _mm512_mul_epu32(a, b_high);
+    let high_low = _mm512_mul_epu32(a_high, b);
+    let high_high = _mm512_mul_epu32(a_high, b_high);
+    // The middle column cannot overflow, as it is a sum of three 32-bit values
+    let middle = _mm512_add_epi64(
+        _mm512_add_epi64(
+            _mm512_srli_epi64(low_low, 32),
+            _mm512_and_si512(low_high, mask),
+        ),
+        _mm512_and_si512(high_low, mask),
+    );
+    let high = _mm512_add_epi64(
+        _mm512_add_epi64(high_high, _mm512_srli_epi64(low_high, 32)),
+        _mm512_srli_epi64(high_low, 32),
+    );
+    _mm512_add_epi64(high, _mm512_srli_epi64(middle, 32))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_mulhi_epi64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Correct the unsigned product for negative inputs, each of which contributes an extra
+    // 2^64 times the other input
+    let high = _mm512_mulhi_epu64_fallback(a, b);
+    let a_correction = _mm512_and_si512(_mm512_srai_epi64(a, 63), b);
+    let b_correction = _mm512_and_si512(_mm512_srai_epi64(b, 63), a);
+    _mm512_sub_epi64(_mm512_sub_epi64(high, a_correction), b_correction)
+}
+
+// Integer division is performed in floating point where the result is guaranteed to be exact
+// after truncation, i.e. where the inputs are representable and the gap between the quotient and
+// the next integer always exceeds the rounding error.
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_div_epu8_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Divide in 16-bit lanes and then truncate
+    let low = _mm512_div_epu16_fallback(
+        _mm512_cvtepu8_epi16(_mm512_castsi512_si256(a)),
+        _mm512_cvtepu8_epi16(_mm512_castsi512_si256(b)),
+    );
+    let high = _mm512_div_epu16_fallback(
+        _mm512_cvtepu8_epi16(_mm512_extracti64x4_epi64(a, 1)),
+        _mm512_cvtepu8_epi16(_mm512_extracti64x4_epi64(b, 1)),
+    );
+    truncate_epi16(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_div_epi8_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Divide in 16-bit lanes and then truncate, which also wraps MIN / -1
+    let low = _mm512_div_epi16_fallback(
+        _mm512_cvtepi8_epi16(_mm512_castsi512_si256(a)),
+        _mm512_cvtepi8_epi16(_mm512_castsi512_si256(b)),
+    );
+    let high = _mm512_div_epi16_fallback(
+        _mm512_cvtepi8_epi16(_mm512_extracti64x4_epi64(a, 1)),
+        _mm512_cvtepi8_epi16(_mm512_extracti64x4_epi64(b, 1)),
+    );
+    truncate_epi16(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn div_epi32_ps(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_cvttps_epi32(_mm512_div_ps(_mm512_cvtepi32_ps(a), _mm512_cvtepi32_ps(b)))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_div_epu16_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Divide in single precision. Division by zero converts to 0x80000000, which truncates to
+    // zero.
+    let low = div_epi32_ps(
+        _mm512_cvtepu16_epi32(_mm512_castsi512_si256(a)),
+        _mm512_cvtepu16_epi32(_mm512_castsi512_si256(b)),
+    );
+    let high = div_epi32_ps(
+        _mm512_cvtepu16_epi32(_mm512_extracti64x4_epi64(a, 1)),
+        _mm512_cvtepu16_epi32(_mm512_extracti64x4_epi64(b, 1)),
+    );
+    truncate_epi32(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_div_epi16_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Divide in single precision. Truncating the result wraps MIN / -1, and division by zero
+    // converts to 0x80000000, which truncates to zero.
+    let low = div_epi32_ps(
+        _mm512_cvtepi16_epi32(_mm512_castsi512_si256(a)),
+        _mm512_cvtepi16_epi32(_mm512_castsi512_si256(b)),
+    );
+    let high = div_epi32_ps(
+        _mm512_cvtepi16_epi32(_mm512_extracti64x4_epi64(a, 1)),
+        _mm512_cvtepi16_epi32(_mm512_extracti64x4_epi64(b, 1)),
+    );
+    truncate_epi32(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn div_epu32_pd(a: __m256i, b: __m256i) -> __m256i {
+    _mm512_cvttpd_epu32(_mm512_div_pd(_mm512_cvtepu32_pd(a), _mm512_cvtepu32_pd(b)))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_div_epu32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Divide in double precision
+    let low = div_epu32_pd(_mm512_castsi512_si256(a), _mm512_castsi512_si256(b));
+    let high = div_epu32_pd(
+        _mm512_extracti64x4_epi64(a, 1),
+        _mm512_extracti64x4_epi64(b, 1),
+    );
+    let quotient = _mm512_inserti64x4(_mm512_castsi256_si512(low), high, 1);
+    _mm512_maskz_mov_epi32(_mm512_test_epi32_mask(b, b), quotient)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn div_epi32_pd(a: __m256i, b: __m256i) -> __m256i {
+    _mm512_cvttpd_epi32(_mm512_div_pd(_mm512_cvtepi32_pd(a), _mm512_cvtepi32_pd(b)))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_div_epi32_fallback(a: __m512i, b: __m512i) -> __m512i {
+    // Divide in double precision. MIN / -1 is out of range, and converts to MIN as desired.
+    let low = div_epi32_pd(_mm512_castsi512_si256(a), _mm512_castsi512_si256(b));
+    let high = div_epi32_pd(
+        _mm512_extracti64x4_epi64(a, 1),
+        _mm512_extracti64x4_epi64(b, 1),
+    );
+    let quotient = _mm512_inserti64x4(_mm512_castsi256_si512(low), high, 1);
+    _mm512_maskz_mov_epi32(_mm512_test_epi32_mask(b, b), quotient)
+}
+
+// There is no floating-point type wide enough to divide 64-bit integers exactly, so fall back to
+// scalar division
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_div_epu64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    let a: [u64; 8] = mem::transmute(a);
+    let b: [u64; 8] = mem::transmute(b);
+    mem::transmute(array::from_fn::<u64, 8, _>(|i| {
+        a[i].checked_div(b[i]).unwrap_or(0)
+    }))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+pub unsafe fn _mm512_div_epi64_fallback(a: __m512i, b: __m512i) -> __m512i {
+    let a: [i64; 8] = mem::transmute(a);
+    let b: [i64; 8] = mem::transmute(b);
+    mem::transmute(array::from_fn::<i64, 8, _>(|i| {
+        if b[i] == 0 {
+            0
+        } else {
+            a[i].wrapping_div(b[i])
+        }
+    }))
+}
+
+/// Returns a bitmask of the lanes below `len`, to be truncated to the width of a k-register.
+#[inline(always)]
+fn prefix_mask(len: usize) -> u64 {
+    if len < 64 {
+        !(u64::MAX << len)
+    } else {
+        u64::MAX
+    }
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn truncate_epi16(a: __m512i, b: __m512i) -> __m512i {
+    let low = _mm512_castsi256_si512(_mm512_cvtepi16_epi8(a));
+    _mm512_inserti64x4(low, _mm512_cvtepi16_epi8(b), 1)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn truncate_epi32(a: __m512i, b: __m512i) -> __m512i {
+    let low = _mm512_castsi256_si512(_mm512_cvtepi32_epi16(a));
+    _mm512_inserti64x4(low, _mm512_cvtepi32_epi16(b), 1)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn truncate_epi64(a: __m512i, b: __m512i) -> __m512i {
+    let low = _mm512_castsi256_si512(_mm512_cvtepi64_epi32(a));
+    _mm512_inserti64x4(low, _mm512_cvtepi64_epi32(b), 1)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn permute_epi32(a: __m512i, b: __m512i, index: [usize; 16]) -> __m512i {
+    // Bit 4 of each index selects between `a` and `b`
+    let index = index.map(|i| i as i32);
+    let index = _mm512_loadu_si512(index.as_ptr() as *const __m512i);
+    _mm512_permutex2var_epi32(a, index, b)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn reverse_epi8(a: __m512i) -> __m512i {
+    // Reverse bytes within each 128-bit lane, then reverse the order of the 128-bit lanes
+    let index = _mm512_broadcast_i32x4(_mm_setr_epi8(
+        15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    ));
+    let res = _mm512_shuffle_epi8(a, index);
+    _mm512_shuffle_i64x2(res, res, 0x1B)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn reverse_epi16(a: __m512i) -> __m512i {
+    // Reverse 16-bit lanes within each 128-bit lane, then reverse the order of the 128-bit lanes
+    let index = _mm512_broadcast_i32x4(_mm_setr_epi8(
+        14, 15, 12, 13, 10, 11, 8, 9, 6, 7, 4, 5, 2, 3, 0, 1,
+    ));
+    let res = _mm512_shuffle_epi8(a, index);
+    _mm512_shuffle_i64x2(res, res, 0x1B)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn reverse_epi32(a: __m512i) -> __m512i {
+    let index = _mm512_setr_epi32(15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    _mm512_permutexvar_epi32(index, a)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn reverse_epi64(a: __m512i) -> __m512i {
+    _mm512_permutexvar_epi64(_mm512_setr_epi64(7, 6, 5, 4, 3, 2, 1, 0), a)
+}
+
+// Even lanes are the low halves of lanes twice as wide, and odd lanes are the high halves
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn deinterleave_epi8(a: __m512i, b: __m512i) -> (__m512i, __m512i) {
+    let even = truncate_epi16(a, b);
+    let odd = truncate_epi16(_mm512_srli_epi16(a, 8), _mm512_srli_epi16(b, 8));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn deinterleave_epi16(a: __m512i, b: __m512i) -> (__m512i, __m512i) {
+    let even = truncate_epi32(a, b);
+    let odd = truncate_epi32(_mm512_srli_epi32(a, 16), _mm512_srli_epi32(b, 16));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn deinterleave_epi32(a: __m512i, b: __m512i) -> (__m512i, __m512i) {
+    let even = truncate_epi64(a, b);
+    let odd = truncate_epi64(_mm512_srli_epi64(a, 32), _mm512_srli_epi64(b, 32));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn deinterleave_epi64(a: __m512i, b: __m512i) -> (__m512i, __m512i) {
+    let even = _mm512_setr_epi64(0, 2, 4, 6, 8, 10, 12, 14);
+    let odd = _mm512_setr_epi64(1, 3, 5, 7, 9, 11, 13, 15);
+    (
+        _mm512_permutex2var_epi64(a, even, b),
+        _mm512_permutex2var_epi64(a, odd, b),
+    )
+}
+
+/// Gathers treat 32-bit indices as signed, so move the base pointer forward by 2^31 elements and
+/// flip the sign bit of each index, which reaches the same elements for every `u32` index.
+#[inline]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+unsafe fn bias_index_epu32(index: __m512i) -> (usize, __m512i) {
+    (
+        1 << 31,
+        _mm512_xor_si512(index, _mm512_set1_epi32(i32::MIN)),
+    )
+}
+
+/// Signed indices, and 64-bit indices that are in bounds, are used as they are.
+#[inline(always)]
+fn unbiased_index(index: __m512i) -> (usize, __m512i) {
+    (0, index)
+}
+
+float_type! {
+    f32x16, __m512, f32, 16, m32x16, __mmask16, u32x16,
+    _mm512_set1_ps, _mm512_loadu_ps, _mm512_load_ps, _mm512_storeu_ps, _mm512_stream_ps,
+    _mm512_maskz_loadu_ps, _mm512_mask_loadu_ps, _mm512_mask_storeu_ps,
+    _mm512_castps_si512, _mm512_castsi512_ps, _mm512_mask_blend_ps,
+    _mm512_cmp_ps_mask, _mm512_max_ps, _mm512_min_ps, _mm512_add_ps, _mm512_sub_ps, _mm512_mul_ps, _mm512_div_ps, _mm512_xor_ps,
+    _mm512_and_ps, _mm512_or_ps, _mm512_andnot_ps, _mm512_fmadd_ps, _mm512_sqrt_ps, _mm512_rcp14_ps, _mm512_rsqrt14_ps,
+    _mm512_roundscale_ps,
+}
+float_type! {
+    f64x8, __m512d, f64, 8, m64x8, __mmask8, u64x8,
+    _mm512_set1_pd, _mm512_loadu_pd, _mm512_load_pd, _mm512_storeu_pd, _mm512_stream_pd,
+    _mm512_maskz_loadu_pd, _mm512_mask_loadu_pd, _mm512_mask_storeu_pd,
+    _mm512_castpd_si512, _mm512_castsi512_pd, _mm512_mask_blend_pd,
+    _mm512_cmp_pd_mask, _mm512_max_pd, _mm512_min_pd, _mm512_add_pd, _mm512_sub_pd, _mm512_mul_pd, _mm512_div_pd, _mm512_xor_pd,
+    _mm512_and_pd, _mm512_or_pd, _mm512_andnot_pd, _mm512_fmadd_pd, _mm512_sqrt_pd, _mm512_rcp14_pd, _mm512_rsqrt14_pd,
+    _mm512_roundscale_pd,
+}
+
+impl_float_to_int! {
+    Cast, cast, f32x16, i32x16, i32, u32x16,
+    _mm512_cvttps_epi32, _mm512_cvttps_epu32, _mm512_cmp_ps_mask, _mm512_set1_ps, _mm512_max_ps, _mm512_setzero_ps,
+    _mm512_mask_mov_epi32, _mm512_maskz_mov_epi32, _mm512_set1_epi32
+}
+impl_float_to_int! {
+    RoundCast, round_cast, f32x16, i32x16, i32, u32x16,
+    _mm512_cvtps_epi32, _mm512_cvtps_epu32, _mm512_cmp_ps_mask, _mm512_set1_ps, _mm512_max_ps, _mm512_setzero_ps,
+    _mm512_mask_mov_epi32, _mm512_maskz_mov_epi32, _mm512_set1_epi32
+}
+impl_float_to_int! {
+    Cast, cast, f64x8, i64x8, i64, u64x8,
+    _mm512_cvttpd_epi64, _mm512_cvttpd_epu64, _mm512_cmp_pd_mask, _mm512_set1_pd, _mm512_max_pd, _mm512_setzero_pd,
+    _mm512_mask_mov_epi64, _mm512_maskz_mov_epi64, _mm512_set1_epi64
+}
+impl_float_to_int! {
+    RoundCast, round_cast, f64x8, i64x8, i64, u64x8,
+    _mm512_cvtpd_epi64, _mm512_cvtpd_epu64, _mm512_cmp_pd_mask, _mm512_set1_pd, _mm512_max_pd, _mm512_setzero_pd,
+    _mm512_mask_mov_epi64, _mm512_maskz_mov_epi64, _mm512_set1_epi64
+}
+impl_gather! { f32x16, u32x16, bias_index_epu32, _mm512_i32gather_ps, _mm512_mask_i32gather_ps, _mm512_i32scatter_ps, _mm512_mask_i32scatter_ps, 4 }
+impl_gather! { f32x16, i32x16, unbiased_index, _mm512_i32gather_ps, _mm512_mask_i32gather_ps, _mm512_i32scatter_ps, _mm512_mask_i32scatter_ps, 4 }
+impl_gather! { f64x8, u64x8, unbiased_index, _mm512_i64gather_pd, _mm512_mask_i64gather_pd, _mm512_i64scatter_pd, _mm512_mask_i64scatter_pd, 8 }
+impl_gather! { f64x8, i64x8, unbiased_index, _mm512_i64gather_pd, _mm512_mask_i64gather_pd, _mm512_i64scatter_pd, _mm512_mask_i64scatter_pd, 8 }
+impl_gather! { [f64x8; 2], u32x16, bias_index_epu32, _mm512_i32gather_pd, _mm512_mask_i32gather_pd, _mm512_i32scatter_pd, _mm512_mask_i32scatter_pd }
+impl_gather! { [f64x8; 2], i32x16, unbiased_index, _mm512_i32gather_pd, _mm512_mask_i32gather_pd, _mm512_i32scatter_pd, _mm512_mask_i32scatter_pd }
+impl_shuffle! { f32x16, _mm512_castps_si512, _mm512_castsi512_ps, reverse_epi32, _mm512_unpacklo_epi32, _mm512_unpackhi_epi32, deinterleave_epi32, permute }
+impl_shuffle! { f64x8, _mm512_castpd_si512, _mm512_castsi512_pd, reverse_epi64, _mm512_unpacklo_epi64, _mm512_unpackhi_epi64, deinterleave_epi64, permute }
+
+impl Widen for f32x16 {
+    type Wide = f64x8;
+    type Parts = [f64x8; 2];
+
+    #[inline(always)]
+    fn widen(self) -> Self::Parts {
+        #[inline]
+        #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+        unsafe fn inner(vec: f32x16) -> [f64x8; 2] {
+            let low = _mm512_castps512_ps256(vec.0);
+            let high = _mm512_extractf32x8_ps(vec.0, 1);
+            [f64x8(_mm512_cvtps_pd(low)), f64x8(_mm512_cvtps_pd(high))]
+        }
+
+        unsafe { inner(self) }
+    }
+
+    #[inline(always)]
+    fn narrow(parts: Self::Parts) -> Self {
+        #[inline]
+        #[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512vl")]
+        unsafe fn inner(parts: [f64x8; 2]) -> f32x16 {
+            let low = _mm512_castps256_ps512(_mm512_cvtpd_ps(parts[0].0));
+            f32x16(_mm512_insertf32x8(low, _mm512_cvtpd_ps(parts[1].0), 1))
+        }
+
+        unsafe { inner(parts) }
+    }
+}
+
+int_type! { u8x64, u8, 64, m8x64, __mmask64, _mm512_set1_epi8, _mm512_cmpeq_epi8_mask, _mm512_mask_blend_epi8, _mm512_maskz_loadu_epi8, _mm512_mask_loadu_epi8, _mm512_mask_storeu_epi8 }
+int_type! { u16x32, u16, 32, m16x32, __mmask32, _mm512_set1_epi16, _mm512_cmpeq_epi16_mask, _mm512_mask_blend_epi16, _mm512_maskz_loadu_epi16, _mm512_mask_loadu_epi16, _mm512_mask_storeu_epi16 }
+int_type! { u32x16, u32, 16, m32x16, __mmask16, _mm512_set1_epi32, _mm512_cmpeq_epi32_mask, _mm512_mask_blend_epi32, _mm512_maskz_loadu_epi32, _mm512_mask_loadu_epi32, _mm512_mask_storeu_epi32 }
+int_type! { u64x8, u64, 8, m64x8, __mmask8, _mm512_set1_epi64, _mm512_cmpeq_epi64_mask, _mm512_mask_blend_epi64, _mm512_maskz_loadu_epi64, _mm512_mask_loadu_epi64, _mm512_mask_storeu_epi64 }
+impl_ord! { u8x64, m8x64, _mm512_cmplt_epu8_mask, _mm512_cmple_epu8_mask, _mm512_max_epu8, _mm512_min_epu8 }
+impl_ord! { u16x32, m16x32, _mm512_cmplt_epu16_mask, _mm512_cmple_epu16_mask, _mm512_max_epu16, _mm512_min_epu16 }
+impl_ord! { u32x16, m32x16, _mm512_cmplt_epu32_mask, _mm512_cmple_epu32_mask, _mm512_max_epu32, _mm512_min_epu32 }
+impl_ord! { u64x8, m64x8, _mm512_cmplt_epu64_mask, _mm512_cmple_epu64_mask, _mm512_max_epu64, _mm512_min_epu64 }
+impl_int! { u8x64, u8, _mm512_set1_epi8, _mm512_add_epi8, _mm512_sub_epi8, _mm512_sll_epi8_fallback, _mm512_srl_epi8_fallback, _mm512_srl_epi8_fallback, _mm512_sllv_epi8_fallback, _mm512_srlv_epi8_fallback, _mm512_adds_epu8, _mm512_subs_epu8, _mm512_avg_epu8, _mm512_mulhi_epu8_fallback, _mm512_div_epu8_fallback }
+impl_int! { u16x32, u16, _mm512_set1_epi16, _mm512_add_epi16, _mm512_sub_epi16, _mm512_sll_epi16, _mm512_srl_epi16, _mm512_srl_epi16, _mm512_sllv_epi16, _mm512_srlv_epi16, _mm512_adds_epu16, _mm512_subs_epu16, _mm512_avg_epu16, _mm512_mulhi_epu16, _mm512_div_epu16_fallback }
+impl_int! { u32x16, u32, _mm512_set1_epi32, _mm512_add_epi32, _mm512_sub_epi32, _mm512_sll_epi32, _mm512_srl_epi32, _mm512_srl_epi32, _mm512_sllv_epi32, _mm512_srlv_epi32, _mm512_adds_epu32_fallback, _mm512_subs_epu32_fallback, _mm512_avg_epu32_fallback, _mm512_mulhi_epu32_fallback, _mm512_div_epu32_fallback }
+impl_int! { u64x8, u64, _mm512_set1_epi64, _mm512_add_epi64, _mm512_sub_epi64, _mm512_sll_epi64, _mm512_srl_epi64, _mm512_srl_epi64, _mm512_sllv_epi64, _mm512_srlv_epi64, _mm512_adds_epu64_fallback, _mm512_subs_epu64_fallback, _mm512_avg_epu64_fallback, _mm512_mulhi_epu64_fallback, _mm512_div_epu64_fallback }
+impl_int_mul! { u8x64, u16x32, u32x16, u64x8 }
+impl_shuffle! { u8x64, identity, identity, reverse_epi8, _mm512_unpacklo_epi8, _mm512_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { u16x32, identity, identity, reverse_epi16, _mm512_unpacklo_epi16, _mm512_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { u32x16, identity, identity, reverse_epi32, _mm512_unpacklo_epi32, _mm512_unpackhi_epi32, deinterleave_epi32, permute }
+impl_shuffle! { u64x8, identity, identity, reverse_epi64, _mm512_unpacklo_epi64, _mm512_unpackhi_epi64, deinterleave_epi64, permute }
+impl_cast_bits! { u8x64, i8x64 }
+impl_cast_bits! { u16x32, i16x32 }
+impl_cast_bits! { u32x16, i32x16 }
+impl_cast_bits! { u64x8, i64x8 }
+impl_int_to_float! { u32x16, f32x16, _mm512_cvtepu32_ps }
+impl_int_to_float! { u64x8, f64x8, _mm512_cvtepu64_pd }
+impl_widen_int! { u8x64, u16x32, _mm512_cvtepu8_epi16, truncate_epi16 }
+impl_widen_int! { u16x32, u32x16, _mm512_cvtepu16_epi32, truncate_epi32 }
+impl_widen_int! { u32x16, u64x8, _mm512_cvtepu32_epi64, truncate_epi64 }
+impl_gather! { u8x64, u8x64, i8x64 }
+impl_gather! { u8x64, [u32x16, i32x16; 4] }
+impl_gather! { u16x32, u16x32, i16x32 }
+impl_gather! { u16x32, [u32x16, i32x16; 2] }
+impl_gather! { u32x16, u32x16, bias_index_epu32, _mm512_i32gather_epi32, _mm512_mask_i32gather_epi32, _mm512_i32scatter_epi32, _mm512_mask_i32scatter_epi32, 4 }
+impl_gather! { u32x16, i32x16, unbiased_index, _mm512_i32gather_epi32, _mm512_mask_i32gather_epi32, _mm512_i32scatter_epi32, _mm512_mask_i32scatter_epi32, 4 }
+impl_gather! { u64x8, u64x8, unbiased_index, _mm512_i64gather_epi64, _mm512_mask_i64gather_epi64, _mm512_i64scatter_epi64, _mm512_mask_i64scatter_epi64, 8 }
+impl_gather! { u64x8, i64x8, unbiased_index, _mm512_i64gather_epi64, _mm512_mask_i64gather_epi64, _mm512_i64scatter_epi64, _mm512_mask_i64scatter_epi64, 8 }
+impl_gather! { [u64x8; 2], u32x16, bias_index_epu32, _mm512_i32gather_epi64, _mm512_mask_i32gather_epi64, _mm512_i32scatter_epi64, _mm512_mask_i32scatter_epi64 }
+impl_gather! { [u64x8; 2], i32x16, unbiased_index, _mm512_i32gather_epi64, _mm512_mask_i32gather_epi64, _mm512_i32scatter_epi64, _mm512_mask_i32scatter_epi64 }
+
+int_type! { i8x64, i8, 64, m8x64, __mmask64, _mm512_set1_epi8, _mm512_cmpeq_epi8_mask, _mm512_mask_blend_epi8, _mm512_maskz_loadu_epi8, _mm512_mask_loadu_epi8, _mm512_mask_storeu_epi8 }
+int_type! { i16x32, i16, 32, m16x32, __mmask32, _mm512_set1_epi16, _mm512_cmpeq_epi16_mask, _mm512_mask_blend_epi16, _mm512_maskz_loadu_epi16, _mm512_mask_loadu_epi16, _mm512_mask_storeu_epi16 }
+int_type! { i32x16, i32, 16, m32x16, __mmask16, _mm512_set1_epi32, _mm512_cmpeq_epi32_mask, _mm512_mask_blend_epi32, _mm512_maskz_loadu_epi32, _mm512_mask_loadu_epi32, _mm512_mask_storeu_epi32 }
+int_type! { i64x8, i64, 8, m64x8, __mmask8, _mm512_set1_epi64, _mm512_cmpeq_epi64_mask, _mm512_mask_blend_epi64, _mm512_maskz_loadu_epi64, _mm512_mask_loadu_epi64, _mm512_mask_storeu_epi64 }
+impl_ord! { i8x64, m8x64, _mm512_cmplt_epi8_mask, _mm512_cmple_epi8_mask, _mm512_max_epi8, _mm512_min_epi8 }
+impl_ord! { i16x32, m16x32, _mm512_cmplt_epi16_mask, _mm512_cmple_epi16_mask, _mm512_max_epi16, _mm512_min_epi16 }
+impl_ord! { i32x16, m32x16, _mm512_cmplt_epi32_mask, _mm512_cmple_epi32_mask, _mm512_max_epi32, _mm512_min_epi32 }
+impl_ord! { i64x8, m64x8, _mm512_cmplt_epi64_mask, _mm512_cmple_epi64_mask, _mm512_max_epi64, _mm512_min_epi64 }
+impl_int! { i8x64, i8, _mm512_set1_epi8, _mm512_add_epi8, _mm512_sub_epi8, _mm512_sll_epi8_fallback, _mm512_sra_epi8_fallback, _mm512_srl_epi8_fallback, _mm512_sllv_epi8_fallback, _mm512_srav_epi8_fallback, _mm512_adds_epi8, _mm512_subs_epi8, _mm512_avg_epi8_fallback, _mm512_mulhi_epi8_fallback, _mm512_div_epi8_fallback }
+impl_int! { i16x32, i16, _mm512_set1_epi16, _mm512_add_epi16, _mm512_sub_epi16, _mm512_sll_epi16, _mm512_sra_epi16, _mm512_srl_epi16, _mm512_sllv_epi16, _mm512_srav_epi16, _mm512_adds_epi16, _mm512_subs_epi16, _mm512_avg_epi16_fallback, _mm512_mulhi_epi16, _mm512_div_epi16_fallback }
+impl_int! { i32x16, i32, _mm512_set1_epi32, _mm512_add_epi32, _mm512_sub_epi32, _mm512_sll_epi32, _mm512_sra_epi32, _mm512_srl_epi32, _mm512_sllv_epi32, _mm512_srav_epi32, _mm512_adds_epi32_fallback, _mm512_subs_epi32_fallback, _mm512_avg_epi32_fallback, _mm512_mulhi_epi32_fallback, _mm512_div_epi32_fallback }
+impl_int! { i64x8, i64, _mm512_set1_epi64, _mm512_add_epi64, _mm512_sub_epi64, _mm512_sll_epi64, _mm512_sra_epi64, _mm512_srl_epi64, _mm512_sllv_epi64, _mm512_srav_epi64, _mm512_adds_epi64_fallback, _mm512_subs_epi64_fallback, _mm512_avg_epi64_fallback, _mm512_mulhi_epi64_fallback, _mm512_div_epi64_fallback }
+impl_int_mul! { i8x64, i16x32, i32x16, i64x8 }
+impl_shuffle! { i8x64, identity, identity, reverse_epi8, _mm512_unpacklo_epi8, _mm512_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { i16x32, identity, identity, reverse_epi16, _mm512_unpacklo_epi16, _mm512_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { i32x16, identity, identity, reverse_epi32, _mm512_unpacklo_epi32, _mm512_unpackhi_epi32, deinterleave_epi32, permute }
+impl_shuffle! { i64x8, identity, identity, reverse_epi64, _mm512_unpacklo_epi64, _mm512_unpackhi_epi64, deinterleave_epi64, permute }
+impl_cast_bits! { i8x64, u8x64 }
+impl_cast_bits! { i16x32, u16x32 }
+impl_cast_bits! { i32x16, u32x16 }
+impl_cast_bits! { i64x8, u64x8 }
+impl_int_to_float! { i32x16, f32x16, _mm512_cvtepi32_ps }
+impl_int_to_float! { i64x8, f64x8, _mm512_cvtepi64_pd }
+impl_widen_int! { i8x64, i16x32, _mm512_cvtepi8_epi16, truncate_epi16 }
+impl_widen_int! { i16x32, i32x16, _mm512_cvtepi16_epi32, truncate_epi32 }
+impl_widen_int! { i32x16, i64x8, _mm512_cvtepi32_epi64, truncate_epi64 }
+impl_gather! { i8x64, u8x64, i8x64 }
+impl_gather! { i8x64, [u32x16, i32x16; 4] }
+impl_gather! { i16x32, u16x32, i16x32 }
+impl_gather! { i16x32, [u32x16, i32x16; 2] }
+impl_gather! { i32x16, u32x16, bias_index_epu32, _mm512_i32gather_epi32, _mm512_mask_i32gather_epi32, _mm512_i32scatter_epi32, _mm512_mask_i32scatter_epi32, 4 }
+impl_gather! { i32x16, i32x16, unbiased_index, _mm512_i32gather_epi32, _mm512_mask_i32gather_epi32, _mm512_i32scatter_epi32, _mm512_mask_i32scatter_epi32, 4 }
+impl_gather! { i64x8, u64x8, unbiased_index, _mm512_i64gather_epi64, _mm512_mask_i64gather_epi64, _mm512_i64scatter_epi64, _mm512_mask_i64scatter_epi64, 8 }
+impl_gather! { i64x8, i64x8, unbiased_index, _mm512_i64gather_epi64, _mm512_mask_i64gather_epi64, _mm512_i64scatter_epi64, _mm512_mask_i64scatter_epi64, 8 }
+impl_gather! { [i64x8; 2], u32x16, bias_index_epu32, _mm512_i32gather_epi64, _mm512_mask_i32gather_epi64, _mm512_i32scatter_epi64, _mm512_mask_i32scatter_epi64 }
+impl_gather! { [i64x8; 2], i32x16, unbiased_index, _mm512_i32gather_epi64, _mm512_mask_i32gather_epi64, _mm512_i32scatter_epi64, _mm512_mask_i32scatter_epi64 }
+
+mask_type! { m8x64, m8, 64, __mmask64, _mm512_movm_epi8, _mm512_movepi8_mask, _mm512_maskz_loadu_epi8, _mm512_mask_storeu_epi8 }
+mask_type! { m16x32, m16, 32, __mmask32, _mm512_movm_epi16, _mm512_movepi16_mask, _mm512_maskz_loadu_epi16, _mm512_mask_storeu_epi16 }
+mask_type! { m32x16, m32, 16, __mmask16, _mm512_movm_epi32, _mm512_movepi32_mask, _mm512_maskz_loadu_epi32, _mm512_mask_storeu_epi32 }
+mask_type! { m64x8, m64, 8, __mmask8, _mm512_movm_epi64, _mm512_movepi64_mask, _mm512_maskz_loadu_epi64, _mm512_mask_storeu_epi64 }
+impl_shuffle! { m8x64, _mm512_movm_epi8, _mm512_movepi8_mask, reverse_epi8, _mm512_unpacklo_epi8, _mm512_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { m16x32, _mm512_movm_epi16, _mm512_movepi16_mask, reverse_epi16, _mm512_unpacklo_epi16, _mm512_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { m32x16, _mm512_movm_epi32, _mm512_movepi32_mask, reverse_epi32, _mm512_unpacklo_epi32, _mm512_unpackhi_epi32, deinterleave_epi32, permute }
+impl_shuffle! { m64x8, _mm512_movm_epi64, _mm512_movepi64_mask, reverse_epi64, _mm512_unpacklo_epi64, _mm512_unpackhi_epi64, deinterleave_epi64, permute }
+
+#[test]
+fn k_register_masks() {
+    if !(is_x86_feature_detected!("avx512f")
+        && is_x86_feature_detected!("avx512bw")
+        && is_x86_feature_detected!("avx512dq")
+        && is_x86_feature_detected!("avx512vl"))
+    {
+        return;
+    }
+
+    // One bit per lane, with comparisons and lane writes acting on the bits directly
+    assert_eq!(mem::size_of::<m8x64>(), 8);
+    assert_eq!(mem::size_of::<m32x16>(), 2);
+
+    let mut mask = u32x16::from_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15])
+        .lt(&u32x16::new(4));
+    assert_eq!(mask.to_bitmask(), 0xF);
+    mask.set(0, m32::FALSE);
+    mask.set(15, m32::TRUE);
+    assert_eq!(mask.to_bitmask(), 0x800E);
+    assert_eq!(
+        (mask[0], mask[1], mask[15]),
+        (m32::FALSE, m32::TRUE, m32::TRUE)
+    );
+}
@@ -1,10 +1,12 @@
 #![allow(non_camel_case_types)]
 
+use core::convert::identity;
 use core::fmt::{self, Debug};
 use core::mem;
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
 use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
 use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
 use core::slice;
 
@@ -14,7 +16,10 @@ use std::arch::x86::*;
 use std::arch::x86_64::*;
 
 use crate::mask::*;
-use crate::simd::{Bitwise, Float, Int, LanesEq, LanesOrd, Select, Simd};
+use crate::simd::{
+    check_aligned, Array, Bitwise, Cast, Float, Gather, Int, LanesEq, LanesOrd, Mask, RoundCast,
+    Select, Shuffle, Simd, Widen,
+};
 use crate::{Arch, Task};
 
 pub struct Sse2Impl;
@@ -50,20 +55,42 @@ impl Arch for Sse2Impl {
 
         unsafe { inner(task) }
     }
+
+    #[inline(always)]
+    fn prefetch<T>(ptr: *const T) {
+        #[inline]
+        #[target_feature(enable = "sse2")]
+        unsafe fn inner(ptr: *const i8) {
+            _mm_prefetch::<_MM_HINT_T0>(ptr);
+        }
+
+        unsafe { inner(ptr as *const i8) }
+    }
+
+    #[inline(always)]
+    fn stream_fence() {
+        #[inline]
+        #[target_feature(enable = "sse2")]
+        unsafe fn inner() {
+            _mm_sfence();
+        }
+
+        unsafe { inner() }
+    }
 }
 
 macro_rules! float_type {
     (
         $feature:literal,
-        $float:ident, $inner:ident, $elem:ident, $lanes:literal, $mask:ident,
-        $set:ident, $load:ident, $store:ident, $cast_to_int:ident, $cast_from_int:ident,
+        $float:ident, $inner:ident, $elem:ident, $lanes:literal, $mask:ident, $bits:ident,
+        $set:ident, $load:ident, $load_aligned:ident, $store:ident, $stream:ident, $cast_to_int:ident, $cast_from_int:ident,
         $cmpeq:ident, $cmpneq:ident, $cmplt:ident, $cmple:ident, $cmpgt:ident, $cmpge:ident,
-        $min:ident, $max:ident, $and:ident, $or:ident, $andnot:ident, $xor:ident,
-        $add:ident, $sub:ident, $mul:ident, $div:ident,
+        $min:ident, $max:ident, $and:ident, $or:ident, $andnot:ident, $xor:ident, $blend:ident,
+        $add:ident, $sub:ident, $mul:ident, $div:ident, $sqrt:ident, $rcp:ident, $rsqrt:ident, $round:ident,
     ) => {
         #[derive(Copy, Clone)]
         #[repr(transparent)]
-        pub struct $float($inner);
+        pub struct $float(pub(super) $inner);
 
         impl Simd for $float {
             type Elem = $elem;
@@ -83,17 +110,8 @@ macro_rules! float_type {
             }
 
             #[inline]
-            fn as_slice(&self) -> &[Self::Elem] {
-                unsafe {
-                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
-                }
-            }
-
-            #[inline]
-            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
-                unsafe {
-                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
-                }
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
             }
 
             #[inline(always)]
@@ -120,6 +138,46 @@ macro_rules! float_type {
                 unsafe { inner(self, slice) }
             }
 
+            #[inline(always)]
+            fn from_slice_aligned(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(slice: &[$elem]) -> $float {
+                    check_aligned::<$float>(slice);
+                    $float($load_aligned(slice.as_ptr()))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            unsafe fn store_stream(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: &$float, slice: &mut [$elem]) {
+                    check_aligned::<$float>(slice);
+                    $stream(slice.as_mut_ptr(), vec.0);
+                }
+
+                inner(self, slice);
+            }
+        }
+
+        impl Array for $float {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
+                }
+            }
+
             #[inline]
             fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
                 unsafe { slice.align_to::<Self>() }
@@ -263,15 +321,213 @@ macro_rules! float_type {
                 #[inline]
                 #[target_feature(enable = $feature)]
                 unsafe fn inner(mask: $mask, if_true: $float, if_false: $float) -> $float {
-                    let mask = $cast_from_int(mask.0);
-                    $float($or($andnot(mask, if_false.0), $and(mask, if_true.0)))
+                    $float($blend(if_false.0, if_true.0, $cast_from_int(mask.0)))
                 }
 
                 unsafe { inner(self, if_true, if_false) }
             }
         }
 
-        impl Float for $float {}
+        impl $float {
+            #[inline(always)]
+            fn fold_lanes(self, f: impl Fn($float, $float) -> $float) -> $elem {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn shift<const BYTES: i32>(vec: $float) -> $float {
+                    $float($cast_from_int(_mm_srli_si128::<BYTES>($cast_to_int(vec.0))))
+                }
+
+                // Repeatedly combine each lane with the one half the remaining width above it,
+                // leaving the result in the lowest lane
+                let mut vec = self;
+                unsafe {
+                    vec = f(vec, shift::<8>(vec));
+                    if mem::size_of::<$elem>() <= 4 {
+                        vec = f(vec, shift::<4>(vec));
+                    }
+                }
+                vec[0]
+            }
+        }
+
+        impl Float for $float {
+            type Bits = $bits;
+
+            #[inline(always)]
+            fn to_bits(self) -> Self::Bits {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float) -> $bits {
+                    $bits($cast_to_int(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn from_bits(bits: Self::Bits) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(bits: $bits) -> $float {
+                    $float($cast_from_int(bits.0))
+                }
+
+                unsafe { inner(bits) }
+            }
+
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(x: $float, a: $float, b: $float) -> $float {
+                    $float($add($mul(x.0, a.0), b.0))
+                }
+
+                unsafe { inner(self, a, b) }
+            }
+
+            #[inline(always)]
+            fn sqrt(self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($sqrt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn recip(self) -> Self {
+                Self::new(1.0) / self
+            }
+
+            #[inline(always)]
+            fn recip_approx(self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($rcp(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn rsqrt(self) -> Self {
+                Self::new(1.0) / self.sqrt()
+            }
+
+            #[inline(always)]
+            fn rsqrt_approx(self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($rsqrt(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn abs(self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($andnot($set(-0.0), vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float, sign: $float) -> $float {
+                    let mask = $set(-0.0);
+                    $float($or($andnot(mask, vec.0), $and(mask, sign.0)))
+                }
+
+                unsafe { inner(self, sign) }
+            }
+
+            #[inline(always)]
+            fn signum(self) -> Self {
+                let signum = Self::new(1.0).copysign(self);
+                self.eq(&self).select(signum, Self::new($elem::NAN))
+            }
+
+            #[inline(always)]
+            fn floor(self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round::<{ _MM_FROUND_TO_NEG_INF | _MM_FROUND_NO_EXC }>(
+                        vec.0,
+                    ))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn ceil(self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round::<{ _MM_FROUND_TO_POS_INF | _MM_FROUND_NO_EXC }>(
+                        vec.0,
+                    ))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn round(self) -> Self {
+                // Adding the largest value below one half before truncating rounds ties away from
+                // zero without rounding anything smaller than a tie upwards
+                let half = Self::new(0.5 - $elem::EPSILON / 4.0);
+                (self + half.copysign(self)).trunc()
+            }
+
+            #[inline(always)]
+            fn trunc(self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $float) -> $float {
+                    $float($round::<{ _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC }>(vec.0))
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn fract(self) -> Self {
+                self - self.trunc()
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a + b)
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a * b)
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.min(b))
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.max(b))
+            }
+        }
 
         impl Add for $float {
             type Output = Self;
@@ -379,10 +635,10 @@ macro_rules! float_type {
 }
 
 macro_rules! int_type {
-    ($feature:literal, $int:ident, $elem:ident, $lanes:literal, $mask:ident, $set:ident) => {
+    ($feature:literal, $int:ident, $elem:ident, $lanes:literal, $mask:ident, $set:ident, $blend:ident) => {
         #[derive(Copy, Clone)]
         #[repr(transparent)]
-        pub struct $int(__m128i);
+        pub struct $int(pub(super) __m128i);
 
         impl Simd for $int {
             type Elem = $elem;
@@ -394,6 +650,8 @@ macro_rules! int_type {
             fn new(elem: Self::Elem) -> Self {
                 #[inline]
                 #[target_feature(enable = $feature)]
+                #[allow(clippy::missing_transmute_annotations, clippy::useless_transmute)]
+                #[allow(unnecessary_transmutes)]
                 unsafe fn inner(elem: $elem) -> $int {
                     $int($set(mem::transmute(elem)))
                 }
@@ -402,17 +660,8 @@ macro_rules! int_type {
             }
 
             #[inline]
-            fn as_slice(&self) -> &[Self::Elem] {
-                unsafe {
-                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
-                }
-            }
-
-            #[inline]
-            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
-                unsafe {
-                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
-                }
+            fn set(&mut self, index: usize, elem: Self::Elem) {
+                self[index] = elem;
             }
 
             #[inline(always)]
@@ -441,6 +690,46 @@ macro_rules! int_type {
                 }
             }
 
+            #[inline(always)]
+            fn from_slice_aligned(slice: &[Self::Elem]) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(slice: &[$elem]) -> $int {
+                    check_aligned::<$int>(slice);
+                    $int(_mm_load_si128(slice.as_ptr() as *const __m128i))
+                }
+
+                unsafe { inner(slice) }
+            }
+
+            #[inline(always)]
+            unsafe fn store_stream(&self, slice: &mut [Self::Elem]) {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: &$int, slice: &mut [$elem]) {
+                    check_aligned::<$int>(slice);
+                    _mm_stream_si128(slice.as_mut_ptr() as *mut __m128i, vec.0);
+                }
+
+                inner(self, slice);
+            }
+        }
+
+        impl Array for $int {
+            #[inline]
+            fn as_slice(&self) -> &[Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts(self as *const Self as *const Self::Elem, Self::LANES)
+                }
+            }
+
+            #[inline]
+            fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+                unsafe {
+                    slice::from_raw_parts_mut(self as *mut Self as *mut Self::Elem, Self::LANES)
+                }
+            }
+
             #[inline]
             fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]) {
                 unsafe { slice.align_to::<Self>() }
@@ -484,7 +773,50 @@ macro_rules! int_type {
             }
         }
 
-        impl Bitwise for $int {}
+        impl $int {
+            #[inline(always)]
+            fn fold_lanes(self, f: impl Fn($int, $int) -> $int) -> $elem {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn shift<const BYTES: i32>(vec: $int) -> $int {
+                    $int(_mm_srli_si128::<BYTES>(vec.0))
+                }
+
+                // Repeatedly combine each lane with the one half the remaining width above it,
+                // leaving the result in the lowest lane
+                let mut vec = self;
+                unsafe {
+                    vec = f(vec, shift::<8>(vec));
+                    if mem::size_of::<$elem>() <= 4 {
+                        vec = f(vec, shift::<4>(vec));
+                    }
+                    if mem::size_of::<$elem>() <= 2 {
+                        vec = f(vec, shift::<2>(vec));
+                    }
+                    if mem::size_of::<$elem>() <= 1 {
+                        vec = f(vec, shift::<1>(vec));
+                    }
+                }
+                vec[0]
+            }
+        }
+
+        impl Bitwise for $int {
+            #[inline(always)]
+            fn reduce_and(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a & b)
+            }
+
+            #[inline(always)]
+            fn reduce_or(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a | b)
+            }
+
+            #[inline(always)]
+            fn reduce_xor(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a ^ b)
+            }
+        }
 
         impl BitAnd for $int {
             type Output = Self;
@@ -574,10 +906,7 @@ macro_rules! int_type {
                 #[inline]
                 #[target_feature(enable = $feature)]
                 unsafe fn inner(mask: $mask, if_true: $int, if_false: $int) -> $int {
-                    $int(_mm_or_si128(
-                        _mm_andnot_si128(mask.0, if_false.0),
-                        _mm_and_si128(mask.0, if_true.0),
-                    ))
+                    $int($blend(if_false.0, if_true.0, mask.0))
                 }
 
                 unsafe { inner(self, if_true, if_false) }
@@ -586,16 +915,129 @@ macro_rules! int_type {
     };
 }
 
-macro_rules! impl_ord_mask {
-    ($feature:literal, $mask:ident) => {
-        impl LanesEq for $mask {
+macro_rules! impl_ord_uint {
+    ($feature:literal, $uint:ident, $mask:ident, $cmpeq:ident, $max:ident, $min:ident) => {
+        impl LanesEq for $uint {
             type Output = $mask;
 
             #[inline(always)]
             fn eq(&self, other: &Self) -> Self::Output {
                 #[inline]
                 #[target_feature(enable = $feature)]
-                unsafe fn inner(lhs: &$mask, rhs: &$mask) -> $mask {
+                unsafe fn inner(lhs: &$uint, rhs: &$uint) -> $mask {
+                    $mask($cmpeq(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        impl LanesOrd for $uint {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                !other.le(self)
+            }
+
+            #[inline(always)]
+            fn le(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: &$uint, rhs: &$uint) -> $mask {
+                    $mask($cmpeq(lhs.0, $min(lhs.0, rhs.0)))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $uint, rhs: $uint) -> $uint {
+                    $uint($max(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $uint, rhs: $uint) -> $uint {
+                    $uint($min(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_ord_int {
+    ($feature:literal, $int:ident, $mask:ident, $cmpeq:ident, $cmpgt:ident, $max:ident, $min:ident) => {
+        impl LanesEq for $int {
+            type Output = $mask;
+
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: &$int, rhs: &$int) -> $mask {
+                    $mask($cmpeq(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+
+        impl LanesOrd for $int {
+            #[inline(always)]
+            fn lt(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: &$int, rhs: &$int) -> $mask {
+                    $mask($cmpgt(rhs.0, lhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($max(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($min(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_ord_mask {
+    ($feature:literal, $mask:ident) => {
+        impl LanesEq for $mask {
+            type Output = $mask;
+
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> Self::Output {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: &$mask, rhs: &$mask) -> $mask {
                     $mask(_mm_cmpeq_epi8(lhs.0, rhs.0))
                 }
 
@@ -662,9 +1104,147 @@ macro_rules! impl_ord_mask {
     };
 }
 
+macro_rules! impl_mask {
+    ($feature:literal, $mask:ident, $movemask:ident, $expand:ident) => {
+        impl Mask for $mask {
+            #[inline(always)]
+            fn to_bitmask(self) -> u64 {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(mask: $mask) -> u64 {
+                    $movemask(mask.0)
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn from_bitmask(bits: u64) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(bits: u64) -> $mask {
+                    $mask($expand(bits))
+                }
+
+                unsafe { inner(bits) }
+            }
+        }
+    };
+}
+
 macro_rules! impl_int {
-    ($feature:literal, $int:ident, $elem:ident, $set:ident, $add:ident, $sub:ident, $shl:ident, $shr:ident) => {
-        impl Int for $int {}
+    ($feature:literal, $int:ident, $elem:ident, $set:ident, $add:ident, $sub:ident, $shl:ident, $shr:ident, $srl:ident, $shlv:ident, $shrv:ident, $adds:ident, $subs:ident, $avg:ident, $mulhi:ident, $div:ident) => {
+        impl Int for $int {
+            #[inline(always)]
+            fn saturating_add(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($adds(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn saturating_sub(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($subs(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn avg(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($avg(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn mulhi(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($mulhi(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+
+            #[inline(always)]
+            fn shl_by(self, counts: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, counts: $int) -> $int {
+                    let counts = _mm_and_si128(counts.0, $set(($elem::BITS - 1) as _));
+                    $int($shlv(lhs.0, counts))
+                }
+
+                unsafe { inner(self, counts) }
+            }
+
+            #[inline(always)]
+            fn shr_by(self, counts: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, counts: $int) -> $int {
+                    let counts = _mm_and_si128(counts.0, $set(($elem::BITS - 1) as _));
+                    $int($shrv(lhs.0, counts))
+                }
+
+                unsafe { inner(self, counts) }
+            }
+
+            #[inline(always)]
+            fn rotate_left(self, n: usize) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $int, n: usize) -> $int {
+                    let bits = $elem::BITS as usize;
+                    let left = n & (bits - 1);
+                    let right = (bits - left) & (bits - 1);
+                    let high = $shl(vec.0, _mm_cvtsi64_si128(left as i64));
+                    let low = $srl(vec.0, _mm_cvtsi64_si128(right as i64));
+                    $int(_mm_or_si128(high, low))
+                }
+
+                unsafe { inner(self, n) }
+            }
+
+            #[inline(always)]
+            fn rotate_right(self, n: usize) -> Self {
+                let bits = $elem::BITS as usize;
+                self.rotate_left(bits - (n & (bits - 1)))
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a + b)
+            }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a * b)
+            }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.min(b))
+            }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Elem {
+                self.fold_lanes(|a, b| a.max(b))
+            }
+        }
 
         impl Add for $int {
             type Output = Self;
@@ -717,6 +1297,44 @@ macro_rules! impl_int {
             }
         }
 
+        impl Div for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $int, rhs: $int) -> $int {
+                    $int($div(lhs.0, rhs.0))
+                }
+
+                unsafe { inner(self, rhs) }
+            }
+        }
+
+        impl DivAssign for $int {
+            #[inline(always)]
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl Rem for $int {
+            type Output = Self;
+
+            #[inline(always)]
+            fn rem(self, rhs: Self) -> Self {
+                self - (self / rhs) * rhs
+            }
+        }
+
+        impl RemAssign for $int {
+            #[inline(always)]
+            fn rem_assign(&mut self, rhs: Self) {
+                *self = *self % rhs;
+            }
+        }
+
         impl Neg for $int {
             type Output = Self;
 
@@ -781,14 +1399,14 @@ macro_rules! impl_int {
 }
 
 macro_rules! impl_int_mul {
-    ($int8:ident, $int16:ident, $int32:ident, $int64:ident) => {
+    ($feature:literal, $int8:ident, $int16:ident, $int32:ident, $int64:ident, $mullo32:ident) => {
         impl Mul for $int8 {
             type Output = Self;
 
             #[inline(always)]
             fn mul(self, rhs: Self) -> Self {
                 #[inline]
-                #[target_feature(enable = "sse2")]
+                #[target_feature(enable = $feature)]
                 unsafe fn inner(lhs: $int8, rhs: $int8) -> $int8 {
                     let lhs_odd = _mm_srli_epi16(lhs.0, 8);
                     let rhs_odd = _mm_srli_epi16(rhs.0, 8);
@@ -808,7 +1426,7 @@ macro_rules! impl_int_mul {
             #[inline(always)]
             fn mul(self, rhs: Self) -> Self {
                 #[inline]
-                #[target_feature(enable = "sse2")]
+                #[target_feature(enable = $feature)]
                 unsafe fn inner(lhs: $int16, rhs: $int16) -> $int16 {
                     $int16(_mm_mullo_epi16(lhs.0, rhs.0))
                 }
@@ -823,14 +1441,9 @@ macro_rules! impl_int_mul {
             #[inline(always)]
             fn mul(self, rhs: Self) -> Self {
                 #[inline]
-                #[target_feature(enable = "sse2")]
+                #[target_feature(enable = $feature)]
                 unsafe fn inner(lhs: $int32, rhs: $int32) -> $int32 {
-                    let lhs_odd = _mm_srli_epi64(lhs.0, 32);
-                    let rhs_odd = _mm_srli_epi64(rhs.0, 32);
-                    let even = _mm_mul_epu32(lhs.0, rhs.0);
-                    let odd = _mm_slli_epi64(_mm_mul_epu32(lhs_odd, rhs_odd), 32);
-                    let mask = _mm_set1_epi64x(0xFFFFFFFF);
-                    $int32(_mm_or_si128(_mm_and_si128(mask, even), odd))
+                    $int32($mullo32(lhs.0, rhs.0))
                 }
 
                 unsafe { inner(self, rhs) }
@@ -843,7 +1456,7 @@ macro_rules! impl_int_mul {
             #[inline(always)]
             fn mul(self, rhs: Self) -> Self {
                 #[inline]
-                #[target_feature(enable = "sse2")]
+                #[target_feature(enable = $feature)]
                 unsafe fn inner(lhs: $int64, rhs: $int64) -> $int64 {
                     let low_high = _mm_mul_epu32(lhs.0, _mm_srli_epi64(rhs.0, 32));
                     let high_low = _mm_mul_epu32(rhs.0, _mm_srli_epi64(lhs.0, 32));
@@ -858,39 +1471,247 @@ macro_rules! impl_int_mul {
     };
 }
 
-#[inline]
-#[target_feature(enable = "sse2")]
-pub unsafe fn _mm_sll_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
-    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
-    let shift = _mm_cvtsi128_si64(count);
-    let mask = _mm_set1_epi8((0xFFu8 << shift) as i8);
-    _mm_and_si128(mask, _mm_sll_epi16(a, count))
-}
-
-#[inline]
-#[target_feature(enable = "sse2")]
-pub unsafe fn _mm_srl_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
-    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
-    let shift = _mm_cvtsi128_si64(count);
-    let mask = _mm_set1_epi8((0xFFu8 >> shift) as i8);
-    _mm_and_si128(mask, _mm_srl_epi16(a, count))
-}
+macro_rules! impl_shuffle {
+    (
+        $feature:literal, $vec:ident, $to_int:ident, $from_int:ident,
+        $reverse:ident, $unpacklo:ident, $unpackhi:ident, $deinterleave:ident
+    ) => {
+        impl Shuffle for $vec {
+            #[inline(always)]
+            fn reverse(self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $vec) -> $vec {
+                    $vec($from_int($reverse($to_int(vec.0))))
+                }
 
-#[inline]
-#[target_feature(enable = "sse2")]
-pub unsafe fn _mm_sra_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
-    // Perform a 16-bit logical shift and then mask out garbage from adjacent lanes
-    let shift = _mm_cvtsi128_si64(count);
-    let mask = _mm_set1_epi8((0xFFu8 >> shift) as i8);
-    let shifted = _mm_and_si128(mask, _mm_srl_epi16(a, count));
-    // Manual sign extension
-    let sign = _mm_and_si128(a, _mm_set1_epi8(1 << 7));
-    let extended = _mm_sub_epi8(_mm_setzero_si128(), _mm_srl_epi16(sign, count));
-    _mm_or_si128(extended, shifted)
-}
+                unsafe { inner(self) }
+            }
 
-#[inline]
-#[target_feature(enable = "sse2")]
+            #[inline(always)]
+            fn interleave_lo(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> $vec {
+                    $vec($from_int($unpacklo($to_int(lhs.0), $to_int(rhs.0))))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn interleave_hi(self, other: Self) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> $vec {
+                    $vec($from_int($unpackhi($to_int(lhs.0), $to_int(rhs.0))))
+                }
+
+                unsafe { inner(self, other) }
+            }
+
+            #[inline(always)]
+            fn deinterleave(self, other: Self) -> (Self, Self) {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(lhs: $vec, rhs: $vec) -> ($vec, $vec) {
+                    let (even, odd) = $deinterleave($to_int(lhs.0), $to_int(rhs.0));
+                    ($vec($from_int(even)), $vec($from_int(odd)))
+                }
+
+                unsafe { inner(self, other) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_gather {
+    ($vec:ident, $($index:ident),*) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = $index;
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+    // Each vector of `$vec` is indexed by `$n` index vectors
+    ($vec:ident, [$($index:ident),*; $n:literal]) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = [$index; $n];
+                type Values = Self;
+                type Masks = <Self as Simd>::Mask;
+            }
+        )*
+    };
+    // Each index vector covers `$n` vectors of `$vec`
+    ([$vec:ident; $n:literal], $($index:ident),*) => {
+        $(
+            impl Gather<$index> for $vec {
+                type Index = $index;
+                type Values = [Self; $n];
+                type Masks = [<Self as Simd>::Mask; $n];
+            }
+        )*
+    };
+}
+
+macro_rules! impl_cast_bits {
+    ($from:ident, $to:ident) => {
+        impl Cast<$to> for $from {
+            #[inline(always)]
+            fn cast(self) -> $to {
+                $to(self.0)
+            }
+        }
+    };
+}
+
+macro_rules! impl_cast_lanes {
+    ($from:ident, $to:ident, $elem:ident) => {
+        // There are no packed conversions between 64-bit floats and integers before AVX-512, so
+        // convert each lane individually (this lowers to one cvt(t)sd2si per lane).
+        impl Cast<$to> for $from {
+            #[inline(always)]
+            fn cast(self) -> $to {
+                let mut res = $to::default();
+                for (out, x) in res.as_mut_slice().iter_mut().zip(self.as_slice()) {
+                    *out = *x as $elem;
+                }
+                res
+            }
+        }
+
+        impl RoundCast<$to> for $from {
+            #[inline(always)]
+            fn round_cast(self) -> $to {
+                let mut res = $to::default();
+                for (out, x) in res.as_mut_slice().iter_mut().zip(self.as_slice()) {
+                    *out = x.round_ties_even() as $elem;
+                }
+                res
+            }
+        }
+    };
+}
+
+macro_rules! impl_f32_to_int {
+    ($feature:literal, $trait:ident, $method:ident, $cvt:ident) => {
+        impl $trait<i32x4> for f32x4 {
+            #[inline(always)]
+            fn $method(self) -> i32x4 {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: f32x4) -> i32x4 {
+                    // The conversion returns i32::MIN for NaN and out-of-range lanes, so saturate
+                    // positive overflow to i32::MAX and zero out NaNs to match `as`
+                    let res = $cvt(vec.0);
+                    let overflow = _mm_castps_si128(_mm_cmpge_ps(vec.0, _mm_set1_ps(2147483648.0)));
+                    let not_nan = _mm_castps_si128(_mm_cmpord_ps(vec.0, vec.0));
+                    i32x4(_mm_and_si128(_mm_xor_si128(res, overflow), not_nan))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+
+        impl $trait<u32x4> for f32x4 {
+            #[inline(always)]
+            fn $method(self) -> u32x4 {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: f32x4) -> u32x4 {
+                    // maxps returns its second operand for NaN, so this also zeroes out NaNs
+                    let vec = _mm_max_ps(vec.0, _mm_setzero_ps());
+                    // Only a signed conversion is available, so bring lanes >= 2^31 into range
+                    // first and then restore the high bit
+                    let bias = _mm_set1_ps(2147483648.0);
+                    let high = _mm_cmpge_ps(vec, bias);
+                    let res = $cvt(_mm_sub_ps(vec, _mm_and_ps(high, bias)));
+                    let res = _mm_xor_si128(res, _mm_slli_epi32(_mm_castps_si128(high), 31));
+                    let overflow = _mm_cmpge_ps(vec, _mm_set1_ps(4294967296.0));
+                    u32x4(_mm_or_si128(res, _mm_castps_si128(overflow)))
+                }
+
+                unsafe { inner(self) }
+            }
+        }
+    };
+}
+
+macro_rules! impl_widen_int {
+    (
+        $feature:literal,
+        $narrow:ident, $wide:ident,
+        $unpacklo:ident, $unpackhi:ident, $extend:ident, $truncate:ident
+    ) => {
+        impl Widen for $narrow {
+            type Wide = $wide;
+            type Parts = [$wide; 2];
+
+            #[inline(always)]
+            fn widen(self) -> Self::Parts {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(vec: $narrow) -> [$wide; 2] {
+                    let high_bits = $extend(vec.0);
+                    [
+                        $wide($unpacklo(vec.0, high_bits)),
+                        $wide($unpackhi(vec.0, high_bits)),
+                    ]
+                }
+
+                unsafe { inner(self) }
+            }
+
+            #[inline(always)]
+            fn narrow(parts: Self::Parts) -> Self {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn inner(parts: [$wide; 2]) -> $narrow {
+                    $narrow($truncate(parts[0].0, parts[1].0))
+                }
+
+                unsafe { inner(parts) }
+            }
+        }
+    };
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_sll_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm_set1_epi8((0xFFu8 << shift) as i8);
+    _mm_and_si128(mask, _mm_sll_epi16(a, count))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srl_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Perform a 16-bit shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm_set1_epi8((0xFFu8 >> shift) as i8);
+    _mm_and_si128(mask, _mm_srl_epi16(a, count))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_sra_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Perform a 16-bit logical shift and then mask out garbage from adjacent lanes
+    let shift = _mm_cvtsi128_si64(count);
+    let mask = _mm_set1_epi8((0xFFu8 >> shift) as i8);
+    let shifted = _mm_and_si128(mask, _mm_srl_epi16(a, count));
+    // Manual sign extension
+    let sign = _mm_and_si128(a, _mm_set1_epi8(1 << 7));
+    let extended = _mm_sub_epi8(_mm_setzero_si128(), _mm_srl_epi16(sign, count));
+    _mm_or_si128(extended, shifted)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
 pub unsafe fn _mm_sra_epi64_fallback(a: __m128i, count: __m128i) -> __m128i {
     // Perform a logical shift followed by manual sign extension
     let sign = _mm_and_si128(a, _mm_set1_epi64x(1 << 63));
@@ -898,366 +1719,1157 @@ pub unsafe fn _mm_sra_epi64_fallback(a: __m128i, count: __m128i) -> __m128i {
     _mm_or_si128(extended, _mm_srl_epi64(a, count))
 }
 
-float_type! {
-    "sse2",
-    f32x4, __m128, f32, 4, m32x4,
-    _mm_set1_ps, _mm_loadu_ps, _mm_storeu_ps, _mm_castps_si128, _mm_castsi128_ps,
-    _mm_cmpeq_ps, _mm_cmpneq_ps, _mm_cmplt_ps, _mm_cmple_ps, _mm_cmpgt_ps, _mm_cmpge_ps,
-    _mm_min_ps, _mm_max_ps, _mm_and_ps, _mm_or_ps, _mm_andnot_ps, _mm_xor_ps,
-    _mm_add_ps, _mm_sub_ps, _mm_mul_ps, _mm_div_ps,
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_sllv_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two. Moving the count into place with a 16-bit shift leaves garbage
+    // in the low bits of each byte, but those bits are never tested.
+    let mut count = _mm_slli_epi16(count, 5);
+    let mut res = a;
+    for shift in [4, 2, 1] {
+        let mask = _mm_cmpgt_epi8(_mm_setzero_si128(), count);
+        let shifted = _mm_sll_epi8_fallback(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi8(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srlv_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two. Moving the count into place with a 16-bit shift leaves garbage
+    // in the low bits of each byte, but those bits are never tested.
+    let mut count = _mm_slli_epi16(count, 5);
+    let mut res = a;
+    for shift in [4, 2, 1] {
+        let mask = _mm_cmpgt_epi8(_mm_setzero_si128(), count);
+        let shifted = _mm_srl_epi8_fallback(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi8(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srav_epi8_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two. Moving the count into place with a 16-bit shift leaves garbage
+    // in the low bits of each byte, but those bits are never tested.
+    let mut count = _mm_slli_epi16(count, 5);
+    let mut res = a;
+    for shift in [4, 2, 1] {
+        let mask = _mm_cmpgt_epi8(_mm_setzero_si128(), count);
+        let shifted = _mm_sra_epi8_fallback(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi8(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_sllv_epi16_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm_slli_epi16(count, 12);
+    let mut res = a;
+    for shift in [8, 4, 2, 1] {
+        let mask = _mm_srai_epi16(count, 15);
+        let shifted = _mm_sll_epi16(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi16(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srlv_epi16_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm_slli_epi16(count, 12);
+    let mut res = a;
+    for shift in [8, 4, 2, 1] {
+        let mask = _mm_srai_epi16(count, 15);
+        let shifted = _mm_srl_epi16(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi16(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srav_epi16_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm_slli_epi16(count, 12);
+    let mut res = a;
+    for shift in [8, 4, 2, 1] {
+        let mask = _mm_srai_epi16(count, 15);
+        let shifted = _mm_sra_epi16(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi16(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_sllv_epi32_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm_slli_epi32(count, 27);
+    let mut res = a;
+    for shift in [16, 8, 4, 2, 1] {
+        let mask = _mm_srai_epi32(count, 31);
+        let shifted = _mm_sll_epi32(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi32(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srlv_epi32_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm_slli_epi32(count, 27);
+    let mut res = a;
+    for shift in [16, 8, 4, 2, 1] {
+        let mask = _mm_srai_epi32(count, 31);
+        let shifted = _mm_srl_epi32(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi32(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srav_epi32_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Test each bit of the count in turn, starting from the top, and conditionally shift by the
+    // corresponding power of two
+    let mut count = _mm_slli_epi32(count, 27);
+    let mut res = a;
+    for shift in [16, 8, 4, 2, 1] {
+        let mask = _mm_srai_epi32(count, 31);
+        let shifted = _mm_sra_epi32(res, _mm_cvtsi32_si128(shift));
+        res = _mm_or_si128(_mm_and_si128(mask, shifted), _mm_andnot_si128(mask, res));
+        count = _mm_add_epi32(count, count);
+    }
+    res
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_sllv_epi64_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // Shift by each lane's count separately and then combine
+    let low = _mm_sll_epi64(a, count);
+    let high = _mm_sll_epi64(a, _mm_unpackhi_epi64(count, count));
+    _mm_castpd_si128(_mm_move_sd(_mm_castsi128_pd(high), _mm_castsi128_pd(low)))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srlv_epi64_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // See _mm_sllv_epi64_fallback
+    let low = _mm_srl_epi64(a, count);
+    let high = _mm_srl_epi64(a, _mm_unpackhi_epi64(count, count));
+    _mm_castpd_si128(_mm_move_sd(_mm_castsi128_pd(high), _mm_castsi128_pd(low)))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_srav_epi64_fallback(a: __m128i, count: __m128i) -> __m128i {
+    // See _mm_sllv_epi64_fallback
+    let low = _mm_sra_epi64_fallback(a, count);
+    let high = _mm_sra_epi64_fallback(a, _mm_unpackhi_epi64(count, count));
+    _mm_castpd_si128(_mm_move_sd(_mm_castsi128_pd(high), _mm_castsi128_pd(low)))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn broadcast_sign_epi64(a: __m128i) -> __m128i {
+    _mm_shuffle_epi32(_mm_srai_epi32(a, 31), 0xF5)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_adds_epu32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Saturate wherever the addition carries out of the top bit
+    let sum = _mm_add_epi32(a, b);
+    let carry = _mm_or_si128(
+        _mm_and_si128(a, b),
+        _mm_andnot_si128(sum, _mm_or_si128(a, b)),
+    );
+    _mm_or_si128(sum, _mm_srai_epi32(carry, 31))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_subs_epu32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Saturate wherever the subtraction borrows from beyond the top bit
+    let diff = _mm_sub_epi32(a, b);
+    let borrow = _mm_or_si128(
+        _mm_andnot_si128(a, b),
+        _mm_andnot_si128(_mm_xor_si128(a, b), diff),
+    );
+    _mm_andnot_si128(_mm_srai_epi32(borrow, 31), diff)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_adds_epi32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Overflow occurs when the inputs have the same sign and the result has the opposite sign,
+    // in which case saturate toward the sign of the inputs
+    let sum = _mm_add_epi32(a, b);
+    let overflow = _mm_srai_epi32(
+        _mm_and_si128(_mm_xor_si128(sum, a), _mm_xor_si128(sum, b)),
+        31,
+    );
+    let saturated = _mm_xor_si128(_mm_srai_epi32(a, 31), _mm_set1_epi32(i32::MAX));
+    _mm_or_si128(
+        _mm_and_si128(overflow, saturated),
+        _mm_andnot_si128(overflow, sum),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_subs_epi32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Overflow occurs when the inputs have opposite signs and the result has the sign of the
+    // subtrahend, in which case saturate toward the sign of the minuend
+    let diff = _mm_sub_epi32(a, b);
+    let overflow = _mm_srai_epi32(
+        _mm_and_si128(_mm_xor_si128(a, b), _mm_xor_si128(a, diff)),
+        31,
+    );
+    let saturated = _mm_xor_si128(_mm_srai_epi32(a, 31), _mm_set1_epi32(i32::MAX));
+    _mm_or_si128(
+        _mm_and_si128(overflow, saturated),
+        _mm_andnot_si128(overflow, diff),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_adds_epu64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Saturate wherever the addition carries out of the top bit
+    let sum = _mm_add_epi64(a, b);
+    let carry = _mm_or_si128(
+        _mm_and_si128(a, b),
+        _mm_andnot_si128(sum, _mm_or_si128(a, b)),
+    );
+    _mm_or_si128(sum, broadcast_sign_epi64(carry))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_subs_epu64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Saturate wherever the subtraction borrows from beyond the top bit
+    let diff = _mm_sub_epi64(a, b);
+    let borrow = _mm_or_si128(
+        _mm_andnot_si128(a, b),
+        _mm_andnot_si128(_mm_xor_si128(a, b), diff),
+    );
+    _mm_andnot_si128(broadcast_sign_epi64(borrow), diff)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_adds_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Overflow occurs when the inputs have the same sign and the result has the opposite sign,
+    // in which case saturate toward the sign of the inputs
+    let sum = _mm_add_epi64(a, b);
+    let overflow =
+        broadcast_sign_epi64(_mm_and_si128(_mm_xor_si128(sum, a), _mm_xor_si128(sum, b)));
+    let saturated = _mm_xor_si128(broadcast_sign_epi64(a), _mm_set1_epi64x(i64::MAX));
+    _mm_or_si128(
+        _mm_and_si128(overflow, saturated),
+        _mm_andnot_si128(overflow, sum),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_subs_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Overflow occurs when the inputs have opposite signs and the result has the sign of the
+    // subtrahend, in which case saturate toward the sign of the minuend
+    let diff = _mm_sub_epi64(a, b);
+    let overflow = broadcast_sign_epi64(_mm_and_si128(_mm_xor_si128(a, b), _mm_xor_si128(a, diff)));
+    let saturated = _mm_xor_si128(broadcast_sign_epi64(a), _mm_set1_epi64x(i64::MAX));
+    _mm_or_si128(
+        _mm_and_si128(overflow, saturated),
+        _mm_andnot_si128(overflow, diff),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_avg_epi8_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Bias into the unsigned range, average, and then remove the bias
+    let bias = _mm_set1_epi8(i8::MIN);
+    _mm_xor_si128(
+        _mm_avg_epu8(_mm_xor_si128(a, bias), _mm_xor_si128(b, bias)),
+        bias,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_avg_epi16_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Bias into the unsigned range, average, and then remove the bias
+    let bias = _mm_set1_epi16(i16::MIN);
+    _mm_xor_si128(
+        _mm_avg_epu16(_mm_xor_si128(a, bias), _mm_xor_si128(b, bias)),
+        bias,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_avg_epu32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // (a | b) - ((a ^ b) >> 1) rounds the average up without overflowing
+    _mm_sub_epi32(_mm_or_si128(a, b), _mm_srli_epi32(_mm_xor_si128(a, b), 1))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_avg_epi32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // See _mm_avg_epu32_fallback
+    _mm_sub_epi32(_mm_or_si128(a, b), _mm_srai_epi32(_mm_xor_si128(a, b), 1))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_avg_epu64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // See _mm_avg_epu32_fallback
+    _mm_sub_epi64(_mm_or_si128(a, b), _mm_srli_epi64(_mm_xor_si128(a, b), 1))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_avg_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // See _mm_avg_epu32_fallback
+    _mm_sub_epi64(
+        _mm_or_si128(a, b),
+        _mm_sra_epi64_fallback(_mm_xor_si128(a, b), _mm_cvtsi32_si128(1)),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_mulhi_epu8_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Multiply even and odd bytes separately in 16-bit lanes and keep the high byte of each
+    let mask = _mm_set1_epi16(0xFF);
+    let even = _mm_mullo_epi16(_mm_and_si128(a, mask), _mm_and_si128(b, mask));
+    let odd = _mm_mullo_epi16(_mm_srli_epi16(a, 8), _mm_srli_epi16(b, 8));
+    _mm_or_si128(_mm_srli_epi16(even, 8), _mm_andnot_si128(mask, odd))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_mulhi_epi8_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Multiply even and odd bytes separately in 16-bit lanes and keep the high byte of each
+    let mask = _mm_set1_epi16(0xFF);
+    let a_even = _mm_srai_epi16(_mm_slli_epi16(a, 8), 8);
+    let b_even = _mm_srai_epi16(_mm_slli_epi16(b, 8), 8);
+    let even = _mm_mullo_epi16(a_even, b_even);
+    let odd = _mm_mullo_epi16(_mm_srai_epi16(a, 8), _mm_srai_epi16(b, 8));
+    _mm_or_si128(_mm_srli_epi16(even, 8), _mm_andnot_si128(mask, odd))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_mulhi_epu32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Multiply even and odd lanes separately in 64-bit lanes and keep the high half of each
+    let mask = _mm_set1_epi64x(0xFFFFFFFF);
+    let even = _mm_mul_epu32(a, b);
+    let odd = _mm_mul_epu32(_mm_srli_epi64(a, 32), _mm_srli_epi64(b, 32));
+    _mm_or_si128(_mm_srli_epi64(even, 32), _mm_andnot_si128(mask, odd))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_mulhi_epi32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Correct the unsigned product for negative inputs, each of which contributes an extra
+    // 2^32 times the other input
+    let high = _mm_mulhi_epu32_fallback(a, b);
+    let a_correction = _mm_and_si128(_mm_srai_epi32(a, 31), b);
+    let b_correction = _mm_and_si128(_mm_srai_epi32(b, 31), a);
+    _mm_sub_epi32(_mm_sub_epi32(high, a_correction), b_correction)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_mulhi_epu64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Sum the four 32x32-bit partial products
+    let mask = _mm_set1_epi64x(0xFFFFFFFF);
+    let a_high = _mm_srli_epi64(a, 32);
+    let b_high = _mm_srli_epi64(b, 32);
+    let low_low = _mm_mul_epu32(a, b);
+    let low_high = _mm_mul_epu32(a, b_high);
+    let high_low = _mm_mul_epu32(a_high, b);
+    let high_high = _mm_mul_epu32(a_high, b_high);
+    // The middle column cannot overflow, as it is a sum of three 32-bit values
+    let middle = _mm_add_epi64(
+        _mm_add_epi64(_mm_srli_epi64(low_low, 32), _mm_and_si128(low_high, mask)),
+        _mm_and_si128(high_low, mask),
+    );
+    let high = _mm_add_epi64(
+        _mm_add_epi64(high_high, _mm_srli_epi64(low_high, 32)),
+        _mm_srli_epi64(high_low, 32),
+    );
+    _mm_add_epi64(high, _mm_srli_epi64(middle, 32))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_mulhi_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Correct the unsigned product for negative inputs, each of which contributes an extra
+    // 2^64 times the other input
+    let high = _mm_mulhi_epu64_fallback(a, b);
+    let a_correction = _mm_and_si128(broadcast_sign_epi64(a), b);
+    let b_correction = _mm_and_si128(broadcast_sign_epi64(b), a);
+    _mm_sub_epi64(_mm_sub_epi64(high, a_correction), b_correction)
+}
+
+// Integer division is performed in floating point where the result is guaranteed to be exact
+// after truncation, i.e. where the inputs are representable and the gap between the quotient and
+// the next integer always exceeds the rounding error.
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_div_epu8_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Divide in 16-bit lanes and then truncate
+    let zero = _mm_setzero_si128();
+    let low = _mm_div_epu16_fallback(_mm_unpacklo_epi8(a, zero), _mm_unpacklo_epi8(b, zero));
+    let high = _mm_div_epu16_fallback(_mm_unpackhi_epi8(a, zero), _mm_unpackhi_epi8(b, zero));
+    truncate_epi16(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_div_epi8_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Divide in 16-bit lanes and then truncate, which also wraps MIN / -1
+    let low = _mm_div_epi16_fallback(
+        _mm_srai_epi16(_mm_unpacklo_epi8(a, a), 8),
+        _mm_srai_epi16(_mm_unpacklo_epi8(b, b), 8),
+    );
+    let high = _mm_div_epi16_fallback(
+        _mm_srai_epi16(_mm_unpackhi_epi8(a, a), 8),
+        _mm_srai_epi16(_mm_unpackhi_epi8(b, b), 8),
+    );
+    truncate_epi16(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn div_epi32_ps(a: __m128i, b: __m128i) -> __m128i {
+    _mm_cvttps_epi32(_mm_div_ps(_mm_cvtepi32_ps(a), _mm_cvtepi32_ps(b)))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_div_epu16_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Divide in single precision. Division by zero converts to 0x80000000, which truncates to
+    // zero.
+    let zero = _mm_setzero_si128();
+    let low = div_epi32_ps(_mm_unpacklo_epi16(a, zero), _mm_unpacklo_epi16(b, zero));
+    let high = div_epi32_ps(_mm_unpackhi_epi16(a, zero), _mm_unpackhi_epi16(b, zero));
+    truncate_epi32(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_div_epi16_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Divide in single precision. Truncating the result wraps MIN / -1, and division by zero
+    // converts to 0x80000000, which truncates to zero.
+    let low = div_epi32_ps(
+        _mm_srai_epi32(_mm_unpacklo_epi16(a, a), 16),
+        _mm_srai_epi32(_mm_unpacklo_epi16(b, b), 16),
+    );
+    let high = div_epi32_ps(
+        _mm_srai_epi32(_mm_unpackhi_epi16(a, a), 16),
+        _mm_srai_epi32(_mm_unpackhi_epi16(b, b), 16),
+    );
+    truncate_epi32(low, high)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn div_epu32_pd(a: __m128i, b: __m128i) -> __m128i {
+    // Convert from unsigned by flipping the top bit and adding back 2^31
+    let bias = _mm_set1_pd(2147483648.0);
+    let flip = _mm_set1_epi32(i32::MIN);
+    let a = _mm_add_pd(_mm_cvtepi32_pd(_mm_xor_si128(a, flip)), bias);
+    let b = _mm_add_pd(_mm_cvtepi32_pd(_mm_xor_si128(b, flip)), bias);
+    let quotient = _mm_div_pd(a, b);
+    // Truncate by rounding to the nearest integer and correcting quotients that were rounded up
+    let magic = _mm_set1_pd(4503599627370496.0);
+    let rounded = _mm_sub_pd(_mm_add_pd(quotient, magic), magic);
+    let correction = _mm_and_pd(_mm_cmpgt_pd(rounded, quotient), _mm_set1_pd(1.0));
+    let truncated = _mm_sub_pd(rounded, correction);
+    _mm_xor_si128(_mm_cvttpd_epi32(_mm_sub_pd(truncated, bias)), flip)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_div_epu32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Divide in double precision
+    let low = div_epu32_pd(a, b);
+    let high = div_epu32_pd(_mm_unpackhi_epi64(a, a), _mm_unpackhi_epi64(b, b));
+    let quotient = _mm_unpacklo_epi64(low, high);
+    _mm_andnot_si128(_mm_cmpeq_epi32(b, _mm_setzero_si128()), quotient)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn div_epi32_pd(a: __m128i, b: __m128i) -> __m128i {
+    _mm_cvttpd_epi32(_mm_div_pd(_mm_cvtepi32_pd(a), _mm_cvtepi32_pd(b)))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_div_epi32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Divide in double precision. MIN / -1 is out of range, and converts to MIN as desired.
+    let low = div_epi32_pd(a, b);
+    let high = div_epi32_pd(_mm_unpackhi_epi64(a, a), _mm_unpackhi_epi64(b, b));
+    let quotient = _mm_unpacklo_epi64(low, high);
+    _mm_andnot_si128(_mm_cmpeq_epi32(b, _mm_setzero_si128()), quotient)
+}
+
+// There is no floating-point type wide enough to divide 64-bit integers exactly, so fall back to
+// scalar division
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_div_epu64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    let a: [u64; 2] = mem::transmute(a);
+    let b: [u64; 2] = mem::transmute(b);
+    let div = |i: usize| a[i].checked_div(b[i]).unwrap_or(0);
+    mem::transmute([div(0), div(1)])
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_div_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    let a: [i64; 2] = mem::transmute(a);
+    let b: [i64; 2] = mem::transmute(b);
+    let div = |i: usize| {
+        if b[i] == 0 {
+            0
+        } else {
+            a[i].wrapping_div(b[i])
+        }
+    };
+    mem::transmute([div(0), div(1)])
+}
+
+// There are no approximate reciprocal instructions for f64, so compute them precisely
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_blendv_ps_fallback(a: __m128, b: __m128, mask: __m128) -> __m128 {
+    // Unlike blendvps, this uses every bit of the mask rather than just the sign bit
+    _mm_or_ps(_mm_andnot_ps(mask, a), _mm_and_ps(mask, b))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_blendv_pd_fallback(a: __m128d, b: __m128d, mask: __m128d) -> __m128d {
+    _mm_or_pd(_mm_andnot_pd(mask, a), _mm_and_pd(mask, b))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_blendv_epi8_fallback(a: __m128i, b: __m128i, mask: __m128i) -> __m128i {
+    _mm_or_si128(_mm_andnot_si128(mask, a), _mm_and_si128(mask, b))
+}
+
+// Both rounding fallbacks treat _MM_FROUND_CUR_DIRECTION as rounding to nearest.
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_round_ps_fallback<const ROUNDING: i32>(a: __m128) -> __m128 {
+    // Adding and subtracting 2^MANTISSA_BITS rounds a magnitude below it to the nearest integer;
+    // anything at or above it is already an integer
+    let sign = _mm_and_ps(a, _mm_set1_ps(-0.0));
+    let magnitude = _mm_xor_ps(a, sign);
+    let limit = _mm_set1_ps(1.0 / f32::EPSILON);
+    let nearest = _mm_sub_ps(_mm_add_ps(magnitude, limit), limit);
+
+    let one = _mm_set1_ps(1.0);
+    let down = _mm_sub_ps(nearest, _mm_and_ps(_mm_cmpgt_ps(nearest, magnitude), one));
+    let up = _mm_add_ps(nearest, _mm_and_ps(_mm_cmplt_ps(nearest, magnitude), one));
+    let negative = _mm_castsi128_ps(_mm_srai_epi32(_mm_castps_si128(a), 31));
+    let rounded = match ROUNDING & 0x3 {
+        _MM_FROUND_TO_NEG_INF => _mm_blendv_ps_fallback(down, up, negative),
+        _MM_FROUND_TO_POS_INF => _mm_blendv_ps_fallback(up, down, negative),
+        _MM_FROUND_TO_ZERO => down,
+        _ => nearest,
+    };
+
+    _mm_blendv_ps_fallback(a, _mm_or_ps(rounded, sign), _mm_cmplt_ps(magnitude, limit))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_round_pd_fallback<const ROUNDING: i32>(a: __m128d) -> __m128d {
+    let sign = _mm_and_pd(a, _mm_set1_pd(-0.0));
+    let magnitude = _mm_xor_pd(a, sign);
+    let limit = _mm_set1_pd(1.0 / f64::EPSILON);
+    let nearest = _mm_sub_pd(_mm_add_pd(magnitude, limit), limit);
+
+    let one = _mm_set1_pd(1.0);
+    let down = _mm_sub_pd(nearest, _mm_and_pd(_mm_cmpgt_pd(nearest, magnitude), one));
+    let up = _mm_add_pd(nearest, _mm_and_pd(_mm_cmplt_pd(nearest, magnitude), one));
+    let negative = _mm_castsi128_pd(broadcast_sign_epi64(_mm_castpd_si128(a)));
+    let rounded = match ROUNDING & 0x3 {
+        _MM_FROUND_TO_NEG_INF => _mm_blendv_pd_fallback(down, up, negative),
+        _MM_FROUND_TO_POS_INF => _mm_blendv_pd_fallback(up, down, negative),
+        _MM_FROUND_TO_ZERO => down,
+        _ => nearest,
+    };
+
+    _mm_blendv_pd_fallback(a, _mm_or_pd(rounded, sign), _mm_cmplt_pd(magnitude, limit))
 }
-float_type! {
-    "sse2",
-    f64x2, __m128d, f64, 2, m64x2,
-    _mm_set1_pd, _mm_loadu_pd, _mm_storeu_pd, _mm_castpd_si128, _mm_castsi128_pd,
-    _mm_cmpeq_pd, _mm_cmpneq_pd, _mm_cmplt_pd, _mm_cmple_pd, _mm_cmpgt_pd, _mm_cmpge_pd,
-    _mm_min_pd, _mm_max_pd, _mm_and_pd, _mm_or_pd, _mm_andnot_pd, _mm_xor_pd,
-    _mm_add_pd, _mm_sub_pd, _mm_mul_pd, _mm_div_pd,
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_mullo_epi32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    let a_odd = _mm_srli_epi64(a, 32);
+    let b_odd = _mm_srli_epi64(b, 32);
+    let even = _mm_mul_epu32(a, b);
+    let odd = _mm_slli_epi64(_mm_mul_epu32(a_odd, b_odd), 32);
+    let mask = _mm_set1_epi64x(0xFFFFFFFF);
+    _mm_or_si128(_mm_and_si128(mask, even), odd)
 }
 
-int_type! { "sse2", u8x16, u8, 16, m8x16, _mm_set1_epi8 }
-int_type! { "sse2", u16x8, u16, 8, m16x8, _mm_set1_epi16 }
-int_type! { "sse2", u32x4, u32, 4, m32x4, _mm_set1_epi32 }
-int_type! { "sse2", u64x2, u64, 2, m64x2, _mm_set1_epi64x }
-impl_int! { "sse2", u8x16, u8, _mm_set1_epi8, _mm_add_epi8, _mm_sub_epi8, _mm_sll_epi8_fallback, _mm_srl_epi8_fallback }
-impl_int! { "sse2", u16x8, u16, _mm_set1_epi16, _mm_add_epi16, _mm_sub_epi16, _mm_sll_epi16, _mm_srl_epi16 }
-impl_int! { "sse2", u32x4, u32, _mm_set1_epi32, _mm_add_epi32, _mm_sub_epi32, _mm_sll_epi32, _mm_srl_epi32 }
-impl_int! { "sse2", u64x2, u64, _mm_set1_epi64x, _mm_add_epi64, _mm_sub_epi64, _mm_sll_epi64, _mm_srl_epi64 }
-impl_int_mul! { u8x16, u16x8, u32x4, u64x2 }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_cmpeq_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Compare high and low 32-bit integers separately, then swap and AND together
+    let res = _mm_cmpeq_epi32(a, b);
+    let swapped = _mm_shuffle_epi32(res, 0xB1);
+    _mm_and_si128(res, swapped)
+}
 
-impl LanesEq for u8x16 {
-    type Output = m8x16;
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_cmpgt_epu64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // If we split two 64-bit integers A and B into pairs of 32-bit integers (Ah, Al) and
+    // (Bh, Bl), A > B iff Ah > Bh || (Ah == Bh && Al > Bl).
+    //
+    // Since we only have a signed 32-bit compare and we need to perform four unsigned
+    // comparisons, we need to bias all four 32-bit integers.
+    let bias = _mm_set1_epi32(i32::MIN);
+    cmpgt_epi64_biased(_mm_add_epi32(a, bias), _mm_add_epi32(b, bias))
+}
 
-    #[inline(always)]
-    fn eq(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &u8x16, rhs: &u8x16) -> m8x16 {
-            m8x16(_mm_cmpeq_epi8(lhs.0, rhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_cmpgt_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // Bias just the lower 32 bits, since we only have a signed 32-bit compare and we need to
+    // perform an unsigned comparison on the lower bits.
+    let bias = _mm_set_epi32(0, i32::MIN, 0, i32::MIN);
+    cmpgt_epi64_biased(_mm_add_epi32(a, bias), _mm_add_epi32(b, bias))
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn cmpgt_epi64_biased(a: __m128i, b: __m128i) -> __m128i {
+    let gt = _mm_cmpgt_epi32(a, b);
+    let eq = _mm_cmpeq_epi32(a, b);
+    // Copy Al > Bl result up to the upper 32 bits
+    let gt_low = _mm_shuffle_epi32(gt, 0xA0);
+    let res = _mm_or_si128(gt, _mm_and_si128(eq, gt_low));
+    // Copy the final result back to the lower 32 bits
+    _mm_shuffle_epi32(res, 0xF5)
 }
 
-impl LanesOrd for u8x16 {
-    #[inline(always)]
-    fn lt(&self, other: &Self) -> Self::Output {
-        !other.le(self)
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_max_epi8_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8_fallback(a, b, _mm_cmpgt_epi8(b, a))
+}
 
-    #[inline(always)]
-    fn le(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &u8x16, rhs: &u8x16) -> m8x16 {
-            m8x16(_mm_cmpeq_epi8(lhs.0, _mm_min_epu8(lhs.0, rhs.0)))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_min_epi8_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8_fallback(a, b, _mm_cmpgt_epi8(a, b))
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_max_epu16_fallback(a: __m128i, b: __m128i) -> __m128i {
+    // a - b saturates to zero when b is the larger
+    _mm_add_epi16(_mm_subs_epu16(a, b), b)
+}
 
-    #[inline(always)]
-    fn max(self, other: Self) -> Self {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: u8x16, rhs: u8x16) -> u8x16 {
-            u8x16(_mm_max_epu8(lhs.0, rhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_min_epu16_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_sub_epi16(a, _mm_subs_epu16(a, b))
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_max_epu32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    let bias = _mm_set1_epi32(i32::MIN);
+    let gt = _mm_cmpgt_epi32(_mm_add_epi32(b, bias), _mm_add_epi32(a, bias));
+    _mm_blendv_epi8_fallback(a, b, gt)
+}
 
-    #[inline(always)]
-    fn min(self, other: Self) -> Self {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: u8x16, rhs: u8x16) -> u8x16 {
-            u8x16(_mm_min_epu8(lhs.0, rhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_min_epu32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    let bias = _mm_set1_epi32(i32::MIN);
+    let gt = _mm_cmpgt_epi32(_mm_add_epi32(a, bias), _mm_add_epi32(b, bias));
+    _mm_blendv_epi8_fallback(a, b, gt)
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_max_epi32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8_fallback(a, b, _mm_cmpgt_epi32(b, a))
 }
 
-impl LanesEq for u16x8 {
-    type Output = m16x8;
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_min_epi32_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8_fallback(a, b, _mm_cmpgt_epi32(a, b))
+}
 
-    #[inline(always)]
-    fn eq(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &u16x8, rhs: &u16x8) -> m16x8 {
-            m16x8(_mm_cmpeq_epi16(lhs.0, rhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_max_epu64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8_fallback(a, b, _mm_cmpgt_epu64_fallback(b, a))
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_min_epu64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8_fallback(a, b, _mm_cmpgt_epu64_fallback(a, b))
 }
 
-impl LanesOrd for u16x8 {
-    #[inline(always)]
-    fn lt(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &u16x8, rhs: &u16x8) -> m16x8 {
-            let bias = _mm_set1_epi16(i16::MIN);
-            m16x8(_mm_cmplt_epi16(
-                _mm_add_epi16(lhs.0, bias),
-                _mm_add_epi16(rhs.0, bias),
-            ))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_max_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8_fallback(a, b, _mm_cmpgt_epi64_fallback(b, a))
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub unsafe fn _mm_min_epi64_fallback(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8_fallback(a, b, _mm_cmpgt_epi64_fallback(a, b))
 }
 
-impl LanesEq for u32x4 {
-    type Output = m32x4;
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn rcp_pd(a: __m128d) -> __m128d {
+    _mm_div_pd(_mm_set1_pd(1.0), a)
+}
 
-    #[inline(always)]
-    fn eq(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &u32x4, rhs: &u32x4) -> m32x4 {
-            m32x4(_mm_cmpeq_epi32(lhs.0, rhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn rsqrt_pd(a: __m128d) -> __m128d {
+    _mm_div_pd(_mm_set1_pd(1.0), _mm_sqrt_pd(a))
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn movemask_epi8(a: __m128i) -> u64 {
+    _mm_movemask_epi8(a) as u32 as u64
 }
 
-impl LanesOrd for u32x4 {
-    #[inline(always)]
-    fn lt(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &u32x4, rhs: &u32x4) -> m32x4 {
-            let bias = _mm_set1_epi32(i32::MIN);
-            m32x4(_mm_cmplt_epi32(
-                _mm_add_epi32(lhs.0, bias),
-                _mm_add_epi32(rhs.0, bias),
-            ))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn movemask_epi16(a: __m128i) -> u64 {
+    _mm_movemask_epi8(_mm_packs_epi16(a, _mm_setzero_si128())) as u32 as u64
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn movemask_epi32(a: __m128i) -> u64 {
+    _mm_movemask_ps(_mm_castsi128_ps(a)) as u32 as u64
 }
 
-impl LanesEq for u64x2 {
-    type Output = m64x2;
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn movemask_epi64(a: __m128i) -> u64 {
+    _mm_movemask_pd(_mm_castsi128_pd(a)) as u32 as u64
+}
 
-    #[inline(always)]
-    fn eq(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &u64x2, rhs: &u64x2) -> m64x2 {
-            // Compare high and low 32-bit integers separately, then swap and AND together
-            let res = _mm_cmpeq_epi32(lhs.0, rhs.0);
-            let swapped = _mm_shuffle_epi32(res, 0xB1);
-            m64x2(_mm_and_si128(res, swapped))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn expand_bits_epi8(bits: u64) -> __m128i {
+    // Broadcast each of the two low bytes across one 64-bit half, then test one bit per byte
+    let lo = (bits & 0xFF).wrapping_mul(0x0101010101010101);
+    let hi = ((bits >> 8) & 0xFF).wrapping_mul(0x0101010101010101);
+    let select = _mm_set1_epi64x(0x8040201008040201u64 as i64);
+    let bytes = _mm_and_si128(_mm_set_epi64x(hi as i64, lo as i64), select);
+    _mm_cmpeq_epi8(bytes, select)
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn expand_bits_epi16(bits: u64) -> __m128i {
+    let select = _mm_setr_epi16(1, 2, 4, 8, 16, 32, 64, 128);
+    _mm_cmpeq_epi16(_mm_and_si128(_mm_set1_epi16(bits as i16), select), select)
 }
 
-impl LanesOrd for u64x2 {
-    #[inline(always)]
-    fn lt(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &u64x2, rhs: &u64x2) -> m64x2 {
-            // If we split two 64-bit integers L and R into pairs of 32-bit integers (Lh, Ll) and
-            // (Rh, Rl), L < R iff Lh < Rh || (Lh == Rh && Ll < Rl).
-            //
-            // Since we only have a signed 32-bit compare and we need to perform four unsigned
-            // comparisons, we need to bias all four 32-bit integers.
-            let bias = _mm_set1_epi32(i32::MIN);
-            let lhs = _mm_add_epi32(lhs.0, bias);
-            let rhs = _mm_add_epi32(rhs.0, bias);
-            let lt = _mm_cmplt_epi32(lhs, rhs);
-            let eq = _mm_cmpeq_epi32(lhs, rhs);
-            // Copy Rh < Lh result down to the lower 32 bits
-            let lt_low = _mm_shuffle_epi32(lt, 0xA0);
-            let res = _mm_or_si128(lt, _mm_and_si128(eq, lt_low));
-            // Copy the final result back to the upper 32 bits
-            m64x2(_mm_shuffle_epi32(res, 0xF5))
-        }
-
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn expand_bits_epi32(bits: u64) -> __m128i {
+    let select = _mm_setr_epi32(1, 2, 4, 8);
+    _mm_cmpeq_epi32(_mm_and_si128(_mm_set1_epi32(bits as i32), select), select)
 }
 
-int_type! { "sse2", i8x16, i8, 16, m8x16, _mm_set1_epi8 }
-int_type! { "sse2", i16x8, i16, 8, m16x8, _mm_set1_epi16 }
-int_type! { "sse2", i32x4, i32, 4, m32x4, _mm_set1_epi32 }
-int_type! { "sse2", i64x2, i64, 2, m64x2, _mm_set1_epi64x }
-impl_int! { "sse2", i8x16, i8, _mm_set1_epi8, _mm_add_epi8, _mm_sub_epi8, _mm_sll_epi8_fallback, _mm_sra_epi8_fallback }
-impl_int! { "sse2", i16x8, i16, _mm_set1_epi16, _mm_add_epi16, _mm_sub_epi16, _mm_sll_epi16, _mm_sra_epi16 }
-impl_int! { "sse2", i32x4, i32, _mm_set1_epi32, _mm_add_epi32, _mm_sub_epi32, _mm_sll_epi32, _mm_sra_epi32 }
-impl_int! { "sse2", i64x2, i64, _mm_set1_epi64x, _mm_add_epi64, _mm_sub_epi64, _mm_sll_epi64, _mm_sra_epi64_fallback }
-impl_int_mul! { i8x16, i16x8, i32x4, i64x2 }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn expand_bits_epi64(bits: u64) -> __m128i {
+    // Both 32-bit halves of each lane test the same bit, since there is no 64-bit compare
+    let select = _mm_setr_epi32(1, 1, 2, 2);
+    _mm_cmpeq_epi32(_mm_and_si128(_mm_set1_epi32(bits as i32), select), select)
+}
 
-impl LanesEq for i8x16 {
-    type Output = m8x16;
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn zero_extend_bits(_a: __m128i) -> __m128i {
+    _mm_setzero_si128()
+}
 
-    #[inline(always)]
-    fn eq(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &i8x16, rhs: &i8x16) -> m8x16 {
-            m8x16(_mm_cmpeq_epi8(lhs.0, rhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn sign_extend_bits_epi8(a: __m128i) -> __m128i {
+    _mm_cmplt_epi8(a, _mm_setzero_si128())
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn sign_extend_bits_epi16(a: __m128i) -> __m128i {
+    _mm_cmplt_epi16(a, _mm_setzero_si128())
 }
 
-impl LanesOrd for i8x16 {
-    #[inline(always)]
-    fn lt(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &i8x16, rhs: &i8x16) -> m8x16 {
-            m8x16(_mm_cmpgt_epi8(rhs.0, lhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn sign_extend_bits_epi32(a: __m128i) -> __m128i {
+    _mm_cmplt_epi32(a, _mm_setzero_si128())
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn truncate_epi16(a: __m128i, b: __m128i) -> __m128i {
+    // Clear the high bytes so that the saturating pack leaves the low bytes unchanged
+    let mask = _mm_set1_epi16(0x00FF);
+    _mm_packus_epi16(_mm_and_si128(a, mask), _mm_and_si128(b, mask))
 }
 
-impl LanesEq for i16x8 {
-    type Output = m16x8;
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn truncate_epi32(a: __m128i, b: __m128i) -> __m128i {
+    // Sign-extend the low halves so that the saturating pack leaves them unchanged
+    let a = _mm_srai_epi32(_mm_slli_epi32(a, 16), 16);
+    let b = _mm_srai_epi32(_mm_slli_epi32(b, 16), 16);
+    _mm_packs_epi32(a, b)
+}
 
-    #[inline(always)]
-    fn eq(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &i16x8, rhs: &i16x8) -> m16x8 {
-            m16x8(_mm_cmpeq_epi16(lhs.0, rhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn truncate_epi64(a: __m128i, b: __m128i) -> __m128i {
+    let res = _mm_shuffle_ps(_mm_castsi128_ps(a), _mm_castsi128_ps(b), 0x88);
+    _mm_castps_si128(res)
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn reverse_epi8(a: __m128i) -> __m128i {
+    // Reverse 16-bit lanes, then swap the bytes within each
+    let a = reverse_epi16(a);
+    _mm_or_si128(_mm_slli_epi16(a, 8), _mm_srli_epi16(a, 8))
 }
 
-impl LanesOrd for i16x8 {
-    #[inline(always)]
-    fn lt(&self, other: &Self) -> Self::Output {
-        #[inline]
-        #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &i16x8, rhs: &i16x8) -> m16x8 {
-            m16x8(_mm_cmplt_epi16(lhs.0, rhs.0))
-        }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn reverse_epi16(a: __m128i) -> __m128i {
+    let a = _mm_shuffle_epi32(a, 0x4E);
+    _mm_shufflehi_epi16(_mm_shufflelo_epi16(a, 0x1B), 0x1B)
+}
 
-        unsafe { inner(self, other) }
-    }
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn reverse_epi32(a: __m128i) -> __m128i {
+    _mm_shuffle_epi32(a, 0x1B)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn reverse_epi64(a: __m128i) -> __m128i {
+    _mm_shuffle_epi32(a, 0x4E)
+}
+
+// Even lanes are the low halves of lanes twice as wide, and odd lanes are the high halves
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn deinterleave_epi8(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+    let even = truncate_epi16(a, b);
+    let odd = truncate_epi16(_mm_srli_epi16(a, 8), _mm_srli_epi16(b, 8));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn deinterleave_epi16(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+    let even = truncate_epi32(a, b);
+    let odd = truncate_epi32(_mm_srli_epi32(a, 16), _mm_srli_epi32(b, 16));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn deinterleave_epi32(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+    let even = truncate_epi64(a, b);
+    let odd = truncate_epi64(_mm_srli_epi64(a, 32), _mm_srli_epi64(b, 32));
+    (even, odd)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn deinterleave_epi64(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+    (_mm_unpacklo_epi64(a, b), _mm_unpackhi_epi64(a, b))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn cvtepu32_ps(a: __m128i) -> __m128 {
+    // Convert the high and low 16 bits separately; both conversions and the multiplication are
+    // exact, so the final addition is the only rounding step
+    let high = _mm_cvtepi32_ps(_mm_srli_epi32(a, 16));
+    let low = _mm_cvtepi32_ps(_mm_and_si128(a, _mm_set1_epi32(0xFFFF)));
+    _mm_add_ps(_mm_mul_ps(high, _mm_set1_ps(65536.0)), low)
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn cvtepu64_pd(a: __m128i) -> __m128d {
+    // Place the high and low 32 bits in the mantissas of 2^84 and 2^52 respectively, subtract
+    // the offsets exactly, and then perform a single rounding addition
+    let high = _mm_or_si128(_mm_srli_epi64(a, 32), _mm_set1_epi64x(0x4530000000000000));
+    let low = _mm_and_si128(a, _mm_set1_epi64x(0xFFFFFFFF));
+    let low = _mm_or_si128(low, _mm_set1_epi64x(0x4330000000000000));
+    let offset = _mm_set1_pd(f64::from_bits(0x4530000000100000));
+    let high = _mm_sub_pd(_mm_castsi128_pd(high), offset);
+    _mm_add_pd(high, _mm_castsi128_pd(low))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn cvtepi64_pd(a: __m128i) -> __m128d {
+    // Same as cvtepu64_pd, but with the high 32 bits biased by 2^31 to make them unsigned
+    let high = _mm_xor_si128(_mm_srli_epi64(a, 32), _mm_set1_epi64x(0x4530000080000000));
+    let low = _mm_and_si128(a, _mm_set1_epi64x(0xFFFFFFFF));
+    let low = _mm_or_si128(low, _mm_set1_epi64x(0x4330000000000000));
+    let offset = _mm_set1_pd(f64::from_bits(0x4530000080100000));
+    let high = _mm_sub_pd(_mm_castsi128_pd(high), offset);
+    _mm_add_pd(high, _mm_castsi128_pd(low))
+}
+
+float_type! {
+    "sse2",
+    f32x4, __m128, f32, 4, m32x4, u32x4,
+    _mm_set1_ps, _mm_loadu_ps, _mm_load_ps, _mm_storeu_ps, _mm_stream_ps, _mm_castps_si128, _mm_castsi128_ps,
+    _mm_cmpeq_ps, _mm_cmpneq_ps, _mm_cmplt_ps, _mm_cmple_ps, _mm_cmpgt_ps, _mm_cmpge_ps,
+    _mm_min_ps, _mm_max_ps, _mm_and_ps, _mm_or_ps, _mm_andnot_ps, _mm_xor_ps, _mm_blendv_ps_fallback,
+    _mm_add_ps, _mm_sub_ps, _mm_mul_ps, _mm_div_ps, _mm_sqrt_ps, _mm_rcp_ps, _mm_rsqrt_ps, _mm_round_ps_fallback,
+}
+float_type! {
+    "sse2",
+    f64x2, __m128d, f64, 2, m64x2, u64x2,
+    _mm_set1_pd, _mm_loadu_pd, _mm_load_pd, _mm_storeu_pd, _mm_stream_pd, _mm_castpd_si128, _mm_castsi128_pd,
+    _mm_cmpeq_pd, _mm_cmpneq_pd, _mm_cmplt_pd, _mm_cmple_pd, _mm_cmpgt_pd, _mm_cmpge_pd,
+    _mm_min_pd, _mm_max_pd, _mm_and_pd, _mm_or_pd, _mm_andnot_pd, _mm_xor_pd, _mm_blendv_pd_fallback,
+    _mm_add_pd, _mm_sub_pd, _mm_mul_pd, _mm_div_pd, _mm_sqrt_pd, rcp_pd, rsqrt_pd, _mm_round_pd_fallback,
+}
+
+impl_f32_to_int! { "sse2", Cast, cast, _mm_cvttps_epi32 }
+impl_f32_to_int! { "sse2", RoundCast, round_cast, _mm_cvtps_epi32 }
+impl_cast_lanes! { f64x2, i64x2, i64 }
+impl_cast_lanes! { f64x2, u64x2, u64 }
+impl_shuffle! { "sse2", f32x4, _mm_castps_si128, _mm_castsi128_ps, reverse_epi32, _mm_unpacklo_epi32, _mm_unpackhi_epi32, deinterleave_epi32 }
+impl_shuffle! { "sse2", f64x2, _mm_castpd_si128, _mm_castsi128_pd, reverse_epi64, _mm_unpacklo_epi64, _mm_unpackhi_epi64, deinterleave_epi64 }
+impl_gather! { f32x4, u32x4, i32x4 }
+impl_gather! { f64x2, u64x2, i64x2 }
+impl_gather! { [f64x2; 2], u32x4, i32x4 }
+
+impl Widen for f32x4 {
+    type Wide = f64x2;
+    type Parts = [f64x2; 2];
 
     #[inline(always)]
-    fn max(self, other: Self) -> Self {
+    fn widen(self) -> Self::Parts {
         #[inline]
         #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: i16x8, rhs: i16x8) -> i16x8 {
-            i16x8(_mm_max_epi16(lhs.0, rhs.0))
+        unsafe fn inner(vec: f32x4) -> [f64x2; 2] {
+            let high = _mm_movehl_ps(vec.0, vec.0);
+            [f64x2(_mm_cvtps_pd(vec.0)), f64x2(_mm_cvtps_pd(high))]
         }
 
-        unsafe { inner(self, other) }
+        unsafe { inner(self) }
     }
 
     #[inline(always)]
-    fn min(self, other: Self) -> Self {
+    fn narrow(parts: Self::Parts) -> Self {
         #[inline]
         #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: i16x8, rhs: i16x8) -> i16x8 {
-            i16x8(_mm_min_epi16(lhs.0, rhs.0))
+        unsafe fn inner(parts: [f64x2; 2]) -> f32x4 {
+            let low = _mm_cvtpd_ps(parts[0].0);
+            let high = _mm_cvtpd_ps(parts[1].0);
+            f32x4(_mm_movelh_ps(low, high))
         }
 
-        unsafe { inner(self, other) }
+        unsafe { inner(parts) }
     }
 }
 
-impl LanesEq for i32x4 {
-    type Output = m32x4;
-
+int_type! { "sse2", u8x16, u8, 16, m8x16, _mm_set1_epi8, _mm_blendv_epi8_fallback }
+int_type! { "sse2", u16x8, u16, 8, m16x8, _mm_set1_epi16, _mm_blendv_epi8_fallback }
+int_type! { "sse2", u32x4, u32, 4, m32x4, _mm_set1_epi32, _mm_blendv_epi8_fallback }
+int_type! { "sse2", u64x2, u64, 2, m64x2, _mm_set1_epi64x, _mm_blendv_epi8_fallback }
+impl_int! { "sse2", u8x16, u8, _mm_set1_epi8, _mm_add_epi8, _mm_sub_epi8, _mm_sll_epi8_fallback, _mm_srl_epi8_fallback, _mm_srl_epi8_fallback, _mm_sllv_epi8_fallback, _mm_srlv_epi8_fallback, _mm_adds_epu8, _mm_subs_epu8, _mm_avg_epu8, _mm_mulhi_epu8_fallback, _mm_div_epu8_fallback }
+impl_int! { "sse2", u16x8, u16, _mm_set1_epi16, _mm_add_epi16, _mm_sub_epi16, _mm_sll_epi16, _mm_srl_epi16, _mm_srl_epi16, _mm_sllv_epi16_fallback, _mm_srlv_epi16_fallback, _mm_adds_epu16, _mm_subs_epu16, _mm_avg_epu16, _mm_mulhi_epu16, _mm_div_epu16_fallback }
+impl_int! { "sse2", u32x4, u32, _mm_set1_epi32, _mm_add_epi32, _mm_sub_epi32, _mm_sll_epi32, _mm_srl_epi32, _mm_srl_epi32, _mm_sllv_epi32_fallback, _mm_srlv_epi32_fallback, _mm_adds_epu32_fallback, _mm_subs_epu32_fallback, _mm_avg_epu32_fallback, _mm_mulhi_epu32_fallback, _mm_div_epu32_fallback }
+impl_int! { "sse2", u64x2, u64, _mm_set1_epi64x, _mm_add_epi64, _mm_sub_epi64, _mm_sll_epi64, _mm_srl_epi64, _mm_srl_epi64, _mm_sllv_epi64_fallback, _mm_srlv_epi64_fallback, _mm_adds_epu64_fallback, _mm_subs_epu64_fallback, _mm_avg_epu64_fallback, _mm_mulhi_epu64_fallback, _mm_div_epu64_fallback }
+impl_ord_uint! { "sse2", u8x16, m8x16, _mm_cmpeq_epi8, _mm_max_epu8, _mm_min_epu8 }
+impl_ord_uint! { "sse2", u16x8, m16x8, _mm_cmpeq_epi16, _mm_max_epu16_fallback, _mm_min_epu16_fallback }
+impl_ord_uint! { "sse2", u32x4, m32x4, _mm_cmpeq_epi32, _mm_max_epu32_fallback, _mm_min_epu32_fallback }
+impl_ord_int! { "sse2", u64x2, m64x2, _mm_cmpeq_epi64_fallback, _mm_cmpgt_epu64_fallback, _mm_max_epu64_fallback, _mm_min_epu64_fallback }
+impl_int_mul! { "sse2", u8x16, u16x8, u32x4, u64x2, _mm_mullo_epi32_fallback }
+impl_shuffle! { "sse2", u8x16, identity, identity, reverse_epi8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { "sse2", u16x8, identity, identity, reverse_epi16, _mm_unpacklo_epi16, _mm_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { "sse2", u32x4, identity, identity, reverse_epi32, _mm_unpacklo_epi32, _mm_unpackhi_epi32, deinterleave_epi32 }
+impl_shuffle! { "sse2", u64x2, identity, identity, reverse_epi64, _mm_unpacklo_epi64, _mm_unpackhi_epi64, deinterleave_epi64 }
+impl_cast_bits! { u8x16, i8x16 }
+impl_cast_bits! { u16x8, i16x8 }
+impl_cast_bits! { u32x4, i32x4 }
+impl_cast_bits! { u64x2, i64x2 }
+impl_widen_int! { "sse2", u8x16, u16x8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, zero_extend_bits, truncate_epi16 }
+impl_widen_int! { "sse2", u16x8, u32x4, _mm_unpacklo_epi16, _mm_unpackhi_epi16, zero_extend_bits, truncate_epi32 }
+impl_widen_int! { "sse2", u32x4, u64x2, _mm_unpacklo_epi32, _mm_unpackhi_epi32, zero_extend_bits, truncate_epi64 }
+impl_gather! { u8x16, u8x16, i8x16 }
+impl_gather! { u8x16, [u32x4, i32x4; 4] }
+impl_gather! { u16x8, u16x8, i16x8 }
+impl_gather! { u16x8, [u32x4, i32x4; 2] }
+impl_gather! { u32x4, u32x4, i32x4 }
+impl_gather! { u64x2, u64x2, i64x2 }
+impl_gather! { [u64x2; 2], u32x4, i32x4 }
+
+impl Cast<f32x4> for u32x4 {
     #[inline(always)]
-    fn eq(&self, other: &Self) -> Self::Output {
+    fn cast(self) -> f32x4 {
         #[inline]
         #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &i32x4, rhs: &i32x4) -> m32x4 {
-            m32x4(_mm_cmpeq_epi32(lhs.0, rhs.0))
+        unsafe fn inner(vec: u32x4) -> f32x4 {
+            f32x4(cvtepu32_ps(vec.0))
         }
 
-        unsafe { inner(self, other) }
+        unsafe { inner(self) }
     }
 }
 
-impl LanesOrd for i32x4 {
+impl Cast<f64x2> for u64x2 {
     #[inline(always)]
-    fn lt(&self, other: &Self) -> Self::Output {
+    fn cast(self) -> f64x2 {
         #[inline]
         #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &i32x4, rhs: &i32x4) -> m32x4 {
-            m32x4(_mm_cmplt_epi32(lhs.0, rhs.0))
+        unsafe fn inner(vec: u64x2) -> f64x2 {
+            f64x2(cvtepu64_pd(vec.0))
         }
 
-        unsafe { inner(self, other) }
+        unsafe { inner(self) }
     }
 }
 
-impl LanesEq for i64x2 {
-    type Output = m64x2;
-
+int_type! { "sse2", i8x16, i8, 16, m8x16, _mm_set1_epi8, _mm_blendv_epi8_fallback }
+int_type! { "sse2", i16x8, i16, 8, m16x8, _mm_set1_epi16, _mm_blendv_epi8_fallback }
+int_type! { "sse2", i32x4, i32, 4, m32x4, _mm_set1_epi32, _mm_blendv_epi8_fallback }
+int_type! { "sse2", i64x2, i64, 2, m64x2, _mm_set1_epi64x, _mm_blendv_epi8_fallback }
+impl_int! { "sse2", i8x16, i8, _mm_set1_epi8, _mm_add_epi8, _mm_sub_epi8, _mm_sll_epi8_fallback, _mm_sra_epi8_fallback, _mm_srl_epi8_fallback, _mm_sllv_epi8_fallback, _mm_srav_epi8_fallback, _mm_adds_epi8, _mm_subs_epi8, _mm_avg_epi8_fallback, _mm_mulhi_epi8_fallback, _mm_div_epi8_fallback }
+impl_int! { "sse2", i16x8, i16, _mm_set1_epi16, _mm_add_epi16, _mm_sub_epi16, _mm_sll_epi16, _mm_sra_epi16, _mm_srl_epi16, _mm_sllv_epi16_fallback, _mm_srav_epi16_fallback, _mm_adds_epi16, _mm_subs_epi16, _mm_avg_epi16_fallback, _mm_mulhi_epi16, _mm_div_epi16_fallback }
+impl_int! { "sse2", i32x4, i32, _mm_set1_epi32, _mm_add_epi32, _mm_sub_epi32, _mm_sll_epi32, _mm_sra_epi32, _mm_srl_epi32, _mm_sllv_epi32_fallback, _mm_srav_epi32_fallback, _mm_adds_epi32_fallback, _mm_subs_epi32_fallback, _mm_avg_epi32_fallback, _mm_mulhi_epi32_fallback, _mm_div_epi32_fallback }
+impl_int! { "sse2", i64x2, i64, _mm_set1_epi64x, _mm_add_epi64, _mm_sub_epi64, _mm_sll_epi64, _mm_sra_epi64_fallback, _mm_srl_epi64, _mm_sllv_epi64_fallback, _mm_srav_epi64_fallback, _mm_adds_epi64_fallback, _mm_subs_epi64_fallback, _mm_avg_epi64_fallback, _mm_mulhi_epi64_fallback, _mm_div_epi64_fallback }
+impl_ord_int! { "sse2", i8x16, m8x16, _mm_cmpeq_epi8, _mm_cmpgt_epi8, _mm_max_epi8_fallback, _mm_min_epi8_fallback }
+impl_ord_int! { "sse2", i16x8, m16x8, _mm_cmpeq_epi16, _mm_cmpgt_epi16, _mm_max_epi16, _mm_min_epi16 }
+impl_ord_int! { "sse2", i32x4, m32x4, _mm_cmpeq_epi32, _mm_cmpgt_epi32, _mm_max_epi32_fallback, _mm_min_epi32_fallback }
+impl_ord_int! { "sse2", i64x2, m64x2, _mm_cmpeq_epi64_fallback, _mm_cmpgt_epi64_fallback, _mm_max_epi64_fallback, _mm_min_epi64_fallback }
+impl_int_mul! { "sse2", i8x16, i16x8, i32x4, i64x2, _mm_mullo_epi32_fallback }
+impl_shuffle! { "sse2", i8x16, identity, identity, reverse_epi8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { "sse2", i16x8, identity, identity, reverse_epi16, _mm_unpacklo_epi16, _mm_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { "sse2", i32x4, identity, identity, reverse_epi32, _mm_unpacklo_epi32, _mm_unpackhi_epi32, deinterleave_epi32 }
+impl_shuffle! { "sse2", i64x2, identity, identity, reverse_epi64, _mm_unpacklo_epi64, _mm_unpackhi_epi64, deinterleave_epi64 }
+impl_cast_bits! { i8x16, u8x16 }
+impl_cast_bits! { i16x8, u16x8 }
+impl_cast_bits! { i32x4, u32x4 }
+impl_cast_bits! { i64x2, u64x2 }
+impl_widen_int! { "sse2", i8x16, i16x8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, sign_extend_bits_epi8, truncate_epi16 }
+impl_widen_int! { "sse2", i16x8, i32x4, _mm_unpacklo_epi16, _mm_unpackhi_epi16, sign_extend_bits_epi16, truncate_epi32 }
+impl_widen_int! { "sse2", i32x4, i64x2, _mm_unpacklo_epi32, _mm_unpackhi_epi32, sign_extend_bits_epi32, truncate_epi64 }
+impl_gather! { i8x16, u8x16, i8x16 }
+impl_gather! { i8x16, [u32x4, i32x4; 4] }
+impl_gather! { i16x8, u16x8, i16x8 }
+impl_gather! { i16x8, [u32x4, i32x4; 2] }
+impl_gather! { i32x4, u32x4, i32x4 }
+impl_gather! { i64x2, u64x2, i64x2 }
+impl_gather! { [i64x2; 2], u32x4, i32x4 }
+
+impl Cast<f32x4> for i32x4 {
     #[inline(always)]
-    fn eq(&self, other: &Self) -> Self::Output {
+    fn cast(self) -> f32x4 {
         #[inline]
         #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &i64x2, rhs: &i64x2) -> m64x2 {
-            // Compare high and low 32-bit integers separately, then swap and AND together
-            let res = _mm_cmpeq_epi32(lhs.0, rhs.0);
-            let swapped = _mm_shuffle_epi32(res, 0xB1);
-            m64x2(_mm_and_si128(res, swapped))
+        unsafe fn inner(vec: i32x4) -> f32x4 {
+            f32x4(_mm_cvtepi32_ps(vec.0))
         }
 
-        unsafe { inner(self, other) }
+        unsafe { inner(self) }
     }
 }
 
-impl LanesOrd for i64x2 {
+impl Cast<f64x2> for i64x2 {
     #[inline(always)]
-    fn lt(&self, other: &Self) -> Self::Output {
+    fn cast(self) -> f64x2 {
         #[inline]
         #[target_feature(enable = "sse2")]
-        unsafe fn inner(lhs: &i64x2, rhs: &i64x2) -> m64x2 {
-            // If we split two 64-bit integers L and R into pairs of 32-bit integers (Lh, Ll) and
-            // (Rh, Rl), L < R iff Lh < Rh || (Lh == Rh && Ll < Rl).
-            //
-            // Bias just the lower 32 bits, since we only have a signed 32-bit compare and we need
-            // to perform an unsigned comparison on the lower bits.
-            let bias = _mm_set_epi32(0, i32::MIN, 0, i32::MIN);
-            let lhs = _mm_add_epi32(lhs.0, bias);
-            let rhs = _mm_add_epi32(rhs.0, bias);
-            let lt = _mm_cmplt_epi32(lhs, rhs);
-            let eq = _mm_cmpeq_epi32(lhs, rhs);
-            // Copy Rh < Lh result down to the lower 32 bits
-            let lt_low = _mm_shuffle_epi32(lt, 0xA0);
-            let res = _mm_or_si128(lt, _mm_and_si128(eq, lt_low));
-            // Copy the final result back to the upper 32 bits
-            m64x2(_mm_shuffle_epi32(res, 0xF5))
-        }
-
-        unsafe { inner(self, other) }
+        unsafe fn inner(vec: i64x2) -> f64x2 {
+            f64x2(cvtepi64_pd(vec.0))
+        }
+
+        unsafe { inner(self) }
     }
 }
 
-int_type! { "sse2", m8x16, m8, 16, m8x16, _mm_set1_epi8 }
-int_type! { "sse2", m16x8, m16, 8, m16x8, _mm_set1_epi16 }
-int_type! { "sse2", m32x4, m32, 4, m32x4, _mm_set1_epi32 }
-int_type! { "sse2", m64x2, m64, 2, m64x2, _mm_set1_epi64x }
+int_type! { "sse2", m8x16, m8, 16, m8x16, _mm_set1_epi8, _mm_blendv_epi8_fallback }
+int_type! { "sse2", m16x8, m16, 8, m16x8, _mm_set1_epi16, _mm_blendv_epi8_fallback }
+int_type! { "sse2", m32x4, m32, 4, m32x4, _mm_set1_epi32, _mm_blendv_epi8_fallback }
+int_type! { "sse2", m64x2, m64, 2, m64x2, _mm_set1_epi64x, _mm_blendv_epi8_fallback }
 impl_ord_mask! { "sse2", m8x16 }
 impl_ord_mask! { "sse2", m16x8 }
 impl_ord_mask! { "sse2", m32x4 }
 impl_ord_mask! { "sse2", m64x2 }
+impl_mask! { "sse2", m8x16, movemask_epi8, expand_bits_epi8 }
+impl_mask! { "sse2", m16x8, movemask_epi16, expand_bits_epi16 }
+impl_mask! { "sse2", m32x4, movemask_epi32, expand_bits_epi32 }
+impl_mask! { "sse2", m64x2, movemask_epi64, expand_bits_epi64 }
+impl_shuffle! { "sse2", m8x16, identity, identity, reverse_epi8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { "sse2", m16x8, identity, identity, reverse_epi16, _mm_unpacklo_epi16, _mm_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { "sse2", m32x4, identity, identity, reverse_epi32, _mm_unpacklo_epi32, _mm_unpackhi_epi32, deinterleave_epi32 }
+impl_shuffle! { "sse2", m64x2, identity, identity, reverse_epi64, _mm_unpacklo_epi64, _mm_unpackhi_epi64, deinterleave_epi64 }
 
 #[test]
 fn u64_lt() {
@@ -1272,3 +2884,55 @@ fn i64_lt() {
     let rhs = i64x2::new(u32::MAX as i64);
     assert!(lhs.lt(&rhs)[0] == true.into(), "{} < {}", lhs[0], rhs[0]);
 }
+
+#[test]
+fn round_signed_zero() {
+    let x = f32x4::from_slice(&[-0.5, 0.5, -0.0, -1.5]);
+    let bits = |v: &[f32]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+    assert_eq!(bits(x.ceil().as_slice()), bits(&[-0.0, 1.0, -0.0, -1.0]));
+    assert_eq!(bits(x.trunc().as_slice()), bits(&[-0.0, 0.0, -0.0, -1.0]));
+}
+
+#[test]
+fn swizzle() {
+    use crate::simd::Swizzle;
+
+    struct Pattern;
+
+    impl Swizzle<4> for Pattern {
+        const INDEX: [usize; 4] = [2, 0, 3, 3];
+    }
+
+    struct Pick;
+
+    impl Swizzle<4> for Pick {
+        const INDEX: [usize; 4] = [7, 0, 5, 2];
+    }
+
+    let a = u32x4::from_slice(&[0, 1, 2, 3]);
+    let b = u32x4::from_slice(&[4, 5, 6, 7]);
+    assert_eq!(a.swizzle::<Pattern, 4>().as_slice(), &[2, 0, 3, 3]);
+    assert_eq!(a.shuffle::<Pick, 4>(b).as_slice(), &[7, 0, 5, 2]);
+}
+
+#[test]
+#[should_panic(expected = "gather index out of bounds")]
+fn gather_negative_index() {
+    let table = [0.0, 1.0, 2.0, 3.0];
+    <f32x4 as Gather<i32x4>>::gather(&table, i32x4::from_slice(&[0, 1, -1, 3]));
+}
+
+#[test]
+#[should_panic(expected = "masked lane out of bounds")]
+fn store_masked_out_of_bounds() {
+    let mut out = [0.0; 3];
+    f32x4::new(1.0).store_masked(&mut out, m32x4::from_bitmask(0b1001));
+}
+
+#[test]
+#[should_panic(expected = "slice is not aligned")]
+fn from_slice_aligned_unaligned() {
+    let values = [0.0; 8];
+    let offset = f32x4::align_slice(&values).0.len();
+    f32x4::from_slice_aligned(&values[offset + 1..offset + 5]);
+}
@@ -0,0 +1,271 @@
+#![allow(non_camel_case_types)]
+
+use core::convert::identity;
+use core::fmt::{self, Debug};
+use core::mem;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
+use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+use core::slice;
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+use super::sse2::*;
+use crate::mask::*;
+use crate::simd::{
+    check_aligned, Array, Bitwise, Cast, Float, Gather, Int, LanesEq, LanesOrd, Mask, RoundCast,
+    Select, Shuffle, Simd, Widen,
+};
+use crate::{Arch, Task};
+
+pub struct Sse41Impl;
+
+impl Arch for Sse41Impl {
+    type f32 = f32x4;
+    type f64 = f64x2;
+
+    type u8 = u8x16;
+    type u16 = u16x8;
+    type u32 = u32x4;
+    type u64 = u64x2;
+
+    type i8 = i8x16;
+    type i16 = i16x8;
+    type i32 = i32x4;
+    type i64 = i64x2;
+
+    type m8 = m8x16;
+    type m16 = m16x8;
+    type m32 = m32x4;
+    type m64 = m64x2;
+
+    const NAME: &'static str = "sse41";
+
+    #[inline(always)]
+    fn invoke<T: Task>(task: T) -> T::Result {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner<T: Task>(task: T) -> T::Result {
+            task.run::<Sse41Impl>()
+        }
+
+        unsafe { inner(task) }
+    }
+
+    #[inline(always)]
+    fn prefetch<T>(ptr: *const T) {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner(ptr: *const i8) {
+            _mm_prefetch::<_MM_HINT_T0>(ptr);
+        }
+
+        unsafe { inner(ptr as *const i8) }
+    }
+
+    #[inline(always)]
+    fn stream_fence() {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner() {
+            _mm_sfence();
+        }
+
+        unsafe { inner() }
+    }
+}
+
+float_type! {
+    "sse4.1",
+    f32x4, __m128, f32, 4, m32x4, u32x4,
+    _mm_set1_ps, _mm_loadu_ps, _mm_load_ps, _mm_storeu_ps, _mm_stream_ps, _mm_castps_si128, _mm_castsi128_ps,
+    _mm_cmpeq_ps, _mm_cmpneq_ps, _mm_cmplt_ps, _mm_cmple_ps, _mm_cmpgt_ps, _mm_cmpge_ps,
+    _mm_min_ps, _mm_max_ps, _mm_and_ps, _mm_or_ps, _mm_andnot_ps, _mm_xor_ps, _mm_blendv_ps,
+    _mm_add_ps, _mm_sub_ps, _mm_mul_ps, _mm_div_ps, _mm_sqrt_ps, _mm_rcp_ps, _mm_rsqrt_ps, _mm_round_ps,
+}
+float_type! {
+    "sse4.1",
+    f64x2, __m128d, f64, 2, m64x2, u64x2,
+    _mm_set1_pd, _mm_loadu_pd, _mm_load_pd, _mm_storeu_pd, _mm_stream_pd, _mm_castpd_si128, _mm_castsi128_pd,
+    _mm_cmpeq_pd, _mm_cmpneq_pd, _mm_cmplt_pd, _mm_cmple_pd, _mm_cmpgt_pd, _mm_cmpge_pd,
+    _mm_min_pd, _mm_max_pd, _mm_and_pd, _mm_or_pd, _mm_andnot_pd, _mm_xor_pd, _mm_blendv_pd,
+    _mm_add_pd, _mm_sub_pd, _mm_mul_pd, _mm_div_pd, _mm_sqrt_pd, rcp_pd, rsqrt_pd, _mm_round_pd,
+}
+
+impl_f32_to_int! { "sse4.1", Cast, cast, _mm_cvttps_epi32 }
+impl_f32_to_int! { "sse4.1", RoundCast, round_cast, _mm_cvtps_epi32 }
+impl_cast_lanes! { f64x2, i64x2, i64 }
+impl_cast_lanes! { f64x2, u64x2, u64 }
+impl_shuffle! { "sse4.1", f32x4, _mm_castps_si128, _mm_castsi128_ps, reverse_epi32, _mm_unpacklo_epi32, _mm_unpackhi_epi32, deinterleave_epi32 }
+impl_shuffle! { "sse4.1", f64x2, _mm_castpd_si128, _mm_castsi128_pd, reverse_epi64, _mm_unpacklo_epi64, _mm_unpackhi_epi64, deinterleave_epi64 }
+impl_gather! { f32x4, u32x4, i32x4 }
+impl_gather! { f64x2, u64x2, i64x2 }
+impl_gather! { [f64x2; 2], u32x4, i32x4 }
+
+impl Widen for f32x4 {
+    type Wide = f64x2;
+    type Parts = [f64x2; 2];
+
+    #[inline(always)]
+    fn widen(self) -> Self::Parts {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner(vec: f32x4) -> [f64x2; 2] {
+            let high = _mm_movehl_ps(vec.0, vec.0);
+            [f64x2(_mm_cvtps_pd(vec.0)), f64x2(_mm_cvtps_pd(high))]
+        }
+
+        unsafe { inner(self) }
+    }
+
+    #[inline(always)]
+    fn narrow(parts: Self::Parts) -> Self {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner(parts: [f64x2; 2]) -> f32x4 {
+            let low = _mm_cvtpd_ps(parts[0].0);
+            let high = _mm_cvtpd_ps(parts[1].0);
+            f32x4(_mm_movelh_ps(low, high))
+        }
+
+        unsafe { inner(parts) }
+    }
+}
+
+int_type! { "sse4.1", u8x16, u8, 16, m8x16, _mm_set1_epi8, _mm_blendv_epi8 }
+int_type! { "sse4.1", u16x8, u16, 8, m16x8, _mm_set1_epi16, _mm_blendv_epi8 }
+int_type! { "sse4.1", u32x4, u32, 4, m32x4, _mm_set1_epi32, _mm_blendv_epi8 }
+int_type! { "sse4.1", u64x2, u64, 2, m64x2, _mm_set1_epi64x, _mm_blendv_epi8 }
+impl_int! { "sse4.1", u8x16, u8, _mm_set1_epi8, _mm_add_epi8, _mm_sub_epi8, _mm_sll_epi8_fallback, _mm_srl_epi8_fallback, _mm_srl_epi8_fallback, _mm_sllv_epi8_fallback, _mm_srlv_epi8_fallback, _mm_adds_epu8, _mm_subs_epu8, _mm_avg_epu8, _mm_mulhi_epu8_fallback, _mm_div_epu8_fallback }
+impl_int! { "sse4.1", u16x8, u16, _mm_set1_epi16, _mm_add_epi16, _mm_sub_epi16, _mm_sll_epi16, _mm_srl_epi16, _mm_srl_epi16, _mm_sllv_epi16_fallback, _mm_srlv_epi16_fallback, _mm_adds_epu16, _mm_subs_epu16, _mm_avg_epu16, _mm_mulhi_epu16, _mm_div_epu16_fallback }
+impl_int! { "sse4.1", u32x4, u32, _mm_set1_epi32, _mm_add_epi32, _mm_sub_epi32, _mm_sll_epi32, _mm_srl_epi32, _mm_srl_epi32, _mm_sllv_epi32_fallback, _mm_srlv_epi32_fallback, _mm_adds_epu32_fallback, _mm_subs_epu32_fallback, _mm_avg_epu32_fallback, _mm_mulhi_epu32_fallback, _mm_div_epu32_fallback }
+impl_int! { "sse4.1", u64x2, u64, _mm_set1_epi64x, _mm_add_epi64, _mm_sub_epi64, _mm_sll_epi64, _mm_srl_epi64, _mm_srl_epi64, _mm_sllv_epi64_fallback, _mm_srlv_epi64_fallback, _mm_adds_epu64_fallback, _mm_subs_epu64_fallback, _mm_avg_epu64_fallback, _mm_mulhi_epu64_fallback, _mm_div_epu64_fallback }
+impl_ord_uint! { "sse4.1", u8x16, m8x16, _mm_cmpeq_epi8, _mm_max_epu8, _mm_min_epu8 }
+impl_ord_uint! { "sse4.1", u16x8, m16x8, _mm_cmpeq_epi16, _mm_max_epu16, _mm_min_epu16 }
+impl_ord_uint! { "sse4.1", u32x4, m32x4, _mm_cmpeq_epi32, _mm_max_epu32, _mm_min_epu32 }
+impl_ord_int! { "sse4.1", u64x2, m64x2, _mm_cmpeq_epi64, _mm_cmpgt_epu64_fallback, _mm_max_epu64_fallback, _mm_min_epu64_fallback }
+impl_int_mul! { "sse4.1", u8x16, u16x8, u32x4, u64x2, _mm_mullo_epi32 }
+impl_shuffle! { "sse4.1", u8x16, identity, identity, reverse_epi8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { "sse4.1", u16x8, identity, identity, reverse_epi16, _mm_unpacklo_epi16, _mm_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { "sse4.1", u32x4, identity, identity, reverse_epi32, _mm_unpacklo_epi32, _mm_unpackhi_epi32, deinterleave_epi32 }
+impl_shuffle! { "sse4.1", u64x2, identity, identity, reverse_epi64, _mm_unpacklo_epi64, _mm_unpackhi_epi64, deinterleave_epi64 }
+impl_cast_bits! { u8x16, i8x16 }
+impl_cast_bits! { u16x8, i16x8 }
+impl_cast_bits! { u32x4, i32x4 }
+impl_cast_bits! { u64x2, i64x2 }
+impl_widen_int! { "sse4.1", u8x16, u16x8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, zero_extend_bits, truncate_epi16 }
+impl_widen_int! { "sse4.1", u16x8, u32x4, _mm_unpacklo_epi16, _mm_unpackhi_epi16, zero_extend_bits, truncate_epi32 }
+impl_widen_int! { "sse4.1", u32x4, u64x2, _mm_unpacklo_epi32, _mm_unpackhi_epi32, zero_extend_bits, truncate_epi64 }
+impl_gather! { u8x16, u8x16, i8x16 }
+impl_gather! { u8x16, [u32x4, i32x4; 4] }
+impl_gather! { u16x8, u16x8, i16x8 }
+impl_gather! { u16x8, [u32x4, i32x4; 2] }
+impl_gather! { u32x4, u32x4, i32x4 }
+impl_gather! { u64x2, u64x2, i64x2 }
+impl_gather! { [u64x2; 2], u32x4, i32x4 }
+
+impl Cast<f32x4> for u32x4 {
+    #[inline(always)]
+    fn cast(self) -> f32x4 {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner(vec: u32x4) -> f32x4 {
+            f32x4(cvtepu32_ps(vec.0))
+        }
+
+        unsafe { inner(self) }
+    }
+}
+
+impl Cast<f64x2> for u64x2 {
+    #[inline(always)]
+    fn cast(self) -> f64x2 {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner(vec: u64x2) -> f64x2 {
+            f64x2(cvtepu64_pd(vec.0))
+        }
+
+        unsafe { inner(self) }
+    }
+}
+
+int_type! { "sse4.1", i8x16, i8, 16, m8x16, _mm_set1_epi8, _mm_blendv_epi8 }
+int_type! { "sse4.1", i16x8, i16, 8, m16x8, _mm_set1_epi16, _mm_blendv_epi8 }
+int_type! { "sse4.1", i32x4, i32, 4, m32x4, _mm_set1_epi32, _mm_blendv_epi8 }
+int_type! { "sse4.1", i64x2, i64, 2, m64x2, _mm_set1_epi64x, _mm_blendv_epi8 }
+impl_int! { "sse4.1", i8x16, i8, _mm_set1_epi8, _mm_add_epi8, _mm_sub_epi8, _mm_sll_epi8_fallback, _mm_sra_epi8_fallback, _mm_srl_epi8_fallback, _mm_sllv_epi8_fallback, _mm_srav_epi8_fallback, _mm_adds_epi8, _mm_subs_epi8, _mm_avg_epi8_fallback, _mm_mulhi_epi8_fallback, _mm_div_epi8_fallback }
+impl_int! { "sse4.1", i16x8, i16, _mm_set1_epi16, _mm_add_epi16, _mm_sub_epi16, _mm_sll_epi16, _mm_sra_epi16, _mm_srl_epi16, _mm_sllv_epi16_fallback, _mm_srav_epi16_fallback, _mm_adds_epi16, _mm_subs_epi16, _mm_avg_epi16_fallback, _mm_mulhi_epi16, _mm_div_epi16_fallback }
+impl_int! { "sse4.1", i32x4, i32, _mm_set1_epi32, _mm_add_epi32, _mm_sub_epi32, _mm_sll_epi32, _mm_sra_epi32, _mm_srl_epi32, _mm_sllv_epi32_fallback, _mm_srav_epi32_fallback, _mm_adds_epi32_fallback, _mm_subs_epi32_fallback, _mm_avg_epi32_fallback, _mm_mulhi_epi32_fallback, _mm_div_epi32_fallback }
+impl_int! { "sse4.1", i64x2, i64, _mm_set1_epi64x, _mm_add_epi64, _mm_sub_epi64, _mm_sll_epi64, _mm_sra_epi64_fallback, _mm_srl_epi64, _mm_sllv_epi64_fallback, _mm_srav_epi64_fallback, _mm_adds_epi64_fallback, _mm_subs_epi64_fallback, _mm_avg_epi64_fallback, _mm_mulhi_epi64_fallback, _mm_div_epi64_fallback }
+impl_ord_int! { "sse4.1", i8x16, m8x16, _mm_cmpeq_epi8, _mm_cmpgt_epi8, _mm_max_epi8, _mm_min_epi8 }
+impl_ord_int! { "sse4.1", i16x8, m16x8, _mm_cmpeq_epi16, _mm_cmpgt_epi16, _mm_max_epi16, _mm_min_epi16 }
+impl_ord_int! { "sse4.1", i32x4, m32x4, _mm_cmpeq_epi32, _mm_cmpgt_epi32, _mm_max_epi32, _mm_min_epi32 }
+impl_ord_int! { "sse4.1", i64x2, m64x2, _mm_cmpeq_epi64, _mm_cmpgt_epi64_fallback, _mm_max_epi64_fallback, _mm_min_epi64_fallback }
+impl_int_mul! { "sse4.1", i8x16, i16x8, i32x4, i64x2, _mm_mullo_epi32 }
+impl_shuffle! { "sse4.1", i8x16, identity, identity, reverse_epi8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { "sse4.1", i16x8, identity, identity, reverse_epi16, _mm_unpacklo_epi16, _mm_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { "sse4.1", i32x4, identity, identity, reverse_epi32, _mm_unpacklo_epi32, _mm_unpackhi_epi32, deinterleave_epi32 }
+impl_shuffle! { "sse4.1", i64x2, identity, identity, reverse_epi64, _mm_unpacklo_epi64, _mm_unpackhi_epi64, deinterleave_epi64 }
+impl_cast_bits! { i8x16, u8x16 }
+impl_cast_bits! { i16x8, u16x8 }
+impl_cast_bits! { i32x4, u32x4 }
+impl_cast_bits! { i64x2, u64x2 }
+impl_widen_int! { "sse4.1", i8x16, i16x8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, sign_extend_bits_epi8, truncate_epi16 }
+impl_widen_int! { "sse4.1", i16x8, i32x4, _mm_unpacklo_epi16, _mm_unpackhi_epi16, sign_extend_bits_epi16, truncate_epi32 }
+impl_widen_int! { "sse4.1", i32x4, i64x2, _mm_unpacklo_epi32, _mm_unpackhi_epi32, sign_extend_bits_epi32, truncate_epi64 }
+impl_gather! { i8x16, u8x16, i8x16 }
+impl_gather! { i8x16, [u32x4, i32x4; 4] }
+impl_gather! { i16x8, u16x8, i16x8 }
+impl_gather! { i16x8, [u32x4, i32x4; 2] }
+impl_gather! { i32x4, u32x4, i32x4 }
+impl_gather! { i64x2, u64x2, i64x2 }
+impl_gather! { [i64x2; 2], u32x4, i32x4 }
+
+impl Cast<f32x4> for i32x4 {
+    #[inline(always)]
+    fn cast(self) -> f32x4 {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner(vec: i32x4) -> f32x4 {
+            f32x4(_mm_cvtepi32_ps(vec.0))
+        }
+
+        unsafe { inner(self) }
+    }
+}
+
+impl Cast<f64x2> for i64x2 {
+    #[inline(always)]
+    fn cast(self) -> f64x2 {
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn inner(vec: i64x2) -> f64x2 {
+            f64x2(cvtepi64_pd(vec.0))
+        }
+
+        unsafe { inner(self) }
+    }
+}
+
+int_type! { "sse4.1", m8x16, m8, 16, m8x16, _mm_set1_epi8, _mm_blendv_epi8 }
+int_type! { "sse4.1", m16x8, m16, 8, m16x8, _mm_set1_epi16, _mm_blendv_epi8 }
+int_type! { "sse4.1", m32x4, m32, 4, m32x4, _mm_set1_epi32, _mm_blendv_epi8 }
+int_type! { "sse4.1", m64x2, m64, 2, m64x2, _mm_set1_epi64x, _mm_blendv_epi8 }
+impl_ord_mask! { "sse4.1", m8x16 }
+impl_ord_mask! { "sse4.1", m16x8 }
+impl_ord_mask! { "sse4.1", m32x4 }
+impl_ord_mask! { "sse4.1", m64x2 }
+impl_mask! { "sse4.1", m8x16, movemask_epi8, expand_bits_epi8 }
+impl_mask! { "sse4.1", m16x8, movemask_epi16, expand_bits_epi16 }
+impl_mask! { "sse4.1", m32x4, movemask_epi32, expand_bits_epi32 }
+impl_mask! { "sse4.1", m64x2, movemask_epi64, expand_bits_epi64 }
+impl_shuffle! { "sse4.1", m8x16, identity, identity, reverse_epi8, _mm_unpacklo_epi8, _mm_unpackhi_epi8, deinterleave_epi8 }
+impl_shuffle! { "sse4.1", m16x8, identity, identity, reverse_epi16, _mm_unpacklo_epi16, _mm_unpackhi_epi16, deinterleave_epi16 }
+impl_shuffle! { "sse4.1", m32x4, identity, identity, reverse_epi32, _mm_unpacklo_epi32, _mm_unpackhi_epi32, deinterleave_epi32 }
+impl_shuffle! { "sse4.1", m64x2, identity, identity, reverse_epi64, _mm_unpacklo_epi64, _mm_unpackhi_epi64, deinterleave_epi64 }
@@ -0,0 +1,39 @@
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::Task;
+
+/// Holds the function chosen by a function generated by [`dispatch`](crate::dispatch), so that
+/// later calls can skip the feature detection.
+#[doc(hidden)]
+pub struct __FnCache(AtomicPtr<()>);
+
+impl __FnCache {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> __FnCache {
+        __FnCache(AtomicPtr::new(ptr::null_mut()))
+    }
+
+    /// # Safety
+    ///
+    /// Every call to [`get`](Self::get) and [`set`](Self::set) on the same cache must use the same
+    /// task type, up to lifetimes.
+    #[inline]
+    pub unsafe fn get<T: Task>(&self) -> Option<fn(T) -> T::Result> {
+        let f = self.0.load(Ordering::Relaxed);
+        if f.is_null() {
+            None
+        } else {
+            Some(unsafe { mem::transmute::<*mut (), fn(T) -> T::Result>(f) })
+        }
+    }
+
+    /// # Safety
+    ///
+    /// See [`get`](Self::get).
+    #[inline]
+    pub unsafe fn set<T: Task>(&self, f: fn(T) -> T::Result) {
+        self.0.store(f as *mut (), Ordering::Relaxed);
+    }
+}
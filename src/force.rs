@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once, RwLock};
+
+static ENV: Once = Once::new();
+static GLOBAL: RwLock<Option<String>> = RwLock::new(None);
+
+// The number of overrides currently set, plus one until the environment variable has been read, so
+// that dispatch can skip the lookup when there are none
+static ACTIVE: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    static LOCAL: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn init() {
+    ENV.call_once(|| {
+        if let Ok(name) = env::var("MULTITRACK_ARCH") {
+            set_global(Some(name));
+        }
+        ACTIVE.fetch_sub(1, Ordering::Relaxed);
+    });
+}
+
+fn set_global(name: Option<String>) {
+    let mut global = GLOBAL.write().unwrap_or_else(|err| err.into_inner());
+    match (global.is_some(), name.is_some()) {
+        (false, true) => {
+            ACTIVE.fetch_add(1, Ordering::Relaxed);
+        }
+        (true, false) => {
+            ACTIVE.fetch_sub(1, Ordering::Relaxed);
+        }
+        _ => {}
+    }
+    *global = name;
+}
+
+/// Makes functions generated by [`dispatch`](crate::dispatch) use the architecture with the given
+/// [`Arch::NAME`](crate::Arch::NAME) instead of the best supported one, on every thread. The
+/// override is ignored by functions which were not compiled for that architecture, or if the CPU
+/// does not support it. The first time a name is ignored, a warning is printed to standard error.
+///
+/// The initial override is taken from the `MULTITRACK_ARCH` environment variable.
+pub fn force_arch(name: &str) {
+    init();
+    set_global(Some(name.to_owned()));
+}
+
+/// Removes the override set by [`force_arch`] or the `MULTITRACK_ARCH` environment variable.
+pub fn clear_forced_arch() {
+    init();
+    set_global(None);
+}
+
+/// Calls `f` with the architecture overridden as by [`force_arch`], but only on the current thread.
+/// Takes precedence over the global override, which still applies to functions that ignore this
+/// one.
+pub fn with_forced_arch<R>(name: &str, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<String>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            if prev.is_none() {
+                ACTIVE.fetch_sub(1, Ordering::Relaxed);
+            }
+            LOCAL.with(|local| *local.borrow_mut() = prev);
+        }
+    }
+
+    let prev = LOCAL.with(|local| local.borrow_mut().replace(name.to_owned()));
+    if prev.is_none() {
+        ACTIVE.fetch_add(1, Ordering::Relaxed);
+    }
+    let _restore = Restore(prev);
+
+    f()
+}
+
+/// Returns whether an override may be set, in which case [`__forced_arch`] has to be consulted.
+#[doc(hidden)]
+#[inline]
+pub fn __arch_forced() -> bool {
+    ACTIVE.load(Ordering::Relaxed) != 0
+}
+
+/// Returns the index of the forced architecture among `archs`, given as pairs of
+/// [`Possible::NAME`](crate::Possible::NAME) and [`Possible::supported`](crate::Possible::supported).
+#[doc(hidden)]
+#[allow(clippy::type_complexity)]
+#[inline]
+pub fn __forced_arch(archs: &[(&str, fn() -> bool)]) -> Option<u8> {
+    init();
+    if ACTIVE.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+
+    let find = |name: &str| {
+        let index = archs.iter().position(|&(arch, supported)| {
+            !arch.is_empty() && arch.eq_ignore_ascii_case(name) && supported()
+        });
+        if index.is_none() {
+            warn_ignored(name);
+        }
+        index.map(|index| index as u8)
+    };
+
+    if let Some(index) = LOCAL.with(|local| local.borrow().as_deref().and_then(find)) {
+        return Some(index);
+    }
+
+    let global = GLOBAL.read().unwrap_or_else(|err| err.into_inner());
+    global.as_deref().and_then(find)
+}
+
+#[cold]
+fn warn_ignored(name: &str) {
+    static WARNED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    let mut warned = WARNED.lock().unwrap_or_else(|err| err.into_inner());
+    if !warned
+        .iter()
+        .any(|warned| warned.eq_ignore_ascii_case(name))
+    {
+        eprintln!(
+            "multitrack: ignoring forced architecture `{name}`, which a dispatched function was not \
+             compiled for or the CPU does not support"
+        );
+        warned.push(name.to_owned());
+    }
+}
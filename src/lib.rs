@@ -1,11 +1,37 @@
+#![cfg_attr(feature = "portable", feature(portable_simd))]
+
 extern crate self as multitrack;
 
 pub mod arch;
 pub mod mask;
+pub mod math;
 pub mod simd;
+pub mod slice;
+
+mod cache;
+mod force;
 
+pub use cache::__FnCache;
+pub use force::{__arch_forced, __forced_arch, clear_forced_arch, force_arch, with_forced_arch};
 pub use multitrack_attributes::{dispatch, specialize};
 
+/// Methods using `#[dispatch]` or `#[specialize]` must be in an impl block with `#[methods]`.
+/// Without it, methods taking `self` are rejected:
+///
+/// ```compile_fail
+/// use multitrack::{arch::Scalar, dispatch, Arch};
+///
+/// struct Gain(f32);
+///
+/// impl Gain {
+///     #[dispatch(Scalar)]
+///     fn apply<A: Arch>(&self, x: f32) -> f32 {
+///         x * self.0
+///     }
+/// }
+/// ```
+pub use multitrack_attributes::methods;
+
 use {mask::*, simd::*};
 
 #[allow(non_camel_case_types)]
@@ -16,27 +42,128 @@ where
     Self::m32: Select<Self::f32> + Select<Self::i32> + Select<Self::u32> + Select<Self::m32>,
     Self::m64: Select<Self::f64> + Select<Self::i64> + Select<Self::u64> + Select<Self::m64>,
 {
-    type f32: Simd<Elem = f32, Mask = Self::m32> + Float;
-    type f64: Simd<Elem = f64, Mask = Self::m64> + Float;
+    type f32: Simd<Elem = f32, Mask = Self::m32>
+        + Float<Bits = Self::u32>
+        + Shuffle
+        + Gather<Self::u32, Index = Self::u32, Values = Self::f32, Masks = Self::m32>
+        + Gather<Self::i32, Index = Self::i32, Values = Self::f32, Masks = Self::m32>
+        + Cast<Self::i32>
+        + Cast<Self::u32>
+        + RoundCast<Self::i32>
+        + RoundCast<Self::u32>
+        + Widen<Wide = Self::f64>;
+    type f64: Simd<Elem = f64, Mask = Self::m64>
+        + Float<Bits = Self::u64>
+        + Shuffle
+        + Gather<Self::u64, Index = Self::u64, Values = Self::f64, Masks = Self::m64>
+        + Gather<Self::i64, Index = Self::i64, Values = Self::f64, Masks = Self::m64>
+        + Gather<Self::u32>
+        + Gather<Self::i32>
+        + Cast<Self::i64>
+        + Cast<Self::u64>
+        + RoundCast<Self::i64>
+        + RoundCast<Self::u64>;
 
-    type u8: Simd<Elem = u8, Mask = Self::m8> + Int + Bitwise;
-    type u16: Simd<Elem = u16, Mask = Self::m16> + Int + Bitwise;
-    type u32: Simd<Elem = u32, Mask = Self::m32> + Int + Bitwise;
-    type u64: Simd<Elem = u64, Mask = Self::m64> + Int + Bitwise;
+    type u8: Simd<Elem = u8, Mask = Self::m8>
+        + Int
+        + Bitwise
+        + Shuffle
+        + Gather<Self::u8, Index = Self::u8, Values = Self::u8, Masks = Self::m8>
+        + Gather<Self::i8, Index = Self::i8, Values = Self::u8, Masks = Self::m8>
+        + Gather<Self::u32>
+        + Gather<Self::i32>
+        + Cast<Self::i8>
+        + Widen<Wide = Self::u16>;
+    type u16: Simd<Elem = u16, Mask = Self::m16>
+        + Int
+        + Bitwise
+        + Shuffle
+        + Gather<Self::u16, Index = Self::u16, Values = Self::u16, Masks = Self::m16>
+        + Gather<Self::i16, Index = Self::i16, Values = Self::u16, Masks = Self::m16>
+        + Gather<Self::u32>
+        + Gather<Self::i32>
+        + Cast<Self::i16>
+        + Widen<Wide = Self::u32>;
+    type u32: Simd<Elem = u32, Mask = Self::m32>
+        + Int
+        + Bitwise
+        + Shuffle
+        + Gather<Self::u32, Index = Self::u32, Values = Self::u32, Masks = Self::m32>
+        + Gather<Self::i32, Index = Self::i32, Values = Self::u32, Masks = Self::m32>
+        + Cast<Self::i32>
+        + Cast<Self::f32>
+        + Widen<Wide = Self::u64>;
+    type u64: Simd<Elem = u64, Mask = Self::m64>
+        + Int
+        + Bitwise
+        + Shuffle
+        + Gather<Self::u64, Index = Self::u64, Values = Self::u64, Masks = Self::m64>
+        + Gather<Self::i64, Index = Self::i64, Values = Self::u64, Masks = Self::m64>
+        + Gather<Self::u32>
+        + Gather<Self::i32>
+        + Cast<Self::i64>
+        + Cast<Self::f64>;
 
-    type i8: Simd<Elem = i8, Mask = Self::m8> + Int + Bitwise;
-    type i16: Simd<Elem = i16, Mask = Self::m16> + Int + Bitwise;
-    type i32: Simd<Elem = i32, Mask = Self::m32> + Int + Bitwise;
-    type i64: Simd<Elem = i64, Mask = Self::m64> + Int + Bitwise;
+    type i8: Simd<Elem = i8, Mask = Self::m8>
+        + Int
+        + Bitwise
+        + Shuffle
+        + Gather<Self::u8, Index = Self::u8, Values = Self::i8, Masks = Self::m8>
+        + Gather<Self::i8, Index = Self::i8, Values = Self::i8, Masks = Self::m8>
+        + Gather<Self::u32>
+        + Gather<Self::i32>
+        + Cast<Self::u8>
+        + Widen<Wide = Self::i16>;
+    type i16: Simd<Elem = i16, Mask = Self::m16>
+        + Int
+        + Bitwise
+        + Shuffle
+        + Gather<Self::u16, Index = Self::u16, Values = Self::i16, Masks = Self::m16>
+        + Gather<Self::i16, Index = Self::i16, Values = Self::i16, Masks = Self::m16>
+        + Gather<Self::u32>
+        + Gather<Self::i32>
+        + Cast<Self::u16>
+        + Widen<Wide = Self::i32>;
+    type i32: Simd<Elem = i32, Mask = Self::m32>
+        + Int
+        + Bitwise
+        + Shuffle
+        + Gather<Self::u32, Index = Self::u32, Values = Self::i32, Masks = Self::m32>
+        + Gather<Self::i32, Index = Self::i32, Values = Self::i32, Masks = Self::m32>
+        + Cast<Self::u32>
+        + Cast<Self::f32>
+        + Widen<Wide = Self::i64>;
+    type i64: Simd<Elem = i64, Mask = Self::m64>
+        + Int
+        + Bitwise
+        + Shuffle
+        + Gather<Self::u64, Index = Self::u64, Values = Self::i64, Masks = Self::m64>
+        + Gather<Self::i64, Index = Self::i64, Values = Self::i64, Masks = Self::m64>
+        + Gather<Self::u32>
+        + Gather<Self::i32>
+        + Cast<Self::u64>
+        + Cast<Self::f64>;
 
-    type m8: Simd<Elem = m8, Mask = Self::m8> + Bitwise;
-    type m16: Simd<Elem = m16, Mask = Self::m16> + Bitwise;
-    type m32: Simd<Elem = m32, Mask = Self::m32> + Bitwise;
-    type m64: Simd<Elem = m64, Mask = Self::m64> + Bitwise;
+    type m8: Simd<Elem = m8, Mask = Self::m8> + Bitwise + Mask + Shuffle;
+    type m16: Simd<Elem = m16, Mask = Self::m16> + Bitwise + Mask + Shuffle;
+    type m32: Simd<Elem = m32, Mask = Self::m32> + Bitwise + Mask + Shuffle;
+    type m64: Simd<Elem = m64, Mask = Self::m64> + Bitwise + Mask + Shuffle;
 
+    /// An identifier naming the architecture, such as `sse41` or `emulated4`, which is distinct for
+    /// each architecture and lane count. Used to select the architecture with [`force_arch`].
     const NAME: &'static str;
 
     fn invoke<T: Task>(task: T) -> T::Result;
+
+    /// Hints that the memory at `ptr` will be read soon. `ptr` does not need to be valid.
+    #[inline(always)]
+    fn prefetch<T>(ptr: *const T) {
+        let _ = ptr;
+    }
+
+    /// Orders preceding [`Simd::store_stream`] calls before all later memory accesses.
+    #[inline(always)]
+    fn stream_fence() {}
 }
 
 pub trait Task {
@@ -46,8 +173,15 @@ pub trait Task {
 }
 
 pub trait Possible {
+    /// The name of the architecture, matching [`Arch::NAME`] for the architecture it invokes.
+    /// Architectures with an empty name cannot be selected with [`force_arch`].
+    const NAME: &'static str = "";
+
     fn supported() -> bool;
 
+    /// # Safety
+    ///
+    /// The architecture must be supported by the current CPU.
     unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result;
 
     #[inline]
@@ -59,6 +193,10 @@ pub trait Possible {
         }
     }
 
+    /// # Safety
+    ///
+    /// The returned function must only be called if the architecture is supported by the current
+    /// CPU.
     #[inline]
     unsafe fn specialize_unchecked<T: Task>() -> fn(T) -> T::Result {
         fn invoke<A: Possible + ?Sized, U: Task>(task: U) -> U::Result {
@@ -83,6 +221,9 @@ pub trait Possible {
     }
 }
 
+/// # Safety
+///
+/// Must only be implemented for architectures which are statically known to be supported.
 pub unsafe trait Supported: Possible {
     #[inline]
     fn invoke<T: Task>(task: T) -> T::Result {
@@ -100,14 +241,20 @@ pub unsafe trait Supported: Possible {
 }
 
 #[cfg(test)]
+#[allow(clippy::type_complexity)]
 mod tests {
     use core::fmt::Debug;
     use core::mem;
-    use core::ops::{Add, Div, Mul, Neg, Shl, Shr, Sub};
+    use core::ops::{Add, Div, Mul, Neg, Rem, Shl, Shr, Sub};
     use core::ops::{BitAnd, BitOr, BitXor, Not};
     use std::num::Wrapping;
 
-    use crate::{arch::*, mask::*, simd::*, Arch, Possible, Supported, Task};
+    use crate::{arch::*, mask::*, math, simd::*, slice, Arch, Possible, Supported, Task};
+
+    /// Collects the lanes of `x`, which need not be stored as an array.
+    fn to_vec<S: Simd>(x: S) -> Vec<S::Elem> {
+        (0..S::LANES).map(|i| x[i]).collect()
+    }
 
     fn test_ops<S>(
         type_: &str,
@@ -135,8 +282,8 @@ mod tests {
 
         for x in values.chunks(S::LANES) {
             for (vector, scalar, op) in unary_ops {
-                let res = vector(S::from_slice(x));
-                for (x, out) in x.iter().zip(res.as_slice().iter()) {
+                let res = vector(S::load_partial(x));
+                for (x, out) in x.iter().zip(to_vec(res).iter()) {
                     let scalar = scalar(*x);
                     assert!(
                         eq(&scalar, out),
@@ -154,8 +301,8 @@ mod tests {
         for x in values {
             for ys in values.chunks(S::LANES) {
                 for (vector, scalar, op) in binary_ops {
-                    let res = vector(S::new(*x), S::from_slice(ys));
-                    for (y, out) in ys.iter().zip(res.as_slice().iter()) {
+                    let res = vector(S::new(*x), S::load_partial(ys));
+                    for (y, out) in ys.iter().zip(to_vec(res).iter()) {
                         let scalar = scalar(*x, *y);
                         assert!(
                             eq(&scalar, out),
@@ -171,8 +318,8 @@ mod tests {
                 }
 
                 for (vector, scalar, op) in cmp_ops {
-                    let res = vector(&S::new(*x), &S::from_slice(ys));
-                    for (y, out) in ys.iter().zip(res.as_slice().iter()) {
+                    let res = vector(&S::new(*x), &S::load_partial(ys));
+                    for (y, out) in ys.iter().zip(to_vec(res).iter()) {
                         let scalar = scalar(x, y);
                         assert!(
                             &scalar == out,
@@ -188,8 +335,8 @@ mod tests {
                 }
 
                 for m in mask_values.chunks(S::LANES) {
-                    let res = S::Mask::from_slice(m).select(S::new(*x), S::from_slice(ys));
-                    for ((m, y), out) in m.iter().zip(ys.iter()).zip(res.as_slice().iter()) {
+                    let res = S::Mask::from_slice(m).select(S::new(*x), S::load_partial(ys));
+                    for ((m, y), out) in m.iter().zip(ys.iter()).zip(to_vec(res).iter()) {
                         let scalar = if *m == true.into() { *x } else { *y };
                         assert!(
                             eq(&scalar, out),
@@ -209,8 +356,8 @@ mod tests {
         for xs in values.chunks(S::LANES) {
             for (vector, scalar, op) in shift_ops {
                 for shift in 0..(mem::size_of::<S::Elem>() * 16) {
-                    let res = vector(S::from_slice(xs), shift);
-                    for (x, out) in xs.iter().zip(res.as_slice().iter()) {
+                    let res = vector(S::load_partial(xs), shift);
+                    for (x, out) in xs.iter().zip(to_vec(res).iter()) {
                         let scalar = scalar(*x, shift);
                         assert!(
                             eq(&scalar, out),
@@ -228,6 +375,628 @@ mod tests {
         }
     }
 
+    fn test_ternary<S>(
+        type_: &str,
+        values: &[S::Elem],
+        eq: fn(&S::Elem, &S::Elem) -> bool,
+        ops: &[(
+            fn(S, S, S) -> S,
+            fn(S::Elem, S::Elem, S::Elem) -> S::Elem,
+            &str,
+        )],
+    ) where
+        S: Simd,
+        S::Elem: Copy + Debug,
+    {
+        for x in values {
+            for (ys, zs) in values.chunks(S::LANES).zip(values.rchunks(S::LANES)) {
+                for (vector, scalar, op) in ops {
+                    let res = vector(S::new(*x), S::load_partial(ys), S::load_partial(zs));
+                    for ((y, z), out) in ys.iter().zip(zs.iter()).zip(to_vec(res).iter()) {
+                        let scalar = scalar(*x, *y, *z);
+                        assert!(
+                            eq(&scalar, out),
+                            "expected {}::{}({:?}, {:?}, {:?}) == {:?}, got {:?}",
+                            type_,
+                            op,
+                            *x,
+                            *y,
+                            *z,
+                            scalar,
+                            *out,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn test_reduce<S>(
+        type_: &str,
+        values: &[S::Elem],
+        eq: fn(&S::Elem, &S::Elem) -> bool,
+        ops: &[(fn(S) -> S::Elem, fn(S::Elem, S::Elem) -> S::Elem, &str)],
+    ) where
+        S: Simd,
+        S::Elem: Copy + Debug,
+    {
+        for xs in values.chunks(S::LANES) {
+            // Pad a partial chunk by repeating its last value, and reduce over the padding too
+            let x = S::load_or(xs, S::new(xs[xs.len() - 1]));
+            let xs = to_vec(x);
+            for (vector, scalar, op) in ops {
+                let res = vector(x);
+                let scalar = xs.iter().copied().reduce(scalar).unwrap();
+                assert!(
+                    eq(&scalar, &res),
+                    "expected {}::{}({:?}) == {:?}, got {:?}",
+                    type_,
+                    op,
+                    xs,
+                    scalar,
+                    res,
+                );
+            }
+        }
+    }
+
+    fn test_shuffle<S>(type_: &str, values: &[S::Elem], eq: fn(&S::Elem, &S::Elem) -> bool)
+    where
+        S: Shuffle,
+        S::Elem: Debug,
+    {
+        let lanes = S::LANES;
+        for xs in values.chunks_exact(2 * lanes) {
+            let (a, b) = (S::from_slice(&xs[..lanes]), S::from_slice(&xs[lanes..]));
+
+            let check = |res: S, expected: &dyn Fn(usize) -> usize, op: &str| {
+                for i in 0..lanes {
+                    assert!(
+                        eq(&res[i], &xs[expected(i)]),
+                        "expected {}::{}({:?})[{}] == {:?}, got {:?}",
+                        type_,
+                        op,
+                        xs,
+                        i,
+                        xs[expected(i)],
+                        res[i],
+                    );
+                }
+            };
+
+            // Indices are into the concatenation [a, b]
+            check(a.reverse(), &|i| lanes - 1 - i, "reverse");
+            check(a.rotate_lanes_left::<0>(), &|i| i, "rotate_lanes_left::<0>");
+            check(
+                a.rotate_lanes_left::<1>(),
+                &|i| (i + 1) % lanes,
+                "rotate_lanes_left::<1>",
+            );
+            check(
+                a.rotate_lanes_left::<3>(),
+                &|i| (i + 3) % lanes,
+                "rotate_lanes_left::<3>",
+            );
+            check(
+                a.rotate_lanes_right::<1>(),
+                &|i| (i + lanes - 1) % lanes,
+                "rotate_lanes_right::<1>",
+            );
+            check(
+                a.rotate_lanes_right::<3>(),
+                &|i| (i + 3 * lanes - 3) % lanes,
+                "rotate_lanes_right::<3>",
+            );
+
+            let interleaved = |j: usize| j / 2 + (j % 2) * lanes;
+            check(a.interleave_lo(b), &|i| interleaved(i), "interleave_lo");
+            check(
+                a.interleave_hi(b),
+                &|i| interleaved(lanes + i),
+                "interleave_hi",
+            );
+
+            let (even, odd) = a.deinterleave(b);
+            check(even, &|i| 2 * i, "deinterleave.0");
+            check(odd, &|i| 2 * i + 1, "deinterleave.1");
+
+            check(a.concat_shift::<0>(b), &|i| i, "concat_shift::<0>");
+            check(a.concat_shift::<1>(b), &|i| i + 1, "concat_shift::<1>");
+        }
+    }
+
+    fn test_partial<S>(type_: &str, values: &[S::Elem], eq: fn(&S::Elem, &S::Elem) -> bool)
+    where
+        S: Simd,
+        S::Elem: Debug,
+    {
+        let lanes = S::LANES;
+        let (zero, or) = (S::default(), S::from_slice(&values[lanes..2 * lanes]));
+
+        let check = |res: &[S::Elem], expected: &dyn Fn(usize) -> S::Elem, op: &str, len: usize| {
+            for (i, out) in res.iter().enumerate() {
+                assert!(
+                    eq(out, &expected(i)),
+                    "expected {}::{} with length {} to give {:?} in lane {}, got {:?}",
+                    type_,
+                    op,
+                    len,
+                    expected(i),
+                    i,
+                    out,
+                );
+            }
+        };
+
+        // Slices may be shorter or longer than a vector
+        for len in 0..=lanes + 1 {
+            let slice = &values[..len];
+            let n = len.min(lanes);
+
+            let res = S::load_partial(slice);
+            let expected = |i| if i < n { values[i] } else { zero[i] };
+            check(&to_vec(res), &expected, "load_partial", len);
+
+            let res = S::load_or(slice, or);
+            let expected = |i| if i < n { values[i] } else { or[i] };
+            check(&to_vec(res), &expected, "load_or", len);
+
+            let mut out = (0..len).map(|i| or[i % lanes]).collect::<Vec<S::Elem>>();
+            S::from_slice(&values[..lanes]).store_partial(&mut out);
+            let expected = |i| if i < n { values[i] } else { or[i % lanes] };
+            check(&out, &expected, "store_partial", len);
+        }
+
+        for pattern in [0, 1, 0b1011, 0x9249_2492_4924_9249, u64::MAX] {
+            let bits = pattern & (u64::MAX >> (64 - lanes));
+            let enabled = |i: usize| bits >> i & 1 != 0;
+            let mask = S::Mask::from_bitmask(bits);
+            // Only the enabled lanes need to be in bounds
+            let len = 64 - bits.leading_zeros() as usize;
+
+            let res = S::load_masked(&values[..len], mask);
+            let expected = |i| if enabled(i) { values[i] } else { zero[i] };
+            check(&to_vec(res), &expected, "load_masked", len);
+
+            let mut out = (0..len).map(|i| or[i]).collect::<Vec<S::Elem>>();
+            S::from_slice(&values[..lanes]).store_masked(&mut out, mask);
+            let expected = |i| if enabled(i) { values[i] } else { or[i] };
+            check(&out, &expected, "store_masked", len);
+        }
+    }
+
+    fn test_load_store<A, S>(type_: &str, values: &[S::Elem], eq: fn(&S::Elem, &S::Elem) -> bool)
+    where
+        A: Arch,
+        S: Simd,
+        S::Elem: Debug,
+    {
+        let lanes = S::LANES;
+        let check = |res: &[S::Elem], expected: &[S::Elem], op: &str| {
+            for (out, expected) in res.iter().zip(expected) {
+                assert!(
+                    eq(out, expected),
+                    "expected {}::{} to give {:?}, got {:?}",
+                    type_,
+                    op,
+                    expected,
+                    res,
+                );
+            }
+        };
+
+        let offset = values.as_ptr().align_offset(mem::align_of::<S>());
+        let aligned = &values[offset..offset + lanes];
+        check(
+            &to_vec(S::from_slice_aligned(aligned)),
+            aligned,
+            "from_slice_aligned",
+        );
+
+        let unaligned = &values[1..1 + lanes];
+        let res = unsafe { S::load_ptr(unaligned.as_ptr()) };
+        check(&to_vec(res), unaligned, "load_ptr");
+
+        let mut out = values.to_vec();
+        unsafe { S::from_slice(aligned).store_ptr(out[1..].as_mut_ptr()) };
+        check(&out[1..1 + lanes], aligned, "store_ptr");
+        check(&out[1 + lanes..], &values[1 + lanes..], "store_ptr");
+
+        let mut out = values.to_vec();
+        let offset = out.as_ptr().align_offset(mem::align_of::<S>());
+        unsafe { S::from_slice(unaligned).store_stream(&mut out[offset..offset + lanes]) };
+        A::stream_fence();
+        check(&out[offset..offset + lanes], unaligned, "store_stream");
+
+        // Prefetching is only a hint, and is allowed for any address
+        A::prefetch(values.as_ptr());
+        A::prefetch(values.as_ptr().wrapping_add(1 << 20));
+    }
+
+    fn test_slice<S>(type_: &str)
+    where
+        S: Float,
+        S::Elem: From<u8> + Add<Output = S::Elem> + Mul<Output = S::Elem> + PartialEq + Debug,
+    {
+        let elem = S::Elem::from;
+        let values = (0..100).map(elem).collect::<Vec<S::Elem>>();
+        let sum = |xs: &[S::Elem]| xs.iter().fold(elem(0), |acc, &x| acc + x);
+
+        // Vary the alignment of every slice, and cover lengths shorter than a vector
+        for start in 0..S::LANES {
+            for len in [0, 1, S::LANES - 1, S::LANES + 1, 50] {
+                let a = &values[start..start + len];
+                let b = &values[values.len() - len..];
+                let mut buf = vec![elem(0); start + len];
+                let out = &mut buf[start..];
+
+                slice::map(a, out, |x: S| x * S::new(elem(2)) + S::new(elem(1)));
+                let expected = a.iter().map(|&x| x * elem(2) + elem(1)).collect::<Vec<_>>();
+                assert_eq!(out, expected, "{} map with {} + {}", type_, start, len);
+
+                slice::map2(a, b, out, |x: S, y| x * y);
+                let expected = a.iter().zip(b).map(|(&x, &y)| x * y).collect::<Vec<_>>();
+                assert_eq!(out, expected, "{} map2 with {} + {}", type_, start, len);
+
+                out.copy_from_slice(a);
+                slice::zip_map_in_place(out, b, |x: S, y| x + y);
+                let expected = a.iter().zip(b).map(|(&x, &y)| x + y).collect::<Vec<_>>();
+                assert_eq!(
+                    out, expected,
+                    "{} zip_map_in_place with {} + {}",
+                    type_, start, len
+                );
+
+                out.copy_from_slice(a);
+                slice::for_each_chunk(out, |x: &mut S| *x = *x * *x);
+                let expected = a.iter().map(|&x| x * x).collect::<Vec<_>>();
+                assert_eq!(
+                    out, expected,
+                    "{} for_each_chunk with {} + {}",
+                    type_, start, len
+                );
+
+                let lanes = slice::fold(a, elem(200), Vec::new(), |mut lanes, x: S| {
+                    lanes.extend_from_slice(x.as_slice());
+                    lanes
+                });
+                assert!(lanes.len() % S::LANES == 0);
+                let filtered = lanes.into_iter().filter(|&x| x != elem(200));
+                assert_eq!(
+                    filtered.collect::<Vec<_>>(),
+                    a,
+                    "{} fold with {} + {}",
+                    type_,
+                    start,
+                    len
+                );
+
+                let res = slice::reduce(a, elem(0), |x: S, y| x + y).reduce_sum();
+                assert_eq!(res, sum(a), "{} reduce with {} + {}", type_, start, len);
+            }
+        }
+    }
+
+    fn test_chunks<S>(type_: &str)
+    where
+        S: Float,
+        S::Elem: From<u8> + Add<Output = S::Elem> + Mul<Output = S::Elem> + PartialEq + Debug,
+    {
+        let elem = S::Elem::from;
+        let values = (0..100).map(elem).collect::<Vec<S::Elem>>();
+        let sum = |xs: &[S::Elem]| xs.iter().fold(elem(0), |acc, &x| acc + x);
+
+        for len in [0, S::LANES - 1, 3 * S::LANES + 1, 4 * S::LANES, 99] {
+            let data = &values[..len];
+
+            // Sum with one accumulator per vector in a group, then add the leftovers
+            let mut chunks = slice::chunks::<S, 4>(data);
+            assert_eq!(chunks.len(), len / (4 * S::LANES));
+            let mut acc = [S::new(elem(0)); 4];
+            for group in &mut chunks {
+                for (acc, x) in acc.iter_mut().zip(group) {
+                    *acc += x;
+                }
+            }
+            let mut remainder = chunks.remainder();
+            assert_eq!(remainder.len(), len % (4 * S::LANES) / S::LANES);
+            let mut total = acc.iter().fold(S::new(elem(0)), |acc, &x| acc + x);
+            for x in &mut remainder {
+                total += x;
+            }
+            let res = total.reduce_sum() + sum(remainder.remainder());
+            assert_eq!(res, sum(data), "{} chunks with length {}", type_, len);
+
+            let mut out = data.to_vec();
+            let two = S::new(elem(2));
+            let mut chunks = slice::chunks_mut::<S, 4>(&mut out);
+            for mut group in &mut chunks {
+                let vectors = group.load();
+                group.store(vectors.map(|x| x * two));
+            }
+            let mut remainder = chunks.into_remainder();
+            for mut x in &mut remainder {
+                let [vector] = x.load();
+                x.store([vector * two]);
+            }
+            for x in remainder.into_remainder() {
+                *x = *x * elem(2);
+            }
+            let expected = data.iter().map(|&x| x * elem(2)).collect::<Vec<_>>();
+            assert_eq!(out, expected, "{} chunks_mut with length {}", type_, len);
+        }
+    }
+
+    fn test_gather<S, I>(
+        type_: &str,
+        index_type: &str,
+        table: &[S::Elem],
+        index: fn(usize) -> I::Elem,
+    ) where
+        S: Gather<I>,
+        S::Elem: Debug + PartialEq,
+        I: Simd,
+        I::Elem: GatherIndex,
+    {
+        // Data and index vectors may differ in lane count, so work on all the lanes they cover
+        let (lanes, len) = (S::Values::COUNT * S::LANES, table.len());
+        assert_eq!(lanes, S::Index::COUNT * I::LANES);
+
+        let bits = 0x9249_2492_4924_9249u64;
+        let enabled = |i: usize| bits >> i & 1 != 0;
+        let mut mask = S::Masks::default();
+        for (k, part) in mask.parts_mut().iter_mut().enumerate() {
+            *part = S::Mask::from_bitmask(bits >> (k * S::LANES));
+        }
+
+        let values_from = |elems: &[S::Elem]| {
+            let mut values = S::Values::default();
+            for (part, chunk) in values.parts_mut().iter_mut().zip(elems.chunks(S::LANES)) {
+                *part = S::from_slice(chunk);
+            }
+            values
+        };
+        let index_from = |idx: &[usize]| {
+            let mut res = S::Index::default();
+            for (part, chunk) in res.parts_mut().iter_mut().zip(idx.chunks(I::LANES)) {
+                for (k, &j) in chunk.iter().enumerate() {
+                    part.set(k, index(j));
+                }
+            }
+            res
+        };
+        let elems = |values: S::Values| {
+            values
+                .parts()
+                .iter()
+                .flat_map(|&part| to_vec(part))
+                .collect::<Vec<S::Elem>>()
+        };
+
+        let or = values_from(&table[len - lanes..]);
+        let values = values_from(&table[..lanes]);
+        let fill = table[len - 1];
+
+        for offset in 0..len {
+            let idx = (0..lanes)
+                .map(|i| (offset + 7 * i) % len)
+                .collect::<Vec<usize>>();
+            let all = index_from(&idx);
+            // Disabled lanes get an out-of-bounds index, which must not be accessed
+            let some = index_from(
+                &(0..lanes)
+                    .map(|i| if enabled(i) { idx[i] } else { len })
+                    .collect::<Vec<usize>>(),
+            );
+
+            let expected = idx.iter().map(|&j| table[j]).collect::<Vec<S::Elem>>();
+            assert_eq!(
+                elems(S::gather(table, all)),
+                expected,
+                "{}::gather with {} indices {:?}",
+                type_,
+                index_type,
+                idx,
+            );
+
+            let expected = (0..lanes)
+                .map(|i| {
+                    if enabled(i) {
+                        table[idx[i]]
+                    } else {
+                        elems(or)[i]
+                    }
+                })
+                .collect::<Vec<S::Elem>>();
+            assert_eq!(
+                elems(S::gather_masked(table, some, mask, or)),
+                expected,
+                "{}::gather_masked with {} indices {:?}",
+                type_,
+                index_type,
+                idx,
+            );
+
+            let mut expected = vec![fill; len];
+            for i in 0..lanes {
+                expected[idx[i]] = elems(values)[i];
+            }
+            let mut out = vec![fill; len];
+            S::scatter(values, &mut out, all);
+            assert_eq!(
+                out, expected,
+                "{}::scatter with {} indices {:?}",
+                type_, index_type, idx,
+            );
+
+            let mut expected = vec![fill; len];
+            for i in (0..lanes).filter(|&i| enabled(i)) {
+                expected[idx[i]] = elems(values)[i];
+            }
+            let mut out = vec![fill; len];
+            S::scatter_masked(values, &mut out, some, mask);
+            assert_eq!(
+                out, expected,
+                "{}::scatter_masked with {} indices {:?}",
+                type_, index_type, idx,
+            );
+        }
+
+        // Repeated indices keep the highest lane
+        let mut out = vec![fill; len];
+        S::scatter(values, &mut out, index_from(&vec![0; lanes]));
+        assert_eq!(out[0], elems(values)[lanes - 1], "{}::scatter", type_);
+    }
+
+    fn test_mask_queries<S>(type_: &str, patterns: &[u64])
+    where
+        S: Mask,
+        S::Elem: Copy + Debug + PartialEq + From<bool> + Into<bool>,
+    {
+        for &pattern in patterns {
+            let bits = pattern & (u64::MAX >> (64 - S::LANES));
+            let lanes = (0..S::LANES)
+                .map(|i| (bits >> i & 1 != 0).into())
+                .collect::<Vec<S::Elem>>();
+            let vector = S::from_slice(&lanes);
+            let bools = lanes.iter().map(|&x| x.into()).collect::<Vec<bool>>();
+
+            let from_bitmask = S::from_bitmask(pattern);
+            assert!(
+                to_vec(from_bitmask) == lanes,
+                "expected {}::from_bitmask({:#x}) == {:?}, got {:?}",
+                type_,
+                pattern,
+                lanes,
+                from_bitmask,
+            );
+
+            let queries = [
+                ("to_bitmask", vector.to_bitmask(), bits),
+                ("any", vector.any() as u64, bools.iter().any(|&x| x) as u64),
+                ("all", vector.all() as u64, bools.iter().all(|&x| x) as u64),
+                (
+                    "none",
+                    vector.none() as u64,
+                    !bools.iter().any(|&x| x) as u64,
+                ),
+                (
+                    "count_true",
+                    vector.count_true() as u64,
+                    bits.count_ones() as u64,
+                ),
+                (
+                    "first_true",
+                    vector.first_true().map_or(u64::MAX, |i| i as u64),
+                    bools.iter().position(|&x| x).map_or(u64::MAX, |i| i as u64),
+                ),
+            ];
+            for (op, res, expected) in queries {
+                assert!(
+                    res == expected,
+                    "expected {}::{}({:?}) == {}, got {}",
+                    type_,
+                    op,
+                    lanes,
+                    expected,
+                    res,
+                );
+            }
+        }
+    }
+
+    fn test_cast<S, T>(
+        from: &str,
+        to: &str,
+        values: &[S::Elem],
+        eq: fn(&T::Elem, &T::Elem) -> bool,
+        ops: &[(fn(S) -> T, fn(S::Elem) -> T::Elem, &str)],
+    ) where
+        S: Simd,
+        T: Simd,
+        S::Elem: Copy + Debug,
+        T::Elem: Copy + Debug,
+    {
+        assert!(S::LANES == T::LANES);
+
+        for xs in values.chunks(S::LANES) {
+            for (vector, scalar, op) in ops {
+                let res = vector(S::load_partial(xs));
+                for (x, out) in xs.iter().zip(to_vec(res).iter()) {
+                    let scalar = scalar(*x);
+                    assert!(
+                        eq(&scalar, out),
+                        "expected {}::{}::<{}>({:?}) == {:?}, got {:?}",
+                        from,
+                        op,
+                        to,
+                        *x,
+                        scalar,
+                        *out,
+                    );
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn test_widen<S>(
+        narrow_type: &str,
+        wide_type: &str,
+        values: &[S::Elem],
+        wide_values: &[<S::Wide as Simd>::Elem],
+        eq: fn(&S::Elem, &S::Elem) -> bool,
+        wide_eq: fn(&<S::Wide as Simd>::Elem, &<S::Wide as Simd>::Elem) -> bool,
+        widen: fn(S::Elem) -> <S::Wide as Simd>::Elem,
+        narrow: fn(<S::Wide as Simd>::Elem) -> S::Elem,
+    ) where
+        S: Simd + Widen,
+        S::Wide: Array,
+        S::Elem: Copy + Debug,
+        <S::Wide as Simd>::Elem: Copy + Debug,
+    {
+        for xs in values.chunks(S::LANES) {
+            let parts = S::load_partial(xs).widen();
+            let res = parts
+                .as_ref()
+                .iter()
+                .flat_map(|part| part.as_slice().iter());
+            assert!(parts.as_ref().len() * S::Wide::LANES == S::LANES);
+            for (x, out) in xs.iter().zip(res) {
+                let scalar = widen(*x);
+                assert!(
+                    wide_eq(&scalar, out),
+                    "expected {}::widen({:?}) == {:?}_{}, got {:?}",
+                    narrow_type,
+                    *x,
+                    scalar,
+                    wide_type,
+                    *out,
+                );
+            }
+        }
+
+        for xs in wide_values.chunks(S::LANES) {
+            let mut parts = S::Parts::default();
+            for (part, chunk) in parts.as_mut().iter_mut().zip(xs.chunks(S::Wide::LANES)) {
+                *part = S::Wide::load_partial(chunk);
+            }
+            let res = S::narrow(parts);
+            for (x, out) in xs.iter().zip(to_vec(res).iter()) {
+                let scalar = narrow(*x);
+                assert!(
+                    eq(&scalar, out),
+                    "expected {}::narrow({:?}_{}) == {:?}, got {:?}",
+                    narrow_type,
+                    *x,
+                    wide_type,
+                    scalar,
+                    *out,
+                );
+            }
+        }
+    }
+
     macro_rules! test_float {
         ($type:ident) => {{
             let values = [
@@ -287,92 +1056,767 @@ mod tests {
                     (A::$type::ge, |x, y| (x >= y).into(), "ge"),
                 ],
             );
-        }};
-    }
 
-    macro_rules! test_int {
-        ($type:ident) => {{
-            let values = ($type::MIN..=$type::MAX)
-                .step_by((1 << ($type::BITS as usize - 7)) + 1)
-                .take(64)
-                .collect::<Vec<$type>>();
+            let values = [
+                -2.5,
+                -1.5,
+                -0.5,
+                0.5,
+                1.5,
+                2.5,
+                3.7,
+                -3.7,
+                0.5 - $type::EPSILON / 4.0,
+                -0.5 + $type::EPSILON / 4.0,
+                1.0 + $type::EPSILON,
+                -1.0 - $type::EPSILON,
+                0.5 / $type::EPSILON + 0.5,
+                -0.5 / $type::EPSILON - 0.5,
+                1.0 / $type::EPSILON - 0.5,
+                1.0 / $type::EPSILON + 1.0,
+                $type::MIN_POSITIVE,
+                $type::MIN_POSITIVE / 2.0,
+            ]
+            .into_iter()
+            .chain(values.iter().copied())
+            .cycle()
+            .take(64)
+            .collect::<Vec<$type>>();
 
             test_ops::<A::$type>(
                 stringify!($type),
                 &values,
-                $type::eq,
-                &[
-                    (A::$type::neg, $type::wrapping_neg, "neg"),
-                    (A::$type::not, $type::not, "not"),
-                ],
-                &[
-                    (A::$type::add, $type::wrapping_add, "add"),
-                    (A::$type::sub, $type::wrapping_sub, "sub"),
-                    (A::$type::mul, $type::wrapping_mul, "mul"),
-                    (A::$type::bitand, $type::bitand, "bitand"),
-                    (A::$type::bitor, $type::bitor, "bitor"),
-                    (A::$type::bitxor, $type::bitxor, "bitxor"),
-                    (A::$type::max, $type::max, "max"),
-                    (A::$type::min, $type::min, "min"),
-                ],
-                &[
-                    (A::$type::shl, |x, s| (Wrapping(x) << s).0, "shl"),
-                    (A::$type::shr, |x, s| (Wrapping(x) >> s).0, "shr"),
-                ],
+                float_eq,
                 &[
-                    (A::$type::eq, |x, y| (x == y).into(), "eq"),
-                    (A::$type::ne, |x, y| (x != y).into(), "ne"),
-                    (A::$type::lt, |x, y| (x < y).into(), "lt"),
-                    (A::$type::le, |x, y| (x <= y).into(), "le"),
-                    (A::$type::gt, |x, y| (x > y).into(), "gt"),
-                    (A::$type::ge, |x, y| (x >= y).into(), "ge"),
+                    (A::$type::sqrt, $type::sqrt, "sqrt"),
+                    (A::$type::recip, $type::recip, "recip"),
+                    (A::$type::rsqrt, |x| x.sqrt().recip(), "rsqrt"),
+                    (A::$type::abs, $type::abs, "abs"),
+                    (A::$type::signum, $type::signum, "signum"),
+                    (A::$type::floor, $type::floor, "floor"),
+                    (A::$type::ceil, $type::ceil, "ceil"),
+                    (A::$type::round, $type::round, "round"),
+                    (A::$type::trunc, $type::trunc, "trunc"),
+                    (A::$type::fract, $type::fract, "fract"),
                 ],
+                &[(A::$type::copysign, $type::copysign, "copysign")],
+                &[],
+                &[],
             );
-        }};
-    }
 
-    macro_rules! test_mask {
-        ($type:ident) => {{
-            let values = [false.into(), true.into()]
-                .into_iter()
+            let approx_values = values
+                .iter()
+                .copied()
+                .filter(|x| !x.is_subnormal())
                 .cycle()
                 .take(64)
                 .collect::<Vec<$type>>();
 
             test_ops::<A::$type>(
                 stringify!($type),
-                &values,
-                $type::eq,
-                &[(A::$type::not, $type::not, "not")],
+                &approx_values,
+                |x, y| approx_eq(*x, *y, $type::MIN_POSITIVE),
                 &[
-                    (A::$type::bitand, $type::bitand, "bitand"),
-                    (A::$type::bitor, $type::bitor, "bitor"),
-                    (A::$type::bitxor, $type::bitxor, "bitxor"),
-                    (A::$type::max, $type::max, "max"),
-                    (A::$type::min, $type::min, "min"),
+                    (A::$type::recip_approx, $type::recip, "recip_approx"),
+                    (A::$type::rsqrt_approx, |x| x.sqrt().recip(), "rsqrt_approx"),
                 ],
                 &[],
-                &[
-                    (A::$type::eq, |x, y| (x == y).into(), "eq"),
-                    (A::$type::ne, |x, y| (x != y).into(), "ne"),
-                    (A::$type::lt, |x, y| (x < y).into(), "lt"),
-                    (A::$type::le, |x, y| (x <= y).into(), "le"),
-                    (A::$type::gt, |x, y| (x > y).into(), "gt"),
-                    (A::$type::ge, |x, y| (x >= y).into(), "ge"),
-                ],
+                &[],
+                &[],
             );
-        }};
-    }
-
-    struct TestArch;
 
-    impl Task for TestArch {
-        type Result = ();
-
-        fn run<A: Arch>(self) {
+            // Powers of two, so that sums and products are exact regardless of evaluation order
+            let values = [
+                1.0, -2.0, 0.5, 4.0, -0.25, 8.0, -1.0, 2.0, 0.125, -4.0, 16.0, -0.5, 0.25, -8.0,
+                2.0, 1.0,
+            ]
+            .into_iter()
+            .cycle()
+            .take(64)
+            .collect::<Vec<$type>>();
+
+            test_reduce::<A::$type>(
+                stringify!($type),
+                &values,
+                |x, y| x.to_bits() == y.to_bits(),
+                &[
+                    (A::$type::reduce_sum, $type::add, "reduce_sum"),
+                    (A::$type::reduce_product, $type::mul, "reduce_product"),
+                    (A::$type::reduce_min, min, "reduce_min"),
+                    (A::$type::reduce_max, max, "reduce_max"),
+                ],
+            );
+
+            // Exact for the same reason, so fused and unfused multiply-add agree
+            test_ternary::<A::$type>(
+                stringify!($type),
+                &values,
+                |x, y| x.to_bits() == y.to_bits(),
+                &[(A::$type::mul_add, |x, y, z| x * y + z, "mul_add")],
+            );
+
+            let values = (1..=64)
+                .map(|i| (i as $type).sqrt())
+                .collect::<Vec<$type>>();
+            test_shuffle::<A::$type>(stringify!($type), &values, float_eq);
+            test_partial::<A::$type>(stringify!($type), &values, float_eq);
+            test_load_store::<A, A::$type>(stringify!($type), &values, float_eq);
+        }};
+    }
+
+    macro_rules! test_math {
+        ($type:ident) => {{
+            let values = [
+                -1000.0,
+                -100.0,
+                -20.5,
+                -3.7,
+                -2.0,
+                -1.0,
+                -0.5,
+                -1e-3,
+                -1e-30,
+                -0.0,
+                0.0,
+                1e-30,
+                1e-3,
+                0.1,
+                0.5,
+                1.0,
+                1.5,
+                2.0,
+                3.0,
+                3.7,
+                10.0,
+                20.5,
+                100.0,
+                1000.0,
+                $type::MIN_POSITIVE,
+                $type::MIN_POSITIVE / 2.0,
+                $type::MAX,
+                $type::NEG_INFINITY,
+                $type::INFINITY,
+                $type::NAN,
+            ]
+            .into_iter()
+            .chain((0..130).map(|i| (i as $type - 65.0) * 0.173))
+            .collect::<Vec<$type>>();
+
+            // Trigonometric functions are only accurate within a limited range. The last two inputs
+            // are close to boundaries between binades of the reduced argument.
+            let trig_values = values
+                .iter()
+                .copied()
+                .filter(|x| !x.is_finite() || x.abs() <= 1000.0)
+                .chain([26.954227, -2877.4478])
+                .cycle()
+                .take(160)
+                .collect::<Vec<$type>>();
+
+            // Keep |y * ln(x)| small enough for the tighter error bound of pow to apply
+            let pow_values = [
+                -3.0,
+                -2.0,
+                -0.5,
+                -0.0,
+                0.0,
+                0.1,
+                0.5,
+                1.0,
+                1.5,
+                2.0,
+                3.0,
+                3.7,
+                10.0,
+                20.5,
+                $type::NEG_INFINITY,
+                $type::INFINITY,
+                $type::NAN,
+            ]
+            .into_iter()
+            .cycle()
+            .take(32)
+            .collect::<Vec<$type>>();
+
+            test_ops::<A::$type>(
+                stringify!($type),
+                &values,
+                |x, y| ulp_eq(*x, *y, 2.0, $type::MIN_POSITIVE, $type::EPSILON),
+                &[
+                    (math::exp, $type::exp, "exp"),
+                    (math::exp2, $type::exp2, "exp2"),
+                    (math::ln, $type::ln, "ln"),
+                    (math::log2, $type::log2, "log2"),
+                ],
+                &[],
+                &[],
+                &[],
+            );
+            test_ops::<A::$type>(
+                stringify!($type),
+                &values,
+                |x, y| ulp_eq(*x, *y, 3.0, $type::MIN_POSITIVE, $type::EPSILON),
+                &[
+                    (math::log10, $type::log10, "log10"),
+                    (math::tanh, $type::tanh, "tanh"),
+                    (math::atan, $type::atan, "atan"),
+                ],
+                &[(math::atan2, $type::atan2, "atan2")],
+                &[],
+                &[],
+            );
+            test_ops::<A::$type>(
+                stringify!($type),
+                &trig_values,
+                |x, y| ulp_eq(*x, *y, 1.0, $type::MIN_POSITIVE, $type::EPSILON),
+                &[
+                    (math::sin, $type::sin, "sin"),
+                    (math::cos, $type::cos, "cos"),
+                    (|x| math::sincos(x).0, $type::sin, "sincos.0"),
+                    (|x| math::sincos(x).1, $type::cos, "sincos.1"),
+                ],
+                &[],
+                &[],
+                &[],
+            );
+            test_ops::<A::$type>(
+                stringify!($type),
+                &trig_values,
+                |x, y| ulp_eq(*x, *y, 3.0, $type::MIN_POSITIVE, $type::EPSILON),
+                &[(math::tan, $type::tan, "tan")],
+                &[],
+                &[],
+                &[],
+            );
+            test_ops::<A::$type>(
+                stringify!($type),
+                &pow_values,
+                |x, y| ulp_eq(*x, *y, 6.0, $type::MIN_POSITIVE, $type::EPSILON),
+                &[],
+                &[(math::pow, $type::powf, "pow")],
+                &[],
+                &[],
+            );
+
+            // Results near e^±85, where the error of ln(x) is scaled up the most
+            test_ops::<A::$type>(
+                stringify!($type),
+                &[0.7, 1.4, 250.0, -250.0, 0.71, 1.41, 245.0, -245.0],
+                |x, y| ulp_eq(*x, *y, 6.0, $type::MIN_POSITIVE, $type::EPSILON),
+                &[],
+                &[(math::pow, $type::powf, "pow")],
+                &[],
+                &[],
+            );
+
+            let fast_eq: fn(&$type, &$type) -> bool =
+                |x, y| rel_eq(*x, *y, 2.0f64.powi(-16), $type::MIN_POSITIVE);
+            test_ops::<A::$type>(
+                stringify!($type),
+                &values,
+                fast_eq,
+                &[
+                    (math::exp_fast, $type::exp, "exp_fast"),
+                    (math::exp2_fast, $type::exp2, "exp2_fast"),
+                    (math::ln_fast, $type::ln, "ln_fast"),
+                    (math::log2_fast, $type::log2, "log2_fast"),
+                    (math::log10_fast, $type::log10, "log10_fast"),
+                    (math::tanh_fast, $type::tanh, "tanh_fast"),
+                    (math::atan_fast, $type::atan, "atan_fast"),
+                ],
+                &[(math::atan2_fast, $type::atan2, "atan2_fast")],
+                &[],
+                &[],
+            );
+            test_ops::<A::$type>(
+                stringify!($type),
+                &trig_values,
+                fast_eq,
+                &[
+                    (math::sin_fast, $type::sin, "sin_fast"),
+                    (math::cos_fast, $type::cos, "cos_fast"),
+                    (|x| math::sincos_fast(x).0, $type::sin, "sincos_fast.0"),
+                    (|x| math::sincos_fast(x).1, $type::cos, "sincos_fast.1"),
+                    (math::tan_fast, $type::tan, "tan_fast"),
+                ],
+                &[],
+                &[],
+                &[],
+            );
+            test_ops::<A::$type>(
+                stringify!($type),
+                &pow_values,
+                fast_eq,
+                &[],
+                &[(math::pow_fast, $type::powf, "pow_fast")],
+                &[],
+                &[],
+            );
+        }};
+    }
+
+    macro_rules! test_int {
+        ($type:ident) => {{
+            let values = ($type::MIN..=$type::MAX)
+                .step_by((1 << ($type::BITS as usize - 7)) + 1)
+                .take(64)
+                .collect::<Vec<$type>>();
+
+            let mulhi =
+                |x: $type, y: $type| ((x as i128).wrapping_mul(y as i128) >> $type::BITS) as $type;
+            let div = |x: $type, y: $type| if y == 0 { 0 } else { x.wrapping_div(y) };
+            let rem = |x: $type, y: $type| if y == 0 { x } else { x.wrapping_rem(y) };
+            let abs_diff = |x: $type, y: $type| x.abs_diff(y) as $type;
+            let avg = |x: $type, y: $type| ((x as i128 + y as i128 + 1) >> 1) as $type;
+
+            test_ops::<A::$type>(
+                stringify!($type),
+                &values,
+                $type::eq,
+                &[
+                    (A::$type::neg, $type::wrapping_neg, "neg"),
+                    (A::$type::not, $type::not, "not"),
+                ],
+                &[
+                    (A::$type::add, $type::wrapping_add, "add"),
+                    (A::$type::sub, $type::wrapping_sub, "sub"),
+                    (A::$type::mul, $type::wrapping_mul, "mul"),
+                    (
+                        A::$type::saturating_add,
+                        $type::saturating_add,
+                        "saturating_add",
+                    ),
+                    (
+                        A::$type::saturating_sub,
+                        $type::saturating_sub,
+                        "saturating_sub",
+                    ),
+                    (A::$type::abs_diff, abs_diff, "abs_diff"),
+                    (A::$type::avg, avg, "avg"),
+                    (A::$type::mulhi, mulhi, "mulhi"),
+                    (
+                        A::$type::shl_by,
+                        |x, y| (Wrapping(x) << y as usize).0,
+                        "shl_by",
+                    ),
+                    (
+                        A::$type::shr_by,
+                        |x, y| (Wrapping(x) >> y as usize).0,
+                        "shr_by",
+                    ),
+                    (A::$type::bitand, $type::bitand, "bitand"),
+                    (A::$type::bitor, $type::bitor, "bitor"),
+                    (A::$type::bitxor, $type::bitxor, "bitxor"),
+                    (A::$type::max, $type::max, "max"),
+                    (A::$type::min, $type::min, "min"),
+                ],
+                &[
+                    (A::$type::shl, |x, s| (Wrapping(x) << s).0, "shl"),
+                    (A::$type::shr, |x, s| (Wrapping(x) >> s).0, "shr"),
+                    (
+                        A::$type::rotate_left,
+                        |x, s| x.rotate_left(s as u32),
+                        "rotate_left",
+                    ),
+                    (
+                        A::$type::rotate_right,
+                        |x, s| x.rotate_right(s as u32),
+                        "rotate_right",
+                    ),
+                ],
+                &[
+                    (A::$type::eq, |x, y| (x == y).into(), "eq"),
+                    (A::$type::ne, |x, y| (x != y).into(), "ne"),
+                    (A::$type::lt, |x, y| (x < y).into(), "lt"),
+                    (A::$type::le, |x, y| (x <= y).into(), "le"),
+                    (A::$type::gt, |x, y| (x > y).into(), "gt"),
+                    (A::$type::ge, |x, y| (x >= y).into(), "ge"),
+                ],
+            );
+
+            let values = (0..values.len())
+                .map(|i| values[(i * 37) % values.len()])
+                .collect::<Vec<$type>>();
+
+            test_reduce::<A::$type>(
+                stringify!($type),
+                &values,
+                $type::eq,
+                &[
+                    (A::$type::reduce_sum, $type::wrapping_add, "reduce_sum"),
+                    (
+                        A::$type::reduce_product,
+                        $type::wrapping_mul,
+                        "reduce_product",
+                    ),
+                    (A::$type::reduce_min, $type::min, "reduce_min"),
+                    (A::$type::reduce_max, $type::max, "reduce_max"),
+                    (A::$type::reduce_and, $type::bitand, "reduce_and"),
+                    (A::$type::reduce_or, $type::bitor, "reduce_or"),
+                    (A::$type::reduce_xor, $type::bitxor, "reduce_xor"),
+                ],
+            );
+
+            // Division and saturation have edge cases at zero, -1 and the extremes
+            let edge_values = [
+                0,
+                1,
+                2,
+                3,
+                7,
+                10,
+                100,
+                $type::MAX,
+                $type::MAX - 1,
+                $type::MAX / 3,
+            ]
+            .into_iter()
+            .chain([1, 2, 3, 7, 100].map(|x: $type| x.wrapping_neg()))
+            .chain([$type::MIN, $type::MIN + 1, $type::MIN / 3])
+            .chain(values.iter().copied())
+            .take(64)
+            .collect::<Vec<$type>>();
+
+            test_ops::<A::$type>(
+                stringify!($type),
+                &edge_values,
+                $type::eq,
+                &[],
+                &[
+                    (A::$type::div, div, "div"),
+                    (A::$type::rem, rem, "rem"),
+                    (
+                        A::$type::saturating_add,
+                        $type::saturating_add,
+                        "saturating_add",
+                    ),
+                    (
+                        A::$type::saturating_sub,
+                        $type::saturating_sub,
+                        "saturating_sub",
+                    ),
+                    (A::$type::abs_diff, abs_diff, "abs_diff"),
+                    (A::$type::avg, avg, "avg"),
+                    (A::$type::mulhi, mulhi, "mulhi"),
+                ],
+                &[],
+                &[],
+            );
+
+            let dividends = if $type::BITS == 8 {
+                ($type::MIN..=$type::MAX).collect::<Vec<$type>>()
+            } else {
+                edge_values.clone()
+            };
+            for &divisor in edge_values.iter().chain(&dividends) {
+                if divisor == 0 {
+                    continue;
+                }
+
+                let divisor_ = Divisor::<A::$type>::new(divisor);
+                for chunk in dividends.chunks(A::$type::LANES) {
+                    let quotient = divisor_.divide(A::$type::load_partial(chunk));
+                    let remainder = divisor_.remainder(A::$type::load_partial(chunk));
+                    for (i, &x) in chunk.iter().enumerate() {
+                        assert_eq!(
+                            quotient[i],
+                            div(x, divisor),
+                            "{}: Divisor({:?}).divide({:?})",
+                            stringify!($type),
+                            divisor,
+                            x,
+                        );
+                        assert_eq!(
+                            remainder[i],
+                            rem(x, divisor),
+                            "{}: Divisor({:?}).remainder({:?})",
+                            stringify!($type),
+                            divisor,
+                            x,
+                        );
+                    }
+                }
+            }
+
+            let values = (1..=128)
+                .map(|i: u8| (i as $type).wrapping_mul(0x9E3779B97F4A7C15u64 as $type))
+                .collect::<Vec<$type>>();
+            test_shuffle::<A::$type>(stringify!($type), &values, $type::eq);
+            test_partial::<A::$type>(stringify!($type), &values, $type::eq);
+            test_load_store::<A, A::$type>(stringify!($type), &values, $type::eq);
+        }};
+    }
+
+    macro_rules! test_mask {
+        ($type:ident) => {{
+            let values = [false.into(), true.into()]
+                .into_iter()
+                .cycle()
+                .take(64)
+                .collect::<Vec<$type>>();
+
+            test_ops::<A::$type>(
+                stringify!($type),
+                &values,
+                $type::eq,
+                &[(A::$type::not, $type::not, "not")],
+                &[
+                    (A::$type::bitand, $type::bitand, "bitand"),
+                    (A::$type::bitor, $type::bitor, "bitor"),
+                    (A::$type::bitxor, $type::bitxor, "bitxor"),
+                    (A::$type::max, $type::max, "max"),
+                    (A::$type::min, $type::min, "min"),
+                ],
+                &[],
+                &[
+                    (A::$type::eq, |x, y| (x == y).into(), "eq"),
+                    (A::$type::ne, |x, y| (x != y).into(), "ne"),
+                    (A::$type::lt, |x, y| (x < y).into(), "lt"),
+                    (A::$type::le, |x, y| (x <= y).into(), "le"),
+                    (A::$type::gt, |x, y| (x > y).into(), "gt"),
+                    (A::$type::ge, |x, y| (x >= y).into(), "ge"),
+                ],
+            );
+
+            let values = (0..64)
+                .map(|i| (i < 32 || i % 3 == 0).into())
+                .collect::<Vec<$type>>();
+
+            test_reduce::<A::$type>(
+                stringify!($type),
+                &values,
+                $type::eq,
+                &[
+                    (A::$type::reduce_and, $type::bitand, "reduce_and"),
+                    (A::$type::reduce_or, $type::bitor, "reduce_or"),
+                    (A::$type::reduce_xor, $type::bitxor, "reduce_xor"),
+                ],
+            );
+
+            test_mask_queries::<A::$type>(
+                stringify!($type),
+                &[
+                    0,
+                    u64::MAX,
+                    1,
+                    1 << (A::$type::LANES - 1),
+                    0x5555555555555555,
+                    0xAAAAAAAAAAAAAAAA,
+                    0x0123456789ABCDEF,
+                    0xFEDCBA9876543210,
+                    !1,
+                ],
+            );
+
+            let values = (0..128)
+                .map(|i| (i % 3 == 0).into())
+                .collect::<Vec<$type>>();
+            test_shuffle::<A::$type>(stringify!($type), &values, $type::eq);
+            test_partial::<A::$type>(stringify!($type), &values, $type::eq);
+            test_load_store::<A, A::$type>(stringify!($type), &values, $type::eq);
+        }};
+    }
+
+    macro_rules! test_gather {
+        ($type:ident, $($index:ident),*) => {{
+            $(
+                // Past `u8::MAX` where the index type can reach it, which 32-bit indices into
+                // narrow data must handle
+                let len = if mem::size_of::<$index>() < 4 { 100 } else { 300 };
+                let table = (0..len).map(|i| i as $type).collect::<Vec<$type>>();
+
+                test_gather::<A::$type, A::$index>(
+                    stringify!($type),
+                    stringify!($index),
+                    &table,
+                    |i| i as $index,
+                );
+            )*
+        }};
+    }
+
+    macro_rules! cast_float_values {
+        ($type:ident) => {{
+            [
+                -1.0,
+                -0.5,
+                -0.0,
+                0.0,
+                0.5,
+                1.0,
+                1.5,
+                2.5,
+                -2.5,
+                3.7,
+                -3.7,
+                1.0 + 1.0 / (1u64 << 30) as f64,
+                1e-40,
+                1e6 + 0.5,
+                16777217.0,
+                2147483520.0,
+                2147483647.5,
+                2147483648.0,
+                -2147483648.0,
+                -2147483904.0,
+                4294967040.0,
+                4294967295.5,
+                4294967296.0,
+                9223371487098961920.0,
+                9223372036854774784.0,
+                9223372036854775808.0,
+                -9223372036854775808.0,
+                -9223372036854777856.0,
+                18446742974197923840.0,
+                18446744073709549568.0,
+                18446744073709551616.0,
+                1e30,
+                -1e30,
+                1e300,
+                -1e300,
+            ]
+            .into_iter()
+            .map(|x: f64| x as $type)
+            .chain([
+                $type::MIN,
+                $type::MAX,
+                $type::EPSILON,
+                $type::NEG_INFINITY,
+                $type::INFINITY,
+                $type::NAN,
+            ])
+            .cycle()
+            .take(64)
+            .collect::<Vec<$type>>()
+        }};
+    }
+
+    macro_rules! cast_int_values {
+        ($type:ident) => {{
+            [$type::MIN, $type::MAX, 0, 1]
+                .into_iter()
+                .chain(($type::MIN..=$type::MAX).step_by((1 << ($type::BITS as usize - 7)) + 1))
+                .take(64)
+                .collect::<Vec<$type>>()
+        }};
+    }
+
+    macro_rules! test_float_cast {
+        ($float:ident, $($int:ident),*) => {{
+            let values = cast_float_values!($float);
+
+            $(
+                test_cast::<A::$float, A::$int>(
+                    stringify!($float),
+                    stringify!($int),
+                    &values,
+                    $int::eq,
+                    &[
+                        (A::$float::cast, |x| x as $int, "cast"),
+                        (A::$float::round_cast, |x| x.round_ties_even() as $int, "round_cast"),
+                    ],
+                );
+
+                test_cast::<A::$int, A::$float>(
+                    stringify!($int),
+                    stringify!($float),
+                    &cast_int_values!($int),
+                    |x, y| x.to_bits() == y.to_bits(),
+                    &[(A::$int::cast, |x| x as $float, "cast")],
+                );
+            )*
+        }};
+    }
+
+    macro_rules! test_int_cast {
+        ($from:ident, $to:ident) => {{
+            test_cast::<A::$from, A::$to>(
+                stringify!($from),
+                stringify!($to),
+                &cast_int_values!($from),
+                $to::eq,
+                &[(A::$from::cast, |x| x as $to, "cast")],
+            );
+        }};
+    }
+
+    macro_rules! test_int_widen {
+        ($narrow:ident, $wide:ident) => {{
+            test_widen::<A::$narrow>(
+                stringify!($narrow),
+                stringify!($wide),
+                &cast_int_values!($narrow),
+                &cast_int_values!($wide),
+                $narrow::eq,
+                $wide::eq,
+                |x| x as $wide,
+                |x| x as $narrow,
+            );
+        }};
+    }
+
+    fn float_eq<T: Into<f64> + Copy>(x: &T, y: &T) -> bool {
+        let (x, y): (f64, f64) = ((*x).into(), (*y).into());
+        x.to_bits() == y.to_bits() || (x.is_nan() && y.is_nan())
+    }
+
+    fn approx_eq<T: Into<f64>>(expected: T, actual: T, min_positive: T) -> bool {
+        let (expected, actual) = (expected.into(), actual.into());
+        if expected.is_nan() || actual.is_nan() {
+            return expected.is_nan() && actual.is_nan();
+        }
+
+        // Allow the relative error of the approximate reciprocal instructions, which also flush
+        // subnormal results to zero
+        expected == actual
+            || (expected - actual).abs() <= 1.5 * 2.0f64.powi(-12) * expected.abs()
+            || (expected.abs() < min_positive.into() && actual == 0.0)
+    }
+
+    fn ulp_eq<T: Into<f64>>(
+        expected: T,
+        actual: T,
+        ulps: f64,
+        min_positive: T,
+        epsilon: T,
+    ) -> bool {
+        let (expected, actual) = (expected.into(), actual.into());
+        if expected.is_nan() || actual.is_nan() {
+            return expected.is_nan() && actual.is_nan();
+        }
+
+        // The spacing of values near the expected result, which is constant below the normal range
+        let magnitude = expected.abs().max(min_positive.into());
+        let ulp = 2.0f64.powi(magnitude.log2().floor() as i32) * epsilon.into();
+        expected == actual || (expected - actual).abs() <= ulps * ulp
+    }
+
+    fn rel_eq<T: Into<f64>>(expected: T, actual: T, max_error: f64, min_positive: T) -> bool {
+        let (expected, actual) = (expected.into(), actual.into());
+        if expected.is_nan() || actual.is_nan() {
+            return expected.is_nan() && actual.is_nan();
+        }
+
+        let magnitude = expected.abs().max(min_positive.into());
+        expected == actual || (expected - actual).abs() <= max_error * magnitude
+    }
+
+    struct TestArch;
+
+    impl Task for TestArch {
+        type Result = ();
+
+        fn run<A: Arch>(self) {
             test_float!(f32);
             test_float!(f64);
 
+            test_math!(f32);
+            test_math!(f64);
+
+            test_slice::<A::f32>("f32");
+            test_slice::<A::f64>("f64");
+            test_chunks::<A::f32>("f32");
+            test_chunks::<A::f64>("f64");
+
             test_int!(u8);
             test_int!(u16);
             test_int!(u32);
@@ -387,6 +1831,47 @@ mod tests {
             test_mask!(m16);
             test_mask!(m32);
             test_mask!(m64);
+
+            test_gather!(f32, u32, i32);
+            test_gather!(f64, u64, i64, u32, i32);
+            test_gather!(u8, u8, i8, u32, i32);
+            test_gather!(u16, u16, i16, u32, i32);
+            test_gather!(u32, u32, i32);
+            test_gather!(u64, u64, i64, u32, i32);
+            test_gather!(i8, u8, i8, u32, i32);
+            test_gather!(i16, u16, i16, u32, i32);
+            test_gather!(i32, u32, i32);
+            test_gather!(i64, u64, i64, u32, i32);
+
+            test_float_cast!(f32, i32, u32);
+            test_float_cast!(f64, i64, u64);
+
+            test_int_cast!(u8, i8);
+            test_int_cast!(u16, i16);
+            test_int_cast!(u32, i32);
+            test_int_cast!(u64, i64);
+            test_int_cast!(i8, u8);
+            test_int_cast!(i16, u16);
+            test_int_cast!(i32, u32);
+            test_int_cast!(i64, u64);
+
+            test_int_widen!(u8, u16);
+            test_int_widen!(u16, u32);
+            test_int_widen!(u32, u64);
+            test_int_widen!(i8, i16);
+            test_int_widen!(i16, i32);
+            test_int_widen!(i32, i64);
+
+            test_widen::<A::f32>(
+                "f32",
+                "f64",
+                &cast_float_values!(f32),
+                &cast_float_values!(f64),
+                float_eq,
+                float_eq,
+                |x| x as f64,
+                |x| x as f32,
+            );
         }
     }
 
@@ -395,6 +1880,437 @@ mod tests {
         Scalar::invoke(TestArch);
     }
 
+    #[test]
+    fn emulated() {
+        Emulated::<2>::invoke(TestArch);
+        Emulated::<3>::invoke(TestArch);
+        Emulated::<13>::invoke(TestArch);
+        Emulated::<16>::invoke(TestArch);
+    }
+
+    #[test]
+    fn arch_names() {
+        assert_eq!(Scalar::NAME, "scalar");
+        assert_eq!(Emulated::<4>::NAME, "emulated4");
+        assert_eq!(Emulated::<64>::NAME, "emulated64");
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        assert_eq!(Sse41::NAME, "sse41");
+        #[cfg(feature = "portable")]
+        assert_eq!(Portable::<16>::NAME, "portable16");
+    }
+
+    #[test]
+    fn forced_arch() {
+        let archs: [(&str, fn() -> bool); 3] = [
+            (Emulated::<4>::NAME, || false),
+            (Emulated::<2>::NAME, || true),
+            (Scalar::NAME, || true),
+        ];
+
+        crate::with_forced_arch("scalar", || {
+            assert_eq!(crate::__forced_arch(&archs), Some(2));
+            crate::with_forced_arch("Emulated2", || {
+                assert_eq!(crate::__forced_arch(&archs), Some(1));
+            });
+            crate::with_forced_arch("emulated", || {
+                assert_eq!(crate::__forced_arch(&archs), None);
+            });
+            assert_eq!(crate::__forced_arch(&archs), Some(2));
+        });
+        crate::with_forced_arch("sse2", || {
+            assert_eq!(crate::__forced_arch(&archs), None);
+        });
+    }
+
+    mod attributes {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        use super::*;
+
+        struct Gain {
+            gain: f32,
+        }
+
+        #[crate::methods]
+        impl Gain {
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn new<A: Arch>(gain: f32) -> Self {
+                Gain { gain }
+            }
+
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn scale<A: Arch>(&self, x: f32) -> f32 {
+                (A::f32::new(x) * A::f32::new(self.gain))[0]
+            }
+
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn set<A: Arch>(&mut self, gain: f32) -> usize {
+                self.gain = gain;
+                self.lanes::<A>()
+            }
+
+            #[crate::specialize]
+            fn lanes<A: Arch>(&self) -> usize {
+                A::f32::LANES
+            }
+
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn into_gain<A: Arch>(self) -> f32 {
+                A::f32::new(self.gain)[0]
+            }
+        }
+
+        struct Wrapper<T>(T);
+
+        #[crate::methods]
+        impl<T: Copy + Into<f32>> Wrapper<T> {
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn dot<A: Arch>(&self, xs: &[T]) -> f32 {
+                let sum = xs
+                    .iter()
+                    .map(|&x| A::f32::new(x.into()))
+                    .fold(A::f32::new(0.0), Add::add);
+                sum[0] * self.0.into()
+            }
+        }
+
+        trait Process<T> {
+            fn process(&mut self, buf: &mut [T]) -> usize;
+        }
+
+        #[crate::methods]
+        impl<'a, T> Process<T> for Wrapper<&'a T>
+        where
+            T: Copy + Mul<Output = T> + 'a,
+        {
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn process<A: Arch>(&mut self, buf: &mut [T]) -> usize {
+                for x in buf.iter_mut() {
+                    *x = *x * *self.0;
+                }
+                A::f32::LANES
+            }
+        }
+
+        #[test]
+        fn methods() {
+            let mut gain = Gain::new(2.0);
+            assert_eq!(gain.scale(3.0), 6.0);
+            assert_eq!(gain.set(4.0), 4);
+            assert_eq!(gain.scale(3.0), 12.0);
+            assert_eq!(gain.into_gain(), 4.0);
+
+            assert_eq!(Wrapper(2u8).dot(&[1, 2, 3]), 12.0);
+
+            let mut buf = [1, 2, 3];
+            assert_eq!(Wrapper(&3).process(&mut buf), 4);
+            assert_eq!(buf, [3, 6, 9]);
+        }
+
+        #[crate::methods]
+        impl Gain {
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn louder<'a, A: Arch>(&'a mut self, other: &'a mut Gain) -> &'a mut Gain {
+                if A::f32::new(self.gain)[0] >= other.gain {
+                    self
+                } else {
+                    other
+                }
+            }
+        }
+
+        #[crate::dispatch(Emulated<4>, Scalar)]
+        fn heaviest<'a, 'b: 'a, A: Arch>(xs: &'a [f32], ys: &'b [f32], zs: &'b [f32]) -> &'a [f32] {
+            heavier::<A>(xs, heavier::<A>(ys, zs))
+        }
+
+        #[crate::specialize]
+        fn heavier<'a, A: Arch>(a: &'a [f32], b: &'a [f32]) -> &'a [f32] {
+            if sum::<A>(a.iter().copied()) >= sum::<A>(b.iter().copied()) {
+                a
+            } else {
+                b
+            }
+        }
+
+        #[crate::dispatch(Emulated<4>, Scalar)]
+        fn total<'a, A: Arch, T: Copy + Into<f32> + 'a>(xs: &'a [T]) -> f32 {
+            sum::<A>(xs.iter().map(|&x| x.into()))
+        }
+
+        #[test]
+        fn lifetimes() {
+            let mut quiet = Gain::new(1.0);
+            let mut loud = Gain::new(2.0);
+            quiet.louder(&mut loud).gain = 3.0;
+            assert_eq!(loud.gain, 3.0);
+
+            assert_eq!(heaviest(&[1.0], &[2.0, 3.0], &[4.0]), &[2.0, 3.0]);
+            assert_eq!(heaviest(&[6.0], &[2.0, 3.0], &[4.0]), &[6.0]);
+            assert_eq!(total(&[1u8, 2, 3]), 6.0);
+        }
+
+        #[crate::dispatch(Emulated<4>, Scalar)]
+        fn fir<A: Arch, const TAPS: usize, const SHIFT: u32>(
+            taps: &[f32; TAPS],
+            xs: &[f32],
+        ) -> f32 {
+            dot::<A, TAPS>(taps, &xs[..TAPS]) * (1 << SHIFT) as f32
+        }
+
+        #[crate::specialize]
+        fn dot<A: Arch, const N: usize>(a: &[f32; N], b: &[f32]) -> f32 {
+            let sum = a
+                .iter()
+                .zip(b)
+                .fold(A::f32::new(0.0), |sum, (&x, &y)| sum + A::f32::new(x * y));
+            sum[0]
+        }
+
+        struct Delay<T, const LEN: usize> {
+            buf: [T; LEN],
+        }
+
+        #[crate::methods]
+        impl<T: Copy + Default, const LEN: usize> Delay<T, LEN> {
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn first<A: Arch, const K: usize>(&self) -> [T; K] {
+                let mut out = [T::default(); K];
+                out.copy_from_slice(&self.buf[..K]);
+                out
+            }
+
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn ends<A: Arch, const K: usize>(&self) -> ([T; K], [T; K]) {
+                (self.first(), self.last::<A, K>())
+            }
+
+            #[crate::specialize]
+            fn last<A: Arch, const K: usize>(&self) -> [T; K] {
+                let mut out = [T::default(); K];
+                out.copy_from_slice(&self.buf[LEN - K..]);
+                out
+            }
+        }
+
+        #[test]
+        fn const_generics() {
+            assert_eq!(fir::<3, 1>(&[1.0, 2.0, 3.0], &[1.0, 1.0, 1.0, 5.0]), 12.0);
+
+            let delay = Delay {
+                buf: [1u8, 2, 3, 4],
+            };
+            assert_eq!(delay.first::<2>(), [1, 2]);
+            assert_eq!(delay.ends::<1>(), ([1], [4]));
+        }
+
+        struct Point {
+            x: f32,
+            y: f32,
+        }
+
+        #[crate::dispatch(Emulated<4>, Scalar)]
+        fn weigh<A: Arch>(
+            (a, b): (f32, f32),
+            Point { x, y }: Point,
+            _: u32,
+            mut scale: f32,
+        ) -> f32 {
+            scale *= 2.0;
+            (A::f32::new(a * x + b * y) * A::f32::new(scale))[0]
+        }
+
+        #[allow(clippy::toplevel_ref_arg)]
+        #[crate::dispatch(Emulated<4>, Scalar)]
+        fn count<A: Arch>(ref name: String, ref mut total: usize) -> usize {
+            *total += name.len();
+            *total * A::f32::LANES
+        }
+
+        #[crate::dispatch(Emulated<4>, Scalar)]
+        fn apply<A: Arch>(
+            buf: &mut [f32],
+            f: impl Fn(f32) -> f32,
+            mut offsets: impl Iterator<Item = f32>,
+        ) {
+            for x in buf.iter_mut() {
+                *x = (A::f32::new(f(*x)) + A::f32::new(offsets.next().unwrap_or(0.0)))[0];
+            }
+        }
+
+        #[crate::dispatch(Emulated<4>, Scalar)]
+        fn twice<A: Arch>(f: Box<impl Fn(f32) -> f32>, x: f32) -> f32 {
+            A::f32::new(f(f(x)))[0]
+        }
+
+        #[crate::specialize]
+        fn sum<A: Arch>(xs: impl IntoIterator<Item = f32>) -> f32 {
+            xs.into_iter()
+                .map(A::f32::new)
+                .fold(A::f32::new(0.0), Add::add)[0]
+        }
+
+        struct Acc(f32);
+
+        #[crate::methods]
+        impl Acc {
+            #[crate::dispatch(Emulated<4>, Scalar)]
+            fn add<A: Arch>(
+                mut self,
+                xs: impl IntoIterator<Item = f32>,
+                (k, _): (f32, f32),
+            ) -> Self {
+                self.0 += sum::<A>(xs) * k;
+                self
+            }
+        }
+
+        #[test]
+        fn arg_patterns() {
+            assert_eq!(weigh((1.0, 2.0), Point { x: 3.0, y: 4.0 }, 0, 0.5), 11.0);
+            assert_eq!(count("abc".to_string(), 1), 16);
+
+            let mut buf = [1.0, 2.0];
+            apply(&mut buf, |x| x * 10.0, [5.0].into_iter());
+            assert_eq!(buf, [15.0, 20.0]);
+
+            assert_eq!(twice(Box::new(|x| x + 1.0), 1.0), 3.0);
+            assert_eq!(Acc(1.0).add([1.0, 2.0], (2.0, 0.0)).0, 7.0);
+        }
+
+        static PROBES: AtomicUsize = AtomicUsize::new(0);
+
+        // Runs on Scalar, but counts how often dispatch checks whether it is supported
+        struct Probed;
+
+        impl Possible for Probed {
+            fn supported() -> bool {
+                PROBES.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+
+            unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+                Scalar::invoke(task)
+            }
+        }
+
+        #[crate::dispatch(Probed, Scalar)]
+        fn probed<A: Arch>(x: f32) -> f32 {
+            A::f32::new(x)[0]
+        }
+
+        #[crate::dispatch(Probed, Scalar)]
+        fn probed_generic<A: Arch, T: Into<f32>>(x: T) -> f32 {
+            A::f32::new(x.into())[0]
+        }
+
+        #[test]
+        fn probes_once() {
+            for i in 0..4 {
+                assert_eq!(probed(i as f32), i as f32);
+            }
+            assert_eq!(PROBES.load(Ordering::Relaxed), 1);
+
+            // Every instantiation of a generic function shares the cached choice
+            for i in 0..4 {
+                assert_eq!(probed_generic(i as u8), i as f32);
+                assert_eq!(probed_generic(i as f32), i as f32);
+            }
+            assert_eq!(PROBES.load(Ordering::Relaxed), 2);
+        }
+
+        // Run on Emulated<4> and Scalar under names that no other test forces, since the global
+        // override is shared with tests running concurrently
+        struct Wide;
+
+        impl Possible for Wide {
+            const NAME: &'static str = "wide";
+
+            fn supported() -> bool {
+                true
+            }
+
+            unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+                Emulated::<4>::invoke(task)
+            }
+        }
+
+        struct Narrow;
+
+        impl Possible for Narrow {
+            const NAME: &'static str = "narrow";
+
+            fn supported() -> bool {
+                true
+            }
+
+            unsafe fn invoke_unchecked<T: Task>(task: T) -> T::Result {
+                Scalar::invoke(task)
+            }
+        }
+
+        unsafe impl Supported for Narrow {}
+
+        struct Missing;
+
+        impl Possible for Missing {
+            const NAME: &'static str = "missing";
+
+            fn supported() -> bool {
+                false
+            }
+
+            unsafe fn invoke_unchecked<T: Task>(_task: T) -> T::Result {
+                unreachable!()
+            }
+        }
+
+        #[crate::dispatch(Missing, Wide, Narrow)]
+        fn forced_lanes<A: Arch>() -> usize {
+            A::f32::LANES
+        }
+
+        #[crate::dispatch(Missing, Wide, Narrow)]
+        fn forced_lanes_generic<A: Arch, T>(_value: T) -> usize {
+            A::f32::LANES
+        }
+
+        #[test]
+        fn forced_dispatch() {
+            let lanes = || (forced_lanes(), forced_lanes_generic(0u8));
+
+            assert_eq!(lanes(), (4, 4));
+
+            crate::force_arch("narrow");
+            assert_eq!(lanes(), (1, 1));
+            crate::with_forced_arch("WIDE", || {
+                assert_eq!(lanes(), (4, 4));
+                // Unsupported or unknown architectures fall back to the global override
+                crate::with_forced_arch("missing", || assert_eq!(lanes(), (1, 1)));
+                crate::with_forced_arch("unknown", || assert_eq!(lanes(), (1, 1)));
+                assert_eq!(lanes(), (4, 4));
+                // Other threads only see the global override
+                std::thread::spawn(move || assert_eq!(lanes(), (1, 1)))
+                    .join()
+                    .unwrap();
+            });
+            assert_eq!(lanes(), (1, 1));
+
+            crate::clear_forced_arch();
+            assert_eq!(lanes(), (4, 4));
+            crate::with_forced_arch("narrow", || assert_eq!(lanes(), (1, 1)));
+            assert_eq!(lanes(), (4, 4));
+        }
+    }
+
+    #[cfg(feature = "portable")]
+    #[test]
+    fn portable() {
+        Portable::<1>::invoke(TestArch);
+        Portable::<4>::invoke(TestArch);
+        Portable::<16>::invoke(TestArch);
+    }
+
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     mod x86 {
         use super::*;
@@ -404,9 +2320,24 @@ mod tests {
             Sse2::invoke(TestArch);
         }
 
+        #[test]
+        fn sse41() {
+            Sse41::try_invoke(TestArch);
+        }
+
+        #[test]
+        fn avx() {
+            Avx::try_invoke(TestArch);
+        }
+
         #[test]
         fn avx2() {
             Avx2::try_invoke(TestArch);
         }
+
+        #[test]
+        fn avx512() {
+            Avx512::try_invoke(TestArch);
+        }
     }
 }
@@ -0,0 +1,932 @@
+//! Elementary functions for float vectors.
+//!
+//! Every function is generic over the vector type, so it can be called on `A::f32` or `A::f64`
+//! for any [`Arch`](crate::Arch).
+//!
+//! Error bounds are given in units in the last place (ULP) of the exact result, and hold for all
+//! finite inputs unless stated otherwise. They are the same for `f32` and `f64` and for every
+//! architecture. Infinities, NaNs and signed zeros are handled as by the corresponding functions
+//! in `std`.
+//!
+//! Each function also has a `_fast` variant which uses lower-degree approximations. These have a
+//! relative error of at most `2^-16` for results in the normal range, and handle special values in
+//! the same way.
+
+use core::f64::consts::{FRAC_2_PI, LN_2, LOG10_E, LOG2_E, PI, SQRT_2};
+use core::mem;
+
+use crate::simd::{Float, LanesEq, Select};
+
+/// Element types supported by the functions in this module, i.e. `f32` and `f64`.
+///
+/// The associated constants are the precision-dependent parameters of the approximations.
+pub trait Real: Copy {
+    /// Number of explicitly stored mantissa bits.
+    const MANTISSA_BITS: usize;
+    /// Exponent bias of the representation.
+    const EXP_BIAS: f64;
+    /// Largest `x` for which `2^x` is finite.
+    const MAX_EXP2: f64;
+    /// Largest `x` for which `2^x` rounds to zero.
+    const MIN_EXP2: f64;
+
+    /// `ln(2)` split so that multiplying the high part by an exponent is exact.
+    const LN2_HI: f64;
+    const LN2_LO: f64;
+    /// `log10(2)` split so that multiplying the high part by an exponent is exact.
+    const LOG10_2_HI: f64;
+    const LOG10_2_LO: f64;
+    /// `pi / 2` split into four parts, the first three of which can be multiplied exactly by the
+    /// index of any quadrant within the accurate range of the trigonometric functions.
+    const FRAC_PI_2_PARTS: [f64; 4];
+    /// Rounding errors of `pi`, `pi / 2` and `pi / 4`.
+    const PI_LO: f64;
+    const FRAC_PI_2_LO: f64;
+    const FRAC_PI_4_LO: f64;
+
+    /// Coefficients of `(exp(r) - 1 - r) / r^2` on `[-ln(2) / 2, ln(2) / 2]`.
+    const EXP_POLY: &'static [f64];
+    /// Coefficients of `(ln((1 + s) / (1 - s)) - 2s) / s^3` in `s^2`, for
+    /// `s^2 <= ((sqrt(2) - 1) / (sqrt(2) + 1))^2`.
+    const LN_POLY: &'static [f64];
+    /// Coefficients of `(sin(r) - r) / r^3` in `r^2`, for `|r| <= pi / 4`.
+    const SIN_POLY: &'static [f64];
+    /// Coefficients of `(cos(r) - 1 + r^2 / 2) / r^4` in `r^2`, for `|r| <= pi / 4`.
+    const COS_POLY: &'static [f64];
+    /// Coefficients of `(atan(t) - t) / t^3` in `t^2`, for `|t| <= tan(pi / 8)`.
+    const ATAN_POLY: &'static [f64];
+
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Real for f32 {
+    const MANTISSA_BITS: usize = 23;
+    const EXP_BIAS: f64 = 127.0;
+    const MAX_EXP2: f64 = 128.0;
+    const MIN_EXP2: f64 = -150.0;
+
+    const LN2_HI: f64 = 0.693145751953125;
+    const LN2_LO: f64 = 1.428606765330187e-6;
+    const LOG10_2_HI: f64 = 0.301025390625;
+    const LOG10_2_LO: f64 = 4.605039066518657e-6;
+    const FRAC_PI_2_PARTS: [f64; 4] = [
+        1.57080078125,
+        -4.453584551811218e-6,
+        -8.706138032721356e-10,
+        6.223371969669989e-14,
+    ];
+    const PI_LO: f64 = -8.742277657347586e-8;
+    const FRAC_PI_2_LO: f64 = -4.371138828673793e-8;
+    const FRAC_PI_4_LO: f64 = -2.1855694143368964e-8;
+
+    const EXP_POLY: &'static [f64] = &[
+        0.00019890980911441147,
+        0.0013933641603216529,
+        0.00833331048488617,
+        0.04166646674275398,
+        0.1666666716337204,
+        0.5,
+    ];
+    const LN_POLY: &'static [f64] = &[
+        0.2333046793937683,
+        0.2855082154273987,
+        0.4000012278556824,
+        0.6666666865348816,
+    ];
+    const SIN_POLY: &'static [f64] = &[
+        2.7249925551586784e-6,
+        -0.00019840087043121457,
+        0.008333331905305386,
+        -0.1666666716337204,
+    ];
+    const COS_POLY: &'static [f64] = &[
+        -2.730095900460583e-7,
+        2.480059993104078e-5,
+        -0.00138888880610466,
+        0.0416666679084301,
+    ];
+    const ATAN_POLY: &'static [f64] = &[
+        0.05048138648271561,
+        -0.08624675869941711,
+        0.1107136532664299,
+        -0.14284151792526245,
+        0.19999977946281433,
+        -0.3333333432674408,
+    ];
+
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Real for f64 {
+    const MANTISSA_BITS: usize = 52;
+    const EXP_BIAS: f64 = 1023.0;
+    const MAX_EXP2: f64 = 1024.0;
+    const MIN_EXP2: f64 = -1075.0;
+
+    const LN2_HI: f64 = 0.6931471805601177;
+    const LN2_LO: f64 = -1.7239444525614835e-13;
+    const LOG10_2_HI: f64 = 0.3010299956640665;
+    const LOG10_2_LO: f64 = -8.532344317057107e-14;
+    const FRAC_PI_2_PARTS: [f64; 4] = [
+        1.5707963267341256,
+        6.077100506303966e-11,
+        2.0222662487111665e-21,
+        8.4784276603689e-32,
+    ];
+    const PI_LO: f64 = 1.2246467991473532e-16;
+    const FRAC_PI_2_LO: f64 = 6.123233995736766e-17;
+    const FRAC_PI_4_LO: f64 = 3.061616997868383e-17;
+
+    const EXP_POLY: &'static [f64] = &[
+        2.0914679376583935e-9,
+        2.510520637395701e-8,
+        2.7557273661348637e-7,
+        2.7557255425746435e-6,
+        2.4801587325533363e-5,
+        0.00019841269874800493,
+        0.0013888888888883752,
+        0.008333333333326141,
+        0.04166666666666667,
+        0.1666666666666667,
+        0.5,
+    ];
+    const LN_POLY: &'static [f64] = &[
+        0.13086626147840102,
+        0.13268773138656886,
+        0.15386239702814658,
+        0.18181795640132906,
+        0.22222222391713917,
+        0.28571428570803614,
+        0.4000000000000088,
+        0.6666666666666666,
+    ];
+    const SIN_POLY: &'static [f64] = &[
+        -7.586697117706918e-13,
+        1.6058531618986147e-10,
+        -2.5052106232447578e-8,
+        2.7557319219339167e-6,
+        -0.00019841269841265065,
+        0.008333333333333331,
+        -0.16666666666666666,
+    ];
+    const COS_POLY: &'static [f64] = &[
+        -1.1382632425521717e-11,
+        2.08761462684032e-9,
+        -2.7557317271729793e-7,
+        2.480158729876569e-5,
+        -0.0013888888888887398,
+        0.041666666666666664,
+    ];
+    const ATAN_POLY: &'static [f64] = &[
+        -0.01917688711906226,
+        0.03923165829558719,
+        -0.0508544973794026,
+        0.0585814891280221,
+        -0.06664511447381948,
+        0.07692183190826087,
+        -0.09090904578123903,
+        0.11111111015256361,
+        -0.14285714284666542,
+        0.1999999999999552,
+        -0.3333333333333333,
+    ];
+
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+// Lower-degree versions of the polynomials above, shared by both precisions
+const EXP_FAST_POLY: &[f64] = &[
+    0.008357200148445668,
+    0.041833804078406464,
+    0.16666630825186785,
+    0.49999748990027465,
+];
+const LN_FAST_POLY: &[f64] = &[0.4085826936143872, 0.6666349945494474];
+const SIN_FAST_POLY: &[f64] = &[0.008211855507308219, -0.16665731001278386];
+const COS_FAST_POLY: &[f64] = &[-0.001373681406173436, 0.04166549508011033];
+const ATAN_FAST_POLY: &[f64] = &[
+    -0.11819444409574102,
+    0.1984809781108456,
+    -0.33331896557788776,
+];
+
+#[inline(always)]
+fn splat<S>(value: f64) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    S::new(S::Elem::from_f64(value))
+}
+
+#[inline(always)]
+fn poly<S>(x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let mut res = splat::<S>(coeffs[0]);
+    for &coeff in &coeffs[1..] {
+        res = res.mul_add(x, splat(coeff));
+    }
+    res
+}
+
+#[inline(always)]
+fn exp2i(exp: usize) -> f64 {
+    (1u64 << exp) as f64
+}
+
+/// Rounds to the nearest integer, with ties to even. Only valid for `|x| < 2^(MANTISSA_BITS - 1)`.
+#[inline(always)]
+fn round_small<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let magic = splat::<S>(1.5 * exp2i(S::Elem::MANTISSA_BITS));
+    (x + magic) - magic
+}
+
+/// Computes `2^n` for an integral `n` in the normal exponent range.
+#[inline(always)]
+fn pow2i<S>(n: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    // Place n + bias in the low mantissa bits and then shift it into the exponent field
+    let bits = S::Elem::MANTISSA_BITS;
+    let magic = splat::<S>(exp2i(bits) + S::Elem::EXP_BIAS);
+    S::from_bits((n + magic).to_bits() << bits)
+}
+
+/// Computes `x * 2^n` for an integral `n` in `[MIN_EXP2 - 1, MAX_EXP2 + 1]`, rounding only once.
+#[inline(always)]
+fn scale<S>(x: S, n: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    // Split the scale into two factors, the first of which keeps x in the normal range
+    let bias = S::Elem::EXP_BIAS;
+    let first = n.max(splat(2.0 - bias)).min(splat(bias));
+    x * pow2i(first) * pow2i(n - first)
+}
+
+/// Returns `exp(r) - 1` for `|r| <= ln(2) / 2`.
+#[inline(always)]
+fn expm1_reduced<S>(r: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    (r * r).mul_add(poly(r, coeffs), r)
+}
+
+/// Computes `e^(hi + lo)`, where `lo` is small compared to `hi`.
+#[inline(always)]
+fn exp_split<S>(hi: S, lo: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    // Clamp one past the limits, so that results still overflow or round to zero after scaling
+    let min = splat((S::Elem::MIN_EXP2 - 1.0) * LN_2);
+    let max = splat((S::Elem::MAX_EXP2 + 1.0) * LN_2);
+    let clamped = hi.max(min).min(max);
+
+    let n = round_small(clamped * splat(LOG2_E));
+    let r = (clamped - n * splat(S::Elem::LN2_HI)) + (lo - n * splat(S::Elem::LN2_LO));
+    let res = scale(expm1_reduced(r, coeffs) + splat(1.0), n);
+
+    hi.eq(&hi).select(res, hi)
+}
+
+#[inline(always)]
+fn exp_impl<S>(x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    exp_split(x, S::default(), coeffs)
+}
+
+#[inline(always)]
+fn exp2_impl<S>(x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let min = splat(S::Elem::MIN_EXP2 - 1.0);
+    let max = splat(S::Elem::MAX_EXP2 + 1.0);
+    let clamped = x.max(min).min(max);
+
+    let n = round_small(clamped);
+    let r = (clamped - n) * splat(LN_2);
+    let res = scale(expm1_reduced(r, coeffs) + splat(1.0), n);
+
+    x.eq(&x).select(res, x)
+}
+
+/// Splits `x` into an exponent `e` and a mantissa `m` in `[sqrt(2) / 2, sqrt(2))`, and returns
+/// `e` along with `m - 1`, which is exact. Special values are not handled.
+#[inline(always)]
+fn decompose<S>(x: S) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let bits = S::Elem::MANTISSA_BITS;
+    let one = splat::<S>(1.0);
+
+    // Bring subnormals into the normal range
+    let subnormal_scale = exp2i(bits + 1);
+    let min_positive = 2.0f64.powi(1 - S::Elem::EXP_BIAS as i32);
+    let subnormal = x.lt(&splat(min_positive));
+    let x = subnormal.select(x * splat(subnormal_scale), x);
+
+    // Reinterpret the exponent field as the mantissa of 2^MANTISSA_BITS to convert it to a float
+    let magic = splat::<S>(exp2i(bits));
+    let exp_field = S::from_bits((x.to_bits() >> bits) | magic.to_bits());
+    let exp = exp_field - splat(exp2i(bits) + S::Elem::EXP_BIAS);
+    let exp = exp - subnormal.select(splat((bits + 1) as f64), S::default());
+
+    // Replace the exponent with that of 1.0 to get the mantissa
+    let mantissa_mask = !splat::<S>(f64::NEG_INFINITY).to_bits();
+    let mantissa = S::from_bits((x.to_bits() & mantissa_mask) | one.to_bits());
+
+    let large = mantissa.gt(&splat(SQRT_2));
+    let mantissa = large.select(mantissa * splat(0.5), mantissa);
+    let exp = large.select(exp + one, exp);
+
+    (exp, mantissa - one)
+}
+
+/// Returns `e` and `ln(m)` as computed by [`decompose`].
+#[inline(always)]
+fn ln_reduced<S>(x: S, coeffs: &[f64]) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let (exp, f) = decompose(x);
+
+    // With f = m - 1 and s = f / (f + 2), ln(m) = ln((1 + s) / (1 - s)) = 2s + s^3 * P(s^2).
+    // Since 2s = f - s * f, this is f - s * (f - s^2 * P(s^2)), where f is exact and the
+    // correction is small, keeping the rounding errors of s out of the leading term.
+    let s = f / (f + splat(2.0));
+    let z = s * s;
+    let ln_mantissa = f - s * (f - z * poly(z, coeffs));
+
+    (exp, ln_mantissa)
+}
+
+#[inline(always)]
+fn log_specials<S>(x: S, res: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let res = x.eq(&splat(f64::INFINITY)).select(x, res);
+    let res = x.eq(&S::default()).select(splat(f64::NEG_INFINITY), res);
+    x.ge(&S::default()).select(res, splat(f64::NAN))
+}
+
+#[inline(always)]
+fn ln_impl<S>(x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let (exp, ln_mantissa) = ln_reduced(x, coeffs);
+    let low = exp.mul_add(splat(S::Elem::LN2_LO), ln_mantissa);
+    log_specials(x, exp.mul_add(splat(S::Elem::LN2_HI), low))
+}
+
+#[inline(always)]
+fn log2_impl<S>(x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let (exp, ln_mantissa) = ln_reduced(x, coeffs);
+    log_specials(x, ln_mantissa.mul_add(splat(LOG2_E), exp))
+}
+
+#[inline(always)]
+fn log10_impl<S>(x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let (exp, ln_mantissa) = ln_reduced(x, coeffs);
+    let low = exp.mul_add(splat(S::Elem::LOG10_2_LO), ln_mantissa * splat(LOG10_E));
+    log_specials(x, exp.mul_add(splat(S::Elem::LOG10_2_HI), low))
+}
+
+/// Computes `ln(x)` as an unevaluated sum `hi + lo` with roughly twice the working precision,
+/// for positive `x`. The low part is not meaningful when `hi` is not finite.
+#[inline(always)]
+fn ln_split<S>(x: S, coeffs: &[f64]) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let (exp, f) = decompose(x);
+
+    // Compute s = f / (f + 2) along with the rounding error of the division. The leading term 2s
+    // of ln(m) is then exact, and the remaining terms are small enough for their rounding errors
+    // to only affect the low part.
+    let two = splat::<S>(2.0);
+    let d = f + two;
+    let d_lo = (two - d) + f;
+    let s = f / d;
+    let (sd, sd_err) = mul_split(s, d);
+    let s_lo = (((f - sd) - sd_err) - s * d_lo) / d;
+    let z = s * s;
+    let t_lo = (s * z).mul_add(poly(z, coeffs), s_lo + s_lo);
+
+    // Since |exp * LN2_HI| >= |2s| unless exp is zero, the rounding error of the sum is exact
+    let exp_hi = exp * splat(S::Elem::LN2_HI);
+    let sum = exp_hi + (s + s);
+    let sum_lo = ((exp_hi - sum) + (s + s)) + exp.mul_add(splat(S::Elem::LN2_LO), t_lo);
+
+    // Renormalize, so that the low part is below the precision of the high part
+    let hi = sum + sum_lo;
+    let lo = (sum - hi) + sum_lo;
+
+    (log_specials(x, hi), lo)
+}
+
+/// Computes `a + b` along with the rounding error of the sum.
+#[inline(always)]
+fn add_split<S>(a: S, b: S) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let err = (a - (sum - b_virtual)) + (b - b_virtual);
+    (sum, err)
+}
+
+/// Splits `x` into two halves of at most half the mantissa width each.
+#[inline(always)]
+fn split<S>(x: S) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let t = x * splat(exp2i((S::Elem::MANTISSA_BITS + 2) / 2) + 1.0);
+    let hi = t - (t - x);
+    (hi, x - hi)
+}
+
+/// Computes `a * b` along with the rounding error of the product, without relying on a fused
+/// multiply-add. Only valid when neither product overflows.
+#[inline(always)]
+fn mul_split<S>(a: S, b: S) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let prod = a * b;
+    let (a_hi, a_lo) = split(a);
+    let (b_hi, b_lo) = split(b);
+    let err = ((a_hi * b_hi - prod) + a_hi * b_lo + a_lo * b_hi) + a_lo * b_lo;
+    (prod, err)
+}
+
+#[inline(always)]
+fn pow_impl<S>(x: S, y: S, exp_coeffs: &[f64], ln_coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let zero = S::default();
+    let one = splat::<S>(1.0);
+
+    // Keep the extra precision of ln(x) through the product, since its error is scaled by y
+    let (ln_hi, ln_lo) = ln_split(x.abs(), ln_coeffs);
+    let (w_hi, w_err) = mul_split(y, ln_hi);
+    let w_lo = y.mul_add(ln_lo, w_err);
+    // Beyond this range the result saturates anyway and the low part may be NaN
+    let w_lo = w_hi
+        .abs()
+        .lt(&splat(2.0 * S::Elem::MAX_EXP2))
+        .select(w_lo, zero);
+    let res = exp_split(w_hi, w_lo, exp_coeffs);
+
+    // Negative bases are only defined for integral exponents, and flip the sign for odd ones
+    let y_int = y.trunc().eq(&y);
+    let y_odd = (y * splat(0.5)).fract().abs().eq(&splat(0.5));
+    let x_neg = one.copysign(x).lt(&zero);
+    let res = (x_neg & y_odd).select(-res, res);
+    let x_finite_neg = x.lt(&zero) & x.gt(&splat(f64::NEG_INFINITY));
+    let res = (x_finite_neg & !y_int).select(splat(f64::NAN), res);
+
+    // (-1)^±inf, 1^y and x^0 are all exactly one, even for NaN inputs
+    let x_abs_one = x.abs().eq(&one) & y.abs().eq(&splat(f64::INFINITY));
+    (x_abs_one | x.eq(&one) | y.eq(&zero)).select(one, res)
+}
+
+/// Returns `(sin(x), cos(x))`.
+#[inline(always)]
+fn sincos_impl<S>(x: S, sin_coeffs: &[f64], cos_coeffs: &[f64]) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let [p1, p2, p3, p4] = S::Elem::FRAC_PI_2_PARTS;
+    let sign_shift = mem::size_of::<S::Elem>() * 8 - 1;
+
+    // Find the nearest multiple j of pi / 2, keeping the low bits of j in the low bits of the sum
+    let magic = splat::<S>(1.5 * exp2i(S::Elem::MANTISSA_BITS));
+    let sum = x * splat(FRAC_2_PI) + magic;
+    let j = sum - magic;
+    let quadrant = sum.to_bits();
+
+    // Subtract j * pi / 2 in four parts, the first three of which are exact, and keep the rounding
+    // errors of the differences in r_lo. Near the boundaries between binades, an error of less
+    // than an ULP of r would otherwise amount to two ULP of the result. The fourth part keeps the
+    // error of j * pi / 2 small compared to r even when x is close to a multiple of pi / 2.
+    let (t, t_lo) = add_split(x - j * splat(p1), -(j * splat(p2)));
+    let (u, u_lo) = add_split(t, -(j * splat(p3)));
+    let (r, r_lo) = add_split(u, -(j * splat(p4)));
+    let r_lo = r_lo + (u_lo + t_lo);
+
+    // sin(r + r_lo) ~= sin(r) + r_lo * (1 - r^2 / 2), and cos(r + r_lo) ~= cos(r) - r * r_lo
+    let one = splat::<S>(1.0);
+    let z = r * r;
+    let half_z = z * splat(0.5);
+    let sin = r + (r * z).mul_add(poly(z, sin_coeffs), r_lo - r_lo * half_z);
+    // Compute the rounding error of 1 - z / 2 separately and add it back with the polynomial
+    let w = one - half_z;
+    let cos_lo = (z * z).mul_add(poly(z, cos_coeffs), ((one - w) - half_z) - r * r_lo);
+    let cos = w + cos_lo;
+
+    // sin(r + j * pi / 2) cycles through sin(r), cos(r), -sin(r), -cos(r) as j increases
+    let odd = (quadrant << sign_shift).ne(&S::Bits::default());
+    let sin_sign = (quadrant >> 1) << sign_shift;
+    let cos_sign = ((quadrant >> 1) ^ quadrant) << sign_shift;
+    let (sin, cos) = (odd.select(cos, sin), odd.select(sin, cos));
+
+    (
+        S::from_bits(sin.to_bits() ^ sin_sign),
+        S::from_bits(cos.to_bits() ^ cos_sign),
+    )
+}
+
+#[inline(always)]
+fn tanh_impl<S>(x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    // tanh(|x|) = (e^2|x| - 1) / (e^2|x| + 1), and rounds to 1 well before |x| = MANTISSA_BITS
+    let a = x.abs().min(splat(S::Elem::MANTISSA_BITS as f64));
+    let y = a + a;
+
+    let n = round_small(y * splat(LOG2_E));
+    let r = (y - n * splat(S::Elem::LN2_HI)) - n * splat(S::Elem::LN2_LO);
+    let pow2 = pow2i(n);
+    let expm1 = pow2.mul_add(expm1_reduced(r, coeffs), pow2 - splat(1.0));
+
+    let res = (expm1 / (expm1 + splat(2.0))).copysign(x);
+    x.eq(&x).select(res, x)
+}
+
+/// Computes `atan(a)` for `a >= 0`.
+#[inline(always)]
+fn atan_positive<S>(a: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let zero = S::default();
+    let one = splat::<S>(1.0);
+
+    // Reduce to |t| <= tan(pi / 8) using atan(a) = pi / 2 + atan(-1 / a) for a > tan(3pi / 8)
+    // and atan(a) = pi / 4 + atan((a - 1) / (a + 1)) for a > tan(pi / 8)
+    let large = a.gt(&splat(SQRT_2 + 1.0));
+    let medium = a.gt(&splat(SQRT_2 - 1.0));
+    let num = large.select(-one, medium.select(a - one, a));
+    let den = large.select(a, medium.select(a + one, one));
+    let base = large.select(splat(PI / 2.0), medium.select(splat(PI / 4.0), zero));
+    let base_lo = large.select(
+        splat(S::Elem::FRAC_PI_2_LO),
+        medium.select(splat(S::Elem::FRAC_PI_4_LO), zero),
+    );
+
+    let t = num / den;
+    let z = t * t;
+    let atan_t = (t * z).mul_add(poly(z, coeffs), t);
+    base + (atan_t + base_lo)
+}
+
+#[inline(always)]
+fn atan_impl<S>(x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    atan_positive(x.abs(), coeffs).copysign(x)
+}
+
+#[inline(always)]
+fn atan2_impl<S>(y: S, x: S, coeffs: &[f64]) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let zero = S::default();
+    let inf = splat::<S>(f64::INFINITY);
+    let (x_abs, y_abs) = (x.abs(), y.abs());
+
+    // Compute the angle in the first quadrant, where 0 / 0 and inf / inf need special handling
+    let res = atan_positive(y_abs / x_abs, coeffs);
+    let res = (x_abs.eq(&zero) & y_abs.eq(&zero)).select(zero, res);
+    let res = (x_abs.eq(&inf) & y_abs.eq(&inf)).select(splat(PI / 4.0), res);
+
+    // Reflect into the second quadrant for negative x (including -0.0)
+    let x_neg = splat::<S>(1.0).copysign(x).lt(&zero);
+    let res = x_neg.select((splat::<S>(PI) - res) + splat(S::Elem::PI_LO), res);
+
+    res.copysign(y)
+}
+
+/// Computes `e^x`, with an error of at most 2 ULP.
+#[inline(always)]
+pub fn exp<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    exp_impl(x, S::Elem::EXP_POLY)
+}
+
+/// Fast version of [`exp`].
+#[inline(always)]
+pub fn exp_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    exp_impl(x, EXP_FAST_POLY)
+}
+
+/// Computes `2^x`, with an error of at most 2 ULP.
+#[inline(always)]
+pub fn exp2<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    exp2_impl(x, S::Elem::EXP_POLY)
+}
+
+/// Fast version of [`exp2`].
+#[inline(always)]
+pub fn exp2_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    exp2_impl(x, EXP_FAST_POLY)
+}
+
+/// Computes the natural logarithm, with an error of at most 2 ULP.
+#[inline(always)]
+pub fn ln<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    ln_impl(x, S::Elem::LN_POLY)
+}
+
+/// Fast version of [`ln`].
+#[inline(always)]
+pub fn ln_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    ln_impl(x, LN_FAST_POLY)
+}
+
+/// Computes the base 2 logarithm, with an error of at most 2 ULP.
+#[inline(always)]
+pub fn log2<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    log2_impl(x, S::Elem::LN_POLY)
+}
+
+/// Fast version of [`log2`].
+#[inline(always)]
+pub fn log2_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    log2_impl(x, LN_FAST_POLY)
+}
+
+/// Computes the base 10 logarithm, with an error of at most 3 ULP.
+#[inline(always)]
+pub fn log10<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    log10_impl(x, S::Elem::LN_POLY)
+}
+
+/// Fast version of [`log10`].
+#[inline(always)]
+pub fn log10_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    log10_impl(x, LN_FAST_POLY)
+}
+
+/// Computes `x^y`, with an error of at most 6 ULP for results in `[e^-90, e^90]`.
+///
+/// Outside of that range, which is only reachable for `f64`, the error grows to at most 48 ULP
+/// near the overflow and underflow thresholds.
+#[inline(always)]
+pub fn pow<S>(x: S, y: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    pow_impl(x, y, S::Elem::EXP_POLY, S::Elem::LN_POLY)
+}
+
+/// Fast version of [`pow`].
+#[inline(always)]
+pub fn pow_fast<S>(x: S, y: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    pow_impl(x, y, EXP_FAST_POLY, LN_FAST_POLY)
+}
+
+/// Computes the sine, with an error of at most 1 ULP.
+///
+/// The bound holds for `|x| <= 6000` for `f32` and `|x| <= 1.6e6` for `f64`. Beyond that the
+/// argument reduction loses accuracy, and the error grows with `|x|`.
+#[inline(always)]
+pub fn sin<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    sincos_impl(x, S::Elem::SIN_POLY, S::Elem::COS_POLY).0
+}
+
+/// Fast version of [`sin`].
+#[inline(always)]
+pub fn sin_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    sincos_impl(x, SIN_FAST_POLY, COS_FAST_POLY).0
+}
+
+/// Computes the cosine, with an error of at most 1 ULP, over the same range as [`sin`].
+#[inline(always)]
+pub fn cos<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    sincos_impl(x, S::Elem::SIN_POLY, S::Elem::COS_POLY).1
+}
+
+/// Fast version of [`cos`].
+#[inline(always)]
+pub fn cos_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    sincos_impl(x, SIN_FAST_POLY, COS_FAST_POLY).1
+}
+
+/// Computes `(sin(x), cos(x))`, with the same error as [`sin`] and [`cos`].
+#[inline(always)]
+pub fn sincos<S>(x: S) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    sincos_impl(x, S::Elem::SIN_POLY, S::Elem::COS_POLY)
+}
+
+/// Fast version of [`sincos`].
+#[inline(always)]
+pub fn sincos_fast<S>(x: S) -> (S, S)
+where
+    S: Float,
+    S::Elem: Real,
+{
+    sincos_impl(x, SIN_FAST_POLY, COS_FAST_POLY)
+}
+
+/// Computes the tangent, with an error of at most 3 ULP, over the same range as [`sin`].
+#[inline(always)]
+pub fn tan<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let (sin, cos) = sincos(x);
+    sin / cos
+}
+
+/// Fast version of [`tan`].
+#[inline(always)]
+pub fn tan_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    let (sin, cos) = sincos_fast(x);
+    sin / cos
+}
+
+/// Computes the hyperbolic tangent, with an error of at most 3 ULP.
+#[inline(always)]
+pub fn tanh<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    tanh_impl(x, S::Elem::EXP_POLY)
+}
+
+/// Fast version of [`tanh`].
+#[inline(always)]
+pub fn tanh_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    tanh_impl(x, EXP_FAST_POLY)
+}
+
+/// Computes the arctangent, with an error of at most 3 ULP.
+#[inline(always)]
+pub fn atan<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    atan_impl(x, S::Elem::ATAN_POLY)
+}
+
+/// Fast version of [`atan`].
+#[inline(always)]
+pub fn atan_fast<S>(x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    atan_impl(x, ATAN_FAST_POLY)
+}
+
+/// Computes the four-quadrant arctangent of `y / x`, with an error of at most 3 ULP.
+#[inline(always)]
+pub fn atan2<S>(y: S, x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    atan2_impl(y, x, S::Elem::ATAN_POLY)
+}
+
+/// Fast version of [`atan2`].
+#[inline(always)]
+pub fn atan2_fast<S>(y: S, x: S) -> S
+where
+    S: Float,
+    S::Elem: Real,
+{
+    atan2_impl(y, x, ATAN_FAST_POLY)
+}
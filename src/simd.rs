@@ -2,31 +2,534 @@ use core::fmt::Debug;
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
 use core::ops::{Index, IndexMut};
+use core::ops::{Rem, RemAssign};
 use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+use core::slice;
 
+/// Vectors of `LANES` lanes.
+///
+/// Lanes are read with `Index` and written with [`set`](Simd::set). Vectors that are also stored
+/// as an array of lanes implement [`Array`], which makes them mutably indexable and lets them be
+/// borrowed as slices; masks may instead be stored one bit per lane.
 pub trait Simd: Copy + Clone + Debug + Default + Send + Sync + Sized
 where
     Self: LanesEq<Output = Self::Mask> + LanesOrd<Output = Self::Mask>,
-    Self: Index<usize, Output = Self::Elem> + IndexMut<usize, Output = Self::Elem>,
+    Self: Index<usize, Output = Self::Elem>,
 {
-    type Elem;
-    type Mask: Select<Self>;
+    type Elem: Copy;
+    type Mask: Select<Self> + Mask;
 
     const LANES: usize;
 
     fn new(elem: Self::Elem) -> Self;
 
-    fn as_slice(&self) -> &[Self::Elem];
-    fn as_mut_slice(&mut self) -> &mut [Self::Elem];
+    /// Sets lane `index` to `elem`. Panics if `index` is out of bounds.
+    fn set(&mut self, index: usize, elem: Self::Elem);
+
     fn from_slice(slice: &[Self::Elem]) -> Self;
     fn write_to_slice(&self, slice: &mut [Self::Elem]);
+
+    /// Like [`from_slice`](Simd::from_slice), but also panics if `slice` is not aligned to
+    /// `align_of::<Self>()`.
+    #[inline(always)]
+    fn from_slice_aligned(slice: &[Self::Elem]) -> Self {
+        check_aligned::<Self>(slice);
+        Self::from_slice(slice)
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reading `LANES` elements. It does not need to be aligned.
+    #[inline(always)]
+    unsafe fn load_ptr(ptr: *const Self::Elem) -> Self {
+        Self::from_slice(slice::from_raw_parts(ptr, Self::LANES))
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writing `LANES` elements. It does not need to be aligned.
+    #[inline(always)]
+    unsafe fn store_ptr(&self, ptr: *mut Self::Elem) {
+        self.write_to_slice(slice::from_raw_parts_mut(ptr, Self::LANES));
+    }
+
+    /// Writes to `slice` with a non-temporal store where the architecture has one, which avoids
+    /// filling the cache with data that will not be read again soon. Panics if `slice` is not
+    /// `LANES` long and aligned to `align_of::<Self>()`.
+    ///
+    /// # Safety
+    ///
+    /// [`Arch::stream_fence`](crate::Arch::stream_fence) must be called after the last streaming
+    /// store and before the memory is accessed again, from this thread or any other.
+    #[inline(always)]
+    unsafe fn store_stream(&self, slice: &mut [Self::Elem]) {
+        check_aligned::<Self>(slice);
+        self.write_to_slice(slice);
+    }
+
+    /// Loads the first `min(slice.len(), LANES)` lanes from `slice`, and sets the rest to zero (or
+    /// `false`).
+    #[inline(always)]
+    fn load_partial(slice: &[Self::Elem]) -> Self {
+        Self::load_or(slice, Self::default())
+    }
+
+    /// Like [`load_partial`](Simd::load_partial), but takes the remaining lanes from `or`.
+    #[inline(always)]
+    fn load_or(slice: &[Self::Elem], or: Self) -> Self {
+        let len = slice.len().min(Self::LANES);
+        let mut res = or;
+        for (i, &elem) in slice[..len].iter().enumerate() {
+            res.set(i, elem);
+        }
+        res
+    }
+
+    /// Writes the first `min(slice.len(), LANES)` lanes to `slice`.
+    #[inline(always)]
+    fn store_partial(&self, slice: &mut [Self::Elem]) {
+        let len = slice.len().min(Self::LANES);
+        for (i, elem) in slice[..len].iter_mut().enumerate() {
+            *elem = self[i];
+        }
+    }
+
+    /// Loads lane `i` from `slice[i]` where `mask` is true, and sets the other lanes to zero (or
+    /// `false`). Disabled lanes may lie past the end of `slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an enabled lane is out of bounds.
+    #[inline(always)]
+    fn load_masked(slice: &[Self::Elem], mask: Self::Mask) -> Self {
+        check_mask_len(slice.len(), mask);
+
+        let bits = mask.to_bitmask();
+        let mut res = Self::default();
+        for i in 0..Self::LANES {
+            if bits & (1 << i) != 0 {
+                res.set(i, unsafe { *slice.get_unchecked(i) });
+            }
+        }
+        res
+    }
+
+    /// Writes lane `i` to `slice[i]` where `mask` is true. Disabled lanes may lie past the end of
+    /// `slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an enabled lane is out of bounds.
+    #[inline(always)]
+    fn store_masked(&self, slice: &mut [Self::Elem], mask: Self::Mask) {
+        check_mask_len(slice.len(), mask);
+
+        let bits = mask.to_bitmask();
+        for i in 0..Self::LANES {
+            if bits & (1 << i) != 0 {
+                unsafe { *slice.get_unchecked_mut(i) = self[i] };
+            }
+        }
+    }
+}
+
+/// Vectors stored as an array of `LANES` elements.
+pub trait Array: Simd + IndexMut<usize, Output = Self::Elem> {
+    fn as_slice(&self) -> &[Self::Elem];
+    fn as_mut_slice(&mut self) -> &mut [Self::Elem];
+
     fn align_slice(slice: &[Self::Elem]) -> (&[Self::Elem], &[Self], &[Self::Elem]);
     fn align_mut_slice(
         slice: &mut [Self::Elem],
     ) -> (&mut [Self::Elem], &mut [Self], &mut [Self::Elem]);
 }
 
-pub trait Float: Sized
+#[inline(always)]
+pub(crate) fn check_aligned<S: Simd>(slice: &[S::Elem]) {
+    assert!(slice.len() == S::LANES);
+    assert!(
+        slice.as_ptr().cast::<S>().is_aligned(),
+        "slice is not aligned"
+    );
+}
+
+#[inline(always)]
+pub(crate) fn check_mask_len<M: Mask>(len: usize, mask: M) {
+    assert!(
+        len >= M::LANES || mask.to_bitmask() >> len == 0,
+        "masked lane out of bounds"
+    );
+}
+
+/// Compile-time lane indices for [`Shuffle::swizzle`] and [`Shuffle::shuffle`].
+pub trait Swizzle<const N: usize> {
+    const INDEX: [usize; N];
+}
+
+pub(crate) const fn check_index(index: &[usize], lanes: usize, inputs: usize) {
+    assert!(
+        index.len() == lanes,
+        "swizzle length must equal the number of lanes"
+    );
+    let mut i = 0;
+    while i < index.len() {
+        assert!(index[i] < lanes * inputs, "swizzle index out of bounds");
+        i += 1;
+    }
+}
+
+/// Lane permutations.
+///
+/// Indices and offsets are checked at compile time. The provided methods move lanes one at a
+/// time, and are overridden with native shuffles where the architecture has them.
+pub trait Shuffle: Simd {
+    /// Returns a vector whose lane `i` is lane `S::INDEX[i]` of `self`. `N` must equal `LANES`.
+    #[inline(always)]
+    fn swizzle<S: Swizzle<N>, const N: usize>(self) -> Self {
+        const { check_index(&S::INDEX, Self::LANES, 1) };
+
+        let mut res = self;
+        for (i, &index) in S::INDEX.iter().enumerate() {
+            res.set(i, self[index]);
+        }
+        res
+    }
+
+    /// Like [`swizzle`](Shuffle::swizzle), but indices from `LANES` to `2 * LANES` select lanes
+    /// of `other`.
+    #[inline(always)]
+    fn shuffle<S: Swizzle<N>, const N: usize>(self, other: Self) -> Self {
+        const { check_index(&S::INDEX, Self::LANES, 2) };
+
+        let mut res = self;
+        for (i, &index) in S::INDEX.iter().enumerate() {
+            let elem = if index < Self::LANES {
+                self[index]
+            } else {
+                other[index - Self::LANES]
+            };
+            res.set(i, elem);
+        }
+        res
+    }
+
+    #[inline(always)]
+    fn reverse(self) -> Self {
+        let mut res = self;
+        for i in 0..Self::LANES {
+            res.set(i, self[Self::LANES - 1 - i]);
+        }
+        res
+    }
+
+    /// Rotates lanes toward index zero by `N` (modulo `LANES`), like [`slice::rotate_left`].
+    #[inline(always)]
+    fn rotate_lanes_left<const N: usize>(self) -> Self {
+        let mut res = self;
+        for i in 0..Self::LANES {
+            res.set(i, self[(i + N) % Self::LANES]);
+        }
+        res
+    }
+
+    /// Rotates lanes away from index zero by `N` (modulo `LANES`), like [`slice::rotate_right`].
+    #[inline(always)]
+    fn rotate_lanes_right<const N: usize>(self) -> Self {
+        let mut res = self;
+        for i in 0..Self::LANES {
+            res.set((i + N) % Self::LANES, self[i]);
+        }
+        res
+    }
+
+    /// Returns the first half of `[self[0], other[0], self[1], other[1], ...]`.
+    #[inline(always)]
+    fn interleave_lo(self, other: Self) -> Self {
+        let mut res = self;
+        for i in 0..Self::LANES {
+            let elem = if i % 2 == 0 {
+                self[i / 2]
+            } else {
+                other[i / 2]
+            };
+            res.set(i, elem);
+        }
+        res
+    }
+
+    /// Returns the second half of `[self[0], other[0], self[1], other[1], ...]`.
+    #[inline(always)]
+    fn interleave_hi(self, other: Self) -> Self {
+        let mut res = self;
+        for i in 0..Self::LANES {
+            let j = i + Self::LANES;
+            let elem = if j % 2 == 0 {
+                self[j / 2]
+            } else {
+                other[j / 2]
+            };
+            res.set(i, elem);
+        }
+        res
+    }
+
+    /// Splits the lanes of `[self, other]` into even and odd lanes. This is the inverse of
+    /// [`interleave_lo`](Shuffle::interleave_lo) and [`interleave_hi`](Shuffle::interleave_hi).
+    #[inline(always)]
+    fn deinterleave(self, other: Self) -> (Self, Self) {
+        let concat = |j: usize| {
+            if j < Self::LANES {
+                self[j]
+            } else {
+                other[j - Self::LANES]
+            }
+        };
+
+        let (mut even, mut odd) = (self, self);
+        for i in 0..Self::LANES {
+            even.set(i, concat(2 * i));
+            odd.set(i, concat(2 * i + 1));
+        }
+        (even, odd)
+    }
+
+    /// Returns lanes `OFFSET..OFFSET + LANES` of `[self, other]`. `OFFSET` must be at most
+    /// `LANES`.
+    #[inline(always)]
+    fn concat_shift<const OFFSET: usize>(self, other: Self) -> Self {
+        const { assert!(OFFSET <= Self::LANES, "offset out of bounds") };
+
+        let mut res = self;
+        for i in 0..Self::LANES {
+            let j = i + OFFSET;
+            let elem = if j < Self::LANES {
+                self[j]
+            } else {
+                other[j - Self::LANES]
+            };
+            res.set(i, elem);
+        }
+        res
+    }
+}
+
+/// An element of an index vector for [`Gather`].
+pub trait GatherIndex: Copy {
+    /// Converts the index to `usize`. Negative indices become `usize::MAX`, which is never in
+    /// bounds.
+    fn to_usize(self) -> usize;
+}
+
+/// One vector, or an array of vectors whose lanes are numbered consecutively, as used by [`Gather`]
+/// when data and index vectors have different lane counts.
+pub trait Parts<S: Simd>: Copy + Default + Debug + Send + Sync {
+    /// The number of vectors.
+    const COUNT: usize;
+
+    fn parts(&self) -> &[S];
+    fn parts_mut(&mut self) -> &mut [S];
+}
+
+impl<S: Simd> Parts<S> for S {
+    const COUNT: usize = 1;
+
+    #[inline(always)]
+    fn parts(&self) -> &[S] {
+        slice::from_ref(self)
+    }
+
+    #[inline(always)]
+    fn parts_mut(&mut self) -> &mut [S] {
+        slice::from_mut(self)
+    }
+}
+
+impl<S: Simd, const N: usize> Parts<S> for [S; N]
+where
+    [S; N]: Default,
+{
+    const COUNT: usize = N;
+
+    #[inline(always)]
+    fn parts(&self) -> &[S] {
+        self
+    }
+
+    #[inline(always)]
+    fn parts_mut(&mut self) -> &mut [S] {
+        self
+    }
+}
+
+#[inline(always)]
+fn lane<S: Simd>(parts: &[S], i: usize) -> S::Elem {
+    parts[i / S::LANES][i % S::LANES]
+}
+
+#[inline(always)]
+fn set_lane<S: Simd>(parts: &mut [S], i: usize, elem: S::Elem) {
+    parts[i / S::LANES].set(i % S::LANES, elem);
+}
+
+#[inline(always)]
+fn lane_enabled<M: Mask>(masks: &[M], i: usize) -> bool {
+    masks[i / M::LANES].to_bitmask() & (1 << (i % M::LANES)) != 0
+}
+
+/// Per-lane reads from and writes to a slice.
+///
+/// Each call reads or writes the lanes of [`Values`](Gather::Values) at the indices in the
+/// corresponding lanes of [`Index`](Gather::Index). When `I` has as many lanes as `Self`, as for
+/// 32-bit lanes indexed by `u32` or `i32` vectors, both are single vectors. Otherwise one of them is
+/// an array covering the lanes of the other: with four `u8` lanes to each `u32` lane, `Index` is
+/// four `u32` vectors for every `u8` vector, and with two `u32` lanes to each `f64` lane, `Values`
+/// is two `f64` vectors for every `u32` vector. Lanes disabled by a mask are neither accessed nor
+/// bounds-checked.
+pub trait Gather<I>: Simd
+where
+    I: Simd,
+    I::Elem: GatherIndex,
+{
+    type Index: Parts<I>;
+    type Values: Parts<Self>;
+    /// Masks covering the lanes of [`Values`](Gather::Values).
+    type Masks: Parts<Self::Mask>;
+
+    /// Returns vectors whose lane `i` is `base[index[i]]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of bounds.
+    #[inline(always)]
+    fn gather(base: &[Self::Elem], index: Self::Index) -> Self::Values {
+        check_gather_index::<I, Self::Mask>(base.len(), index.parts(), None);
+        unsafe { Self::gather_unchecked(base, index) }
+    }
+
+    /// # Safety
+    ///
+    /// Every index must be in bounds for `base`.
+    #[inline(always)]
+    unsafe fn gather_unchecked(base: &[Self::Elem], index: Self::Index) -> Self::Values {
+        let mut res = Self::Values::default();
+        for i in 0..Self::Values::COUNT * Self::LANES {
+            let elem = *base.get_unchecked(lane(index.parts(), i).to_usize());
+            set_lane(res.parts_mut(), i, elem);
+        }
+        res
+    }
+
+    /// Like [`gather`](Gather::gather), but lanes where `mask` is false are taken from `or`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of any enabled lane is out of bounds.
+    #[inline(always)]
+    fn gather_masked(
+        base: &[Self::Elem],
+        index: Self::Index,
+        mask: Self::Masks,
+        or: Self::Values,
+    ) -> Self::Values {
+        check_gather_index(base.len(), index.parts(), Some(mask.parts()));
+        unsafe { Self::gather_masked_unchecked(base, index, mask, or) }
+    }
+
+    /// # Safety
+    ///
+    /// The index of every enabled lane must be in bounds for `base`.
+    #[inline(always)]
+    unsafe fn gather_masked_unchecked(
+        base: &[Self::Elem],
+        index: Self::Index,
+        mask: Self::Masks,
+        or: Self::Values,
+    ) -> Self::Values {
+        let mut res = or;
+        for i in 0..Self::Values::COUNT * Self::LANES {
+            if lane_enabled(mask.parts(), i) {
+                let elem = *base.get_unchecked(lane(index.parts(), i).to_usize());
+                set_lane(res.parts_mut(), i, elem);
+            }
+        }
+        res
+    }
+
+    /// Writes lane `i` of `values` to `base[index[i]]`. Lanes are written in order, so when
+    /// indices repeat, the highest lane wins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of bounds.
+    #[inline(always)]
+    fn scatter(values: Self::Values, base: &mut [Self::Elem], index: Self::Index) {
+        check_gather_index::<I, Self::Mask>(base.len(), index.parts(), None);
+        unsafe { Self::scatter_unchecked(values, base, index) }
+    }
+
+    /// # Safety
+    ///
+    /// Every index must be in bounds for `base`.
+    #[inline(always)]
+    unsafe fn scatter_unchecked(values: Self::Values, base: &mut [Self::Elem], index: Self::Index) {
+        for i in 0..Self::Values::COUNT * Self::LANES {
+            *base.get_unchecked_mut(lane(index.parts(), i).to_usize()) = lane(values.parts(), i);
+        }
+    }
+
+    /// Like [`scatter`](Gather::scatter), but only writes lanes where `mask` is true.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of any enabled lane is out of bounds.
+    #[inline(always)]
+    fn scatter_masked(
+        values: Self::Values,
+        base: &mut [Self::Elem],
+        index: Self::Index,
+        mask: Self::Masks,
+    ) {
+        check_gather_index(base.len(), index.parts(), Some(mask.parts()));
+        unsafe { Self::scatter_masked_unchecked(values, base, index, mask) }
+    }
+
+    /// # Safety
+    ///
+    /// The index of every enabled lane must be in bounds for `base`.
+    #[inline(always)]
+    unsafe fn scatter_masked_unchecked(
+        values: Self::Values,
+        base: &mut [Self::Elem],
+        index: Self::Index,
+        mask: Self::Masks,
+    ) {
+        for i in 0..Self::Values::COUNT * Self::LANES {
+            if lane_enabled(mask.parts(), i) {
+                *base.get_unchecked_mut(lane(index.parts(), i).to_usize()) =
+                    lane(values.parts(), i);
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn check_gather_index<I, M>(len: usize, index: &[I], mask: Option<&[M]>)
+where
+    I: Simd,
+    I::Elem: GatherIndex,
+    M: Mask,
+{
+    for i in 0..index.len() * I::LANES {
+        if mask.is_none_or(|mask| lane_enabled(mask, i)) {
+            assert!(
+                lane(index, i).to_usize() < len,
+                "gather index out of bounds"
+            );
+        }
+    }
+}
+
+pub trait Float: Array
 where
     Self: Add<Output = Self> + AddAssign,
     Self: Sub<Output = Self> + SubAssign,
@@ -34,26 +537,146 @@ where
     Self: Div<Output = Self> + DivAssign,
     Self: Neg<Output = Self>,
 {
+    type Bits: Simd<Mask = Self::Mask> + Int + Bitwise;
+
+    /// Reinterprets each lane as an unsigned integer with the same bit pattern.
+    fn to_bits(self) -> Self::Bits;
+    /// Reinterprets each lane of an unsigned integer vector as a float with the same bit pattern.
+    fn from_bits(bits: Self::Bits) -> Self;
+
+    /// Computes `self * a + b`. The result is rounded once where the architecture supports fused
+    /// multiply-add, and twice otherwise.
+    fn mul_add(self, a: Self, b: Self) -> Self;
+
+    fn sqrt(self) -> Self;
+    /// Computes `1 / self`.
+    fn recip(self) -> Self;
+    /// Approximates `1 / self` with a relative error of at most `1.5 * 2^-12`. Subnormal inputs and
+    /// results may be flushed to zero.
+    fn recip_approx(self) -> Self;
+    /// Computes `1 / self.sqrt()`.
+    fn rsqrt(self) -> Self;
+    /// Approximates `1 / self.sqrt()` with a relative error of at most `1.5 * 2^-12`. Subnormal
+    /// inputs may be flushed to zero.
+    fn rsqrt_approx(self) -> Self;
+
+    fn abs(self) -> Self;
+    /// Returns `self` with the sign bit of `sign`.
+    fn copysign(self, sign: Self) -> Self;
+    /// Returns `1.0` if the sign bit is clear, `-1.0` if it is set, and NaN for NaN.
+    fn signum(self) -> Self;
+
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    /// Rounds to the nearest integer, with ties rounded away from zero.
+    fn round(self) -> Self;
+    fn trunc(self) -> Self;
+    /// Computes `self - self.trunc()`.
+    fn fract(self) -> Self;
+
+    /// Sums all lanes. The order in which lanes are combined is unspecified.
+    fn reduce_sum(self) -> Self::Elem;
+    /// Multiplies all lanes. The order in which lanes are combined is unspecified.
+    fn reduce_product(self) -> Self::Elem;
+    /// Returns the minimum lane. The result is unspecified if any lane is NaN.
+    fn reduce_min(self) -> Self::Elem;
+    /// Returns the maximum lane. The result is unspecified if any lane is NaN.
+    fn reduce_max(self) -> Self::Elem;
 }
 
-pub trait Int: Sized
+/// Integer vectors.
+///
+/// Arithmetic wraps on overflow. Division truncates toward zero; dividing by zero yields zero
+/// (and a remainder equal to the dividend), and `MIN / -1` wraps to `MIN` with a remainder of
+/// zero.
+pub trait Int: Array
 where
     Self: Add<Output = Self> + AddAssign,
     Self: Sub<Output = Self> + SubAssign,
     Self: Mul<Output = Self> + MulAssign,
+    Self: Div<Output = Self> + DivAssign,
+    Self: Rem<Output = Self> + RemAssign,
     Self: Neg<Output = Self>,
     Self: Shl<usize, Output = Self> + ShlAssign<usize>,
     Self: Shr<usize, Output = Self> + ShrAssign<usize>,
 {
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+    /// Computes the absolute difference of each pair of lanes. For signed types, the result is the
+    /// unsigned difference reinterpreted as signed.
+    #[inline(always)]
+    fn abs_diff(self, rhs: Self) -> Self {
+        self.max(rhs) - self.min(rhs)
+    }
+    /// Computes `(self + rhs + 1) >> 1` without intermediate overflow.
+    fn avg(self, rhs: Self) -> Self;
+    /// Returns the high half of the full-width product of each pair of lanes.
+    fn mulhi(self, rhs: Self) -> Self;
+
+    /// Shifts each lane left by the corresponding lane of `counts`, modulo the lane width.
+    fn shl_by(self, counts: Self) -> Self;
+    /// Shifts each lane right by the corresponding lane of `counts`, modulo the lane width. The
+    /// shift is arithmetic for signed types.
+    fn shr_by(self, counts: Self) -> Self;
+    fn rotate_left(self, n: usize) -> Self;
+    fn rotate_right(self, n: usize) -> Self;
+
+    /// Sums all lanes with wrapping addition.
+    fn reduce_sum(self) -> Self::Elem;
+    /// Multiplies all lanes with wrapping multiplication.
+    fn reduce_product(self) -> Self::Elem;
+    fn reduce_min(self) -> Self::Elem;
+    fn reduce_max(self) -> Self::Elem;
 }
 
-pub trait Bitwise: Sized
+pub trait Bitwise: Simd
 where
     Self: BitAnd<Output = Self> + BitAndAssign,
     Self: BitOr<Output = Self> + BitOrAssign,
     Self: BitXor<Output = Self> + BitXorAssign,
     Self: Not<Output = Self>,
 {
+    fn reduce_and(self) -> Self::Elem;
+    fn reduce_or(self) -> Self::Elem;
+    fn reduce_xor(self) -> Self::Elem;
+}
+
+pub trait Mask: Simd + Bitwise {
+    /// Packs the lanes into an integer, with lane `i` stored in bit `i`.
+    fn to_bitmask(self) -> u64;
+    /// Unpacks an integer into lanes, with lane `i` taken from bit `i`. Bits beyond `LANES` are
+    /// ignored.
+    fn from_bitmask(bits: u64) -> Self;
+
+    #[inline(always)]
+    fn any(self) -> bool {
+        self.to_bitmask() != 0
+    }
+
+    #[inline(always)]
+    fn all(self) -> bool {
+        self.to_bitmask() == u64::MAX >> (64 - Self::LANES)
+    }
+
+    #[inline(always)]
+    fn none(self) -> bool {
+        !self.any()
+    }
+
+    #[inline(always)]
+    fn count_true(self) -> usize {
+        self.to_bitmask().count_ones() as usize
+    }
+
+    #[inline(always)]
+    fn first_true(self) -> Option<usize> {
+        let bits = self.to_bitmask();
+        if bits != 0 {
+            Some(bits.trailing_zeros() as usize)
+        } else {
+            None
+        }
+    }
 }
 
 pub trait LanesEq<Rhs = Self>: Sized {
@@ -104,3 +727,185 @@ pub trait LanesOrd<Rhs = Self>: LanesEq<Rhs> {
 pub trait Select<V> {
     fn select(self, if_true: V, if_false: V) -> V;
 }
+
+/// Lane-wise conversion with the same semantics as an `as` cast.
+pub trait Cast<T>: Sized {
+    fn cast(self) -> T;
+}
+
+/// Lane-wise float-to-integer conversion which rounds to nearest (ties to even) before casting.
+pub trait RoundCast<T>: Sized {
+    fn round_cast(self) -> T;
+}
+
+/// Conversion between a vector and the vectors of the next wider element type which cover the
+/// same lanes.
+///
+/// Depending on the architecture, the lanes of a vector may be split across several wider vectors
+/// (e.g. one `u8x16` widens to two `u16x8`s), so conversions go through the `Parts` array type.
+pub trait Widen: Sized {
+    type Wide: Simd;
+    type Parts: Copy
+        + Default
+        + Debug
+        + Send
+        + Sync
+        + AsRef<[Self::Wide]>
+        + AsMut<[Self::Wide]>
+        + IntoIterator<Item = Self::Wide>;
+
+    fn widen(self) -> Self::Parts;
+
+    /// Narrows with the same semantics as an `as` cast (integers are truncated, floats are
+    /// rounded).
+    fn narrow(parts: Self::Parts) -> Self;
+}
+
+/// A divisor with precomputed magic numbers, for dividing many vectors by the same value.
+///
+/// Division is performed with a high multiplication and shifts, which is much faster than
+/// lane-wise [`Div`]. Results are identical to those of [`Div`] and [`Rem`].
+#[derive(Copy, Clone, Debug)]
+pub struct Divisor<S> {
+    divisor: S,
+    multiplier: S,
+    sign: S,
+    pre_shift: usize,
+    post_shift: usize,
+}
+
+impl<S> Divisor<S>
+where
+    S: Int + Bitwise,
+    S::Elem: DivisorElem,
+{
+    /// Panics if `divisor` is zero.
+    #[inline]
+    pub fn new(divisor: S::Elem) -> Self {
+        let magic = S::Elem::magic(divisor);
+
+        Divisor {
+            divisor: S::new(divisor),
+            multiplier: S::new(magic.multiplier),
+            sign: S::new(magic.sign),
+            pre_shift: magic.pre_shift,
+            post_shift: magic.post_shift,
+        }
+    }
+
+    #[inline(always)]
+    pub fn divide(&self, dividend: S) -> S {
+        // Granlund and Montgomery, "Division by Invariant Integers using Multiplication"
+        if S::Elem::SIGNED {
+            let quotient = dividend + dividend.mulhi(self.multiplier);
+            let quotient =
+                (quotient >> self.post_shift) - (dividend >> (S::Elem::BITS as usize - 1));
+            (quotient ^ self.sign) - self.sign
+        } else {
+            let high = dividend.mulhi(self.multiplier);
+            (high + ((dividend - high) >> self.pre_shift)) >> self.post_shift
+        }
+    }
+
+    #[inline(always)]
+    pub fn remainder(&self, dividend: S) -> S {
+        dividend - self.divide(dividend) * self.divisor
+    }
+}
+
+/// Magic numbers for dividing by a particular value; see [`DivisorElem`].
+pub struct Magic<T> {
+    pub multiplier: T,
+    /// Sign mask of the divisor, for signed types.
+    pub sign: T,
+    pub pre_shift: usize,
+    pub post_shift: usize,
+}
+
+/// Integer element types supported by [`Divisor`].
+pub trait DivisorElem: Copy + Sized {
+    const SIGNED: bool;
+    const BITS: u32;
+
+    /// Panics if `divisor` is zero.
+    fn magic(divisor: Self) -> Magic<Self>;
+}
+
+macro_rules! impl_divisor_unsigned {
+    ($elem:ident) => {
+        impl DivisorElem for $elem {
+            const SIGNED: bool = false;
+            const BITS: u32 = $elem::BITS;
+
+            fn magic(divisor: $elem) -> Magic<$elem> {
+                assert!(divisor != 0, "attempt to divide by zero");
+
+                let d = divisor as u128;
+                let log = $elem::BITS - (divisor - 1).leading_zeros();
+                let multiplier = (1u128 << $elem::BITS) * ((1u128 << log) - d) / d + 1;
+
+                Magic {
+                    multiplier: multiplier as $elem,
+                    sign: 0,
+                    pre_shift: log.min(1) as usize,
+                    post_shift: log.saturating_sub(1) as usize,
+                }
+            }
+        }
+    };
+}
+
+impl_divisor_unsigned! { u8 }
+impl_divisor_unsigned! { u16 }
+impl_divisor_unsigned! { u32 }
+impl_divisor_unsigned! { u64 }
+
+macro_rules! impl_divisor_signed {
+    ($elem:ident) => {
+        impl DivisorElem for $elem {
+            const SIGNED: bool = true;
+            const BITS: u32 = $elem::BITS;
+
+            fn magic(divisor: $elem) -> Magic<$elem> {
+                assert!(divisor != 0, "attempt to divide by zero");
+
+                let d = divisor.unsigned_abs() as u128;
+                let log = (128 - (d - 1).leading_zeros()).max(1);
+                // The multiplier is 2^N too large to fit; the excess is added back in `divide`
+                let multiplier = 1 + (1u128 << ($elem::BITS + log - 1)) / d;
+
+                Magic {
+                    multiplier: multiplier as $elem,
+                    sign: if divisor < 0 { -1 } else { 0 },
+                    pre_shift: 0,
+                    post_shift: (log - 1) as usize,
+                }
+            }
+        }
+    };
+}
+
+impl_divisor_signed! { i8 }
+impl_divisor_signed! { i16 }
+impl_divisor_signed! { i32 }
+impl_divisor_signed! { i64 }
+
+macro_rules! impl_gather_index {
+    ($elem:ident) => {
+        impl GatherIndex for $elem {
+            #[inline(always)]
+            fn to_usize(self) -> usize {
+                usize::try_from(self).unwrap_or(usize::MAX)
+            }
+        }
+    };
+}
+
+impl_gather_index! { u8 }
+impl_gather_index! { u16 }
+impl_gather_index! { u32 }
+impl_gather_index! { u64 }
+impl_gather_index! { i8 }
+impl_gather_index! { i16 }
+impl_gather_index! { i32 }
+impl_gather_index! { i64 }
@@ -0,0 +1,334 @@
+//! Loops over slices a vector at a time.
+//!
+//! Each function splits one slice at the boundaries of aligned vectors with
+//! [`Array::align_slice`] or [`Array::align_mut_slice`]: `output` for [`map`] and [`map2`], and
+//! `data` for the others. Any other slice is read at the same offsets and need not be aligned. The
+//! unaligned head and tail are handled as partial vectors. Lanes of a partial vector that lie past
+//! the end of the slice are filled with zero (or `false`) unless stated otherwise, and the results
+//! in those lanes are discarded.
+//!
+//! [`chunks`] and [`chunks_mut`] instead iterate over groups of `N` vectors, which lets a loop keep
+//! several independent accumulators, and leave the remaining vectors and elements to the caller.
+
+use core::array;
+use core::marker::PhantomData;
+use core::slice::{ChunksExact, ChunksExactMut};
+
+use crate::simd::{Array, Simd};
+
+/// Sets `output` to `f` applied to `input`.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+#[inline(always)]
+pub fn map<S, F>(input: &[S::Elem], output: &mut [S::Elem], mut f: F)
+where
+    S: Array,
+    F: FnMut(S) -> S,
+{
+    assert!(input.len() == output.len(), "slices differ in length");
+
+    for_each_piece_mut::<S>(output, |offset, out| {
+        let x = load(&input[offset..offset + out.len()]);
+        store(f(x), out);
+    });
+}
+
+/// Sets `output` to `f` applied to `a` and `b`.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+#[inline(always)]
+pub fn map2<S, F>(a: &[S::Elem], b: &[S::Elem], output: &mut [S::Elem], mut f: F)
+where
+    S: Array,
+    F: FnMut(S, S) -> S,
+{
+    assert!(
+        a.len() == output.len() && b.len() == output.len(),
+        "slices differ in length"
+    );
+
+    for_each_piece_mut::<S>(output, |offset, out| {
+        let range = offset..offset + out.len();
+        store(f(load(&a[range.clone()]), load(&b[range])), out);
+    });
+}
+
+/// Sets `data` to `f` applied to `data` and `other`.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+#[inline(always)]
+pub fn zip_map_in_place<S, F>(data: &mut [S::Elem], other: &[S::Elem], mut f: F)
+where
+    S: Array,
+    F: FnMut(S, S) -> S,
+{
+    assert!(data.len() == other.len(), "slices differ in length");
+
+    for_each_piece_mut::<S>(data, |offset, piece| {
+        let y = load(&other[offset..offset + piece.len()]);
+        store(f(load(piece), y), piece);
+    });
+}
+
+/// Calls `f` on each vector of `data`, writing back any changes.
+#[inline(always)]
+pub fn for_each_chunk<S, F>(data: &mut [S::Elem], mut f: F)
+where
+    S: Array,
+    F: FnMut(&mut S),
+{
+    for_each_piece_mut::<S>(data, |_, piece| {
+        let mut x = load(piece);
+        f(&mut x);
+        store(x, piece);
+    });
+}
+
+/// Folds each vector of `data` into an accumulator. Partial vectors are filled with `fill`.
+#[inline(always)]
+pub fn fold<S, T, F>(data: &[S::Elem], fill: S::Elem, init: T, mut f: F) -> T
+where
+    S: Array,
+    F: FnMut(T, S) -> T,
+{
+    let (head, body, tail) = S::align_slice(data);
+    let partial = |piece: &[S::Elem]| S::load_or(piece, S::new(fill));
+
+    let acc = head
+        .chunks(S::LANES)
+        .fold(init, |acc, x| f(acc, partial(x)));
+    let acc = body.iter().fold(acc, |acc, x| f(acc, *x));
+    tail.chunks(S::LANES).fold(acc, |acc, x| f(acc, partial(x)))
+}
+
+/// Combines the vectors of `data` lane-wise with `f`, starting from a vector of `identity`.
+/// Partial vectors are also filled with `identity`.
+#[inline(always)]
+pub fn reduce<S, F>(data: &[S::Elem], identity: S::Elem, f: F) -> S
+where
+    S: Array,
+    F: FnMut(S, S) -> S,
+{
+    fold(data, identity, S::new(identity), f)
+}
+
+/// Returns an iterator over groups of `N` vectors loaded from `data`.
+#[inline]
+pub fn chunks<S: Simd, const N: usize>(data: &[S::Elem]) -> Chunks<'_, S, N> {
+    const { assert!(N > 0, "group size must be nonzero") };
+
+    Chunks {
+        chunks: data.chunks_exact(N * S::LANES),
+        _marker: PhantomData,
+    }
+}
+
+/// An iterator over groups of `N` vectors in a slice, created by [`chunks`].
+pub struct Chunks<'a, S: Simd, const N: usize> {
+    chunks: ChunksExact<'a, S::Elem>,
+    _marker: PhantomData<S>,
+}
+
+impl<'a, S: Simd, const N: usize> Chunks<'a, S, N> {
+    /// Returns an iterator over the vectors left over after the last group, which are fewer than
+    /// `N`.
+    #[inline]
+    pub fn remainder(&self) -> Remainder<'a, S> {
+        Remainder {
+            chunks: self.chunks.remainder().chunks_exact(S::LANES),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S: Simd, const N: usize> Iterator for Chunks<'_, S, N> {
+    type Item = [S; N];
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        Some(array::from_fn(|i| {
+            S::from_slice(&chunk[i * S::LANES..(i + 1) * S::LANES])
+        }))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<S: Simd, const N: usize> ExactSizeIterator for Chunks<'_, S, N> {}
+
+/// An iterator over the vectors left over by [`Chunks`].
+pub struct Remainder<'a, S: Simd> {
+    chunks: ChunksExact<'a, S::Elem>,
+    _marker: PhantomData<S>,
+}
+
+impl<'a, S: Simd> Remainder<'a, S> {
+    /// Returns the elements left over after the last vector, which are fewer than `LANES`.
+    #[inline]
+    pub fn remainder(&self) -> &'a [S::Elem] {
+        self.chunks.remainder()
+    }
+}
+
+impl<S: Simd> Iterator for Remainder<'_, S> {
+    type Item = S;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(S::from_slice)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<S: Simd> ExactSizeIterator for Remainder<'_, S> {}
+
+/// Returns an iterator over groups of `N` vectors in `data`, which can be loaded and stored
+/// through the yielded [`ChunkMut`]s.
+#[inline]
+pub fn chunks_mut<S: Simd, const N: usize>(data: &mut [S::Elem]) -> ChunksMut<'_, S, N> {
+    const { assert!(N > 0, "group size must be nonzero") };
+
+    ChunksMut {
+        chunks: data.chunks_exact_mut(N * S::LANES),
+        _marker: PhantomData,
+    }
+}
+
+/// An iterator over groups of `N` vectors in a mutable slice, created by [`chunks_mut`].
+pub struct ChunksMut<'a, S: Simd, const N: usize> {
+    chunks: ChunksExactMut<'a, S::Elem>,
+    _marker: PhantomData<S>,
+}
+
+impl<'a, S: Simd, const N: usize> ChunksMut<'a, S, N> {
+    /// Returns an iterator over the vectors left over after the last group, which are fewer than
+    /// `N`.
+    #[inline]
+    pub fn into_remainder(self) -> RemainderMut<'a, S> {
+        RemainderMut {
+            chunks: self.chunks.into_remainder().chunks_exact_mut(S::LANES),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, S: Simd, const N: usize> Iterator for ChunksMut<'a, S, N> {
+    type Item = ChunkMut<'a, S, N>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(|data| ChunkMut {
+            data,
+            _marker: PhantomData,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<S: Simd, const N: usize> ExactSizeIterator for ChunksMut<'_, S, N> {}
+
+/// An iterator over the vectors left over by [`ChunksMut`], yielded one at a time.
+pub struct RemainderMut<'a, S: Simd> {
+    chunks: ChunksExactMut<'a, S::Elem>,
+    _marker: PhantomData<S>,
+}
+
+impl<'a, S: Simd> RemainderMut<'a, S> {
+    /// Returns the elements left over after the last vector, which are fewer than `LANES`.
+    #[inline]
+    pub fn into_remainder(self) -> &'a mut [S::Elem] {
+        self.chunks.into_remainder()
+    }
+}
+
+impl<'a, S: Simd> Iterator for RemainderMut<'a, S> {
+    type Item = ChunkMut<'a, S, 1>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(|data| ChunkMut {
+            data,
+            _marker: PhantomData,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<S: Simd> ExactSizeIterator for RemainderMut<'_, S> {}
+
+/// A group of `N` vectors in a mutable slice.
+pub struct ChunkMut<'a, S: Simd, const N: usize> {
+    data: &'a mut [S::Elem],
+    _marker: PhantomData<S>,
+}
+
+impl<S: Simd, const N: usize> ChunkMut<'_, S, N> {
+    #[inline(always)]
+    pub fn load(&self) -> [S; N] {
+        array::from_fn(|i| S::from_slice(&self.data[i * S::LANES..(i + 1) * S::LANES]))
+    }
+
+    #[inline(always)]
+    pub fn store(&mut self, vectors: [S; N]) {
+        for (i, vector) in vectors.iter().enumerate() {
+            vector.write_to_slice(&mut self.data[i * S::LANES..(i + 1) * S::LANES]);
+        }
+    }
+}
+
+/// Splits `data` into aligned vectors and the partial pieces before and after them, and calls `f`
+/// with the offset and contents of each piece in order.
+#[inline(always)]
+fn for_each_piece_mut<S: Array>(data: &mut [S::Elem], mut f: impl FnMut(usize, &mut [S::Elem])) {
+    let (head, body, tail) = S::align_mut_slice(data);
+
+    let mut offset = 0;
+    let mut call = |piece: &mut [S::Elem]| {
+        f(offset, piece);
+        offset += piece.len();
+    };
+
+    head.chunks_mut(S::LANES).for_each(&mut call);
+    body.iter_mut().for_each(|x| call(x.as_mut_slice()));
+    tail.chunks_mut(S::LANES).for_each(&mut call);
+}
+
+#[inline(always)]
+fn load<S: Array>(piece: &[S::Elem]) -> S {
+    if piece.len() == S::LANES {
+        S::from_slice(piece)
+    } else {
+        S::load_partial(piece)
+    }
+}
+
+#[inline(always)]
+fn store<S: Array>(x: S, piece: &mut [S::Elem]) {
+    if piece.len() == S::LANES {
+        x.write_to_slice(piece);
+    } else {
+        x.store_partial(piece);
+    }
+}